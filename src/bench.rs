@@ -0,0 +1,278 @@
+use std::fmt::Write as _;
+use std::thread;
+use std::time::{ Duration, Instant };
+
+use crate::input::InputDistribution;
+use crate::sorting_algorithms::recorder::{ OperationCounts, SortRecorder };
+use crate::sorting_algorithms::{ run_sort, SortingAlgorithmKind };
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum BenchOutputFormat {
+    #[default]
+    Table,
+    Csv,
+    Json,
+}
+
+impl BenchOutputFormat {
+    pub fn from_cli_name(name: &str) -> Option<BenchOutputFormat> {
+        match name.to_ascii_lowercase().as_str() {
+            "table" => Some(BenchOutputFormat::Table),
+            "csv" => Some(BenchOutputFormat::Csv),
+            "json" => Some(BenchOutputFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+// runs a bench, tournament or check may make per algorithm and size; each is a full sort of
+// a fresh input, and a tournament keeps the counts of every one
+pub const SEEDS_RANGE: std::ops::RangeInclusive<usize> = 1..=1000;
+
+#[derive(Clone, Debug)]
+pub struct BenchOptions {
+    pub algorithms: Vec<SortingAlgorithmKind>,
+    pub sizes: Vec<usize>,
+    pub input_distribution: InputDistribution,
+    // runs per algorithm and size, seeded first_seed, first_seed + 1, ...
+    pub seed_count: usize,
+    pub first_seed: u64,
+    pub output_format: BenchOutputFormat,
+}
+
+// counts and wall time of one run, without recording frames
+#[derive(Clone, Copy, Debug)]
+pub struct BenchMeasurement {
+    pub operation_counts: OperationCounts,
+    pub duration: Duration,
+}
+
+pub fn measure_sort(algorithm_kind: SortingAlgorithmKind, base_values: &[u32]) -> BenchMeasurement {
+    let mut recorder = SortRecorder::counting_only();
+    let start_time = Instant::now();
+
+    run_sort(algorithm_kind, base_values, &mut recorder);
+
+    BenchMeasurement {
+        operation_counts: recorder.operation_counts(),
+        duration: start_time.elapsed(),
+    }
+}
+
+// one algorithm at one size, averaged over every seed
+#[derive(Clone, Debug)]
+pub struct BenchRow {
+    pub algorithm_kind: SortingAlgorithmKind,
+    pub number_of_values: usize,
+    pub input_distribution: InputDistribution,
+    pub seed_count: usize,
+    pub mean_comparisons: f64,
+    pub mean_swaps: f64,
+    pub mean_writes: f64,
+    pub mean_milliseconds: f64,
+    pub minimum_milliseconds: f64,
+}
+
+// quick sort recurses once per element on sorted and reversed inputs
+const BENCH_THREAD_STACK_SIZE: usize = 512 * 1024 * 1024;
+
+// counting runs go through here so deep recursion on large adversarial inputs has room
+pub fn spawn_bench_thread<T: Send + 'static>(
+    job: impl FnOnce() -> T + Send + 'static
+) -> thread::JoinHandle<T> {
+    thread::Builder::new()
+        .name("bench".to_owned())
+        .stack_size(BENCH_THREAD_STACK_SIZE)
+        .spawn(job)
+        .expect("failed to spawn bench thread")
+}
+
+pub fn run_bench(options: &BenchOptions) -> Vec<BenchRow> {
+    let options = options.clone();
+    spawn_bench_thread(move || collect_bench_rows(&options))
+        .join()
+        .expect("bench thread panicked")
+}
+
+fn collect_bench_rows(options: &BenchOptions) -> Vec<BenchRow> {
+    let seed_count = options.seed_count.max(1);
+    let mut bench_rows = Vec::with_capacity(options.sizes.len() * options.algorithms.len());
+
+    for number_of_values in options.sizes.iter().copied() {
+        // every algorithm sorts the same inputs
+        let inputs: Vec<Vec<u32>> = (0..seed_count)
+            .map(|seed_offset| {
+                options.input_distribution.generate(
+                    number_of_values,
+                    Some(options.first_seed.wrapping_add(seed_offset as u64))
+                )
+            })
+            .collect();
+
+        for algorithm_kind in options.algorithms.iter().copied() {
+            let measurements: Vec<BenchMeasurement> = inputs
+                .iter()
+                .map(|base_values| measure_sort(algorithm_kind, base_values))
+                .collect();
+
+            let run_count = measurements.len() as f64;
+            let mean_of = |counter: fn(&BenchMeasurement) -> f64| {
+                measurements.iter().map(counter).sum::<f64>() / run_count
+            };
+
+            bench_rows.push(BenchRow {
+                algorithm_kind,
+                number_of_values,
+                input_distribution: options.input_distribution,
+                seed_count,
+                mean_comparisons: mean_of(|measurement| {
+                    measurement.operation_counts.comparisons as f64
+                }),
+                mean_swaps: mean_of(|measurement| measurement.operation_counts.swaps as f64),
+                mean_writes: mean_of(|measurement| measurement.operation_counts.writes as f64),
+                mean_milliseconds: mean_of(|measurement| {
+                    measurement.duration.as_secs_f64() * 1000.0
+                }),
+                minimum_milliseconds: measurements
+                    .iter()
+                    .map(|measurement| measurement.duration.as_secs_f64() * 1000.0)
+                    .fold(f64::INFINITY, f64::min),
+            });
+        }
+    }
+
+    bench_rows
+}
+
+pub fn format_bench_rows(bench_rows: &[BenchRow], output_format: BenchOutputFormat) -> String {
+    match output_format {
+        BenchOutputFormat::Table => format_table(bench_rows),
+        BenchOutputFormat::Csv => format_csv(bench_rows),
+        BenchOutputFormat::Json => format_json(bench_rows),
+    }
+}
+
+const TABLE_HEADERS: [&str; 9] = [
+    "algorithm",
+    "n",
+    "input",
+    "seeds",
+    "comparisons",
+    "swaps",
+    "writes",
+    "mean ms",
+    "min ms",
+];
+
+fn format_table(bench_rows: &[BenchRow]) -> String {
+    let cells: Vec<[String; 9]> = bench_rows
+        .iter()
+        .map(|bench_row| {
+            [
+                bench_row.algorithm_kind.display_name().to_owned(),
+                bench_row.number_of_values.to_string(),
+                bench_row.input_distribution.cli_name().to_owned(),
+                bench_row.seed_count.to_string(),
+                format_count(bench_row.mean_comparisons),
+                format_count(bench_row.mean_swaps),
+                format_count(bench_row.mean_writes),
+                format!("{:.3}", bench_row.mean_milliseconds),
+                format!("{:.3}", bench_row.minimum_milliseconds),
+            ]
+        })
+        .collect();
+
+    let mut column_widths = TABLE_HEADERS.map(str::len);
+    for row_cells in &cells {
+        for (column_index, cell) in row_cells.iter().enumerate() {
+            column_widths[column_index] = column_widths[column_index].max(cell.len());
+        }
+    }
+
+    let mut table = String::new();
+    let header_cells = TABLE_HEADERS.map(str::to_owned);
+    write_table_line(&mut table, &header_cells, &column_widths);
+
+    let separator_cells = column_widths.map(|column_width| "-".repeat(column_width));
+    write_table_line(&mut table, &separator_cells, &column_widths);
+
+    for row_cells in &cells {
+        write_table_line(&mut table, row_cells, &column_widths);
+    }
+    table
+}
+
+fn write_table_line(table: &mut String, cells: &[String; 9], column_widths: &[usize; 9]) {
+    for (column_index, cell) in cells.iter().enumerate() {
+        if column_index > 0 {
+            table.push_str("  ");
+        }
+
+        // text columns left aligned, numbers right aligned
+        let column_width = column_widths[column_index];
+        if column_index == 0 || column_index == 2 {
+            let _ = write!(table, "{:<column_width$}", cell);
+        } else {
+            let _ = write!(table, "{:>column_width$}", cell);
+        }
+    }
+    table.push('\n');
+}
+
+// whole numbers stay whole; averages over seeds keep one decimal
+fn format_count(count: f64) -> String {
+    if count.fract() == 0.0 {
+        format!("{}", count as u64)
+    } else {
+        format!("{:.1}", count)
+    }
+}
+
+fn format_csv(bench_rows: &[BenchRow]) -> String {
+    let mut csv = String::from(
+        "algorithm,n,input,seeds,mean_comparisons,mean_swaps,mean_writes,mean_ms,min_ms\n"
+    );
+
+    for bench_row in bench_rows {
+        let _ = writeln!(
+            csv,
+            "{},{},{},{},{},{},{},{:.6},{:.6}",
+            bench_row.algorithm_kind.cli_name(),
+            bench_row.number_of_values,
+            bench_row.input_distribution.cli_name(),
+            bench_row.seed_count,
+            bench_row.mean_comparisons,
+            bench_row.mean_swaps,
+            bench_row.mean_writes,
+            bench_row.mean_milliseconds,
+            bench_row.minimum_milliseconds
+        );
+    }
+    csv
+}
+
+fn format_json(bench_rows: &[BenchRow]) -> String {
+    let mut json = String::from("[\n");
+
+    for (row_index, bench_row) in bench_rows.iter().enumerate() {
+        let _ = write!(
+            json,
+            "  {{\"algorithm\": \"{}\", \"n\": {}, \"input\": \"{}\", \"seeds\": {}, \
+             \"mean_comparisons\": {}, \"mean_swaps\": {}, \"mean_writes\": {}, \
+             \"mean_ms\": {:.6}, \"min_ms\": {:.6}}}",
+            bench_row.algorithm_kind.cli_name(),
+            bench_row.number_of_values,
+            bench_row.input_distribution.cli_name(),
+            bench_row.seed_count,
+            bench_row.mean_comparisons,
+            bench_row.mean_swaps,
+            bench_row.mean_writes,
+            bench_row.mean_milliseconds,
+            bench_row.minimum_milliseconds
+        );
+        json.push_str(if row_index + 1 < bench_rows.len() { ",\n" } else { "\n" });
+    }
+
+    json.push_str("]\n");
+    json
+}
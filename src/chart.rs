@@ -0,0 +1,410 @@
+use crate::palette::{ Palette, Rgba8 };
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ChartStyle {
+    #[default]
+    Bars,
+    Pyramid,
+    Dots,
+    ColorStrip,
+    ColorWheel,
+    Spiral,
+    DisparityDots,
+}
+
+impl ChartStyle {
+    pub const ALL: [ChartStyle; 7] = [
+        ChartStyle::Bars,
+        ChartStyle::Pyramid,
+        ChartStyle::Dots,
+        ChartStyle::ColorStrip,
+        ChartStyle::ColorWheel,
+        ChartStyle::Spiral,
+        ChartStyle::DisparityDots,
+    ];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ChartStyle::Bars => "Bars",
+            ChartStyle::Pyramid => "Pyramid",
+            ChartStyle::Dots => "Dot plot",
+            ChartStyle::ColorStrip => "Color strip",
+            ChartStyle::ColorWheel => "Color wheel",
+            ChartStyle::Spiral => "Spiral",
+            ChartStyle::DisparityDots => "Disparity dots",
+        }
+    }
+
+    pub fn cli_name(&self) -> &'static str {
+        match self {
+            ChartStyle::Bars => "bars",
+            ChartStyle::Pyramid => "pyramid",
+            ChartStyle::Dots => "dots",
+            ChartStyle::ColorStrip => "strip",
+            ChartStyle::ColorWheel => "wheel",
+            ChartStyle::Spiral => "spiral",
+            ChartStyle::DisparityDots => "disparity",
+        }
+    }
+
+    pub fn from_cli_name(name: &str) -> Option<ChartStyle> {
+        ChartStyle::ALL
+            .into_iter()
+            .find(|chart_style| chart_style.cli_name().eq_ignore_ascii_case(name))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChartRect {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
+
+impl ChartRect {
+    pub fn width(&self) -> f32 {
+        self.right - self.left
+    }
+
+    pub fn height(&self) -> f32 {
+        self.bottom - self.top
+    }
+
+    pub fn center(&self) -> [f32; 2] {
+        [(self.left + self.right) * 0.5, (self.top + self.bottom) * 0.5]
+    }
+
+    pub fn shrink(&self, amount: f32) -> ChartRect {
+        ChartRect {
+            left: self.left + amount,
+            top: self.top + amount,
+            right: self.right - amount,
+            bottom: self.bottom - amount,
+        }
+    }
+}
+
+// backend-independent drawing primitives; the ui, the rasterizer and the svg writer all consume these
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChartShape {
+    Rect {
+        rect: ChartRect,
+        corner_radius: f32,
+        color: Rgba8,
+    },
+    Circle {
+        center: [f32; 2],
+        radius: f32,
+        color: Rgba8,
+    },
+    Triangle {
+        points: [[f32; 2]; 3],
+        color: Rgba8,
+    },
+}
+
+impl ChartShape {
+    pub fn color_mut(&mut self) -> &mut Rgba8 {
+        match self {
+            ChartShape::Rect { color, .. } => color,
+            ChartShape::Circle { color, .. } => color,
+            ChartShape::Triangle { color, .. } => color,
+        }
+    }
+}
+
+// shapes for one chart, background excluded; highlighted elements use the palette's highlight color
+pub fn chart_shapes(
+    chart_style: ChartStyle,
+    chart_rect: ChartRect,
+    values: &[u32],
+    highlighted_indices: &[usize],
+    palette: &Palette,
+    is_finished: bool
+) -> Vec<ChartShape> {
+    if values.is_empty() {
+        return Vec::new();
+    }
+
+    // all zeros, e.g. from a trace, still gives a shape per element, each of zero height
+    let maximum_value = values.iter().copied().max().unwrap_or(1).max(1) as f32;
+
+    let chart = ChartInput {
+        chart_rect,
+        values,
+        maximum_value,
+        palette,
+        is_finished,
+    };
+
+    let mut shapes = match chart_style {
+        ChartStyle::Bars => bar_shapes(&chart, false),
+        ChartStyle::Pyramid => bar_shapes(&chart, true),
+        ChartStyle::Dots => dot_plot_shapes(&chart),
+        ChartStyle::ColorStrip => color_strip_shapes(&chart),
+        ChartStyle::ColorWheel => color_wheel_shapes(&chart),
+        ChartStyle::Spiral => spiral_shapes(&chart),
+        ChartStyle::DisparityDots => disparity_dot_shapes(&chart),
+    };
+
+    // every style emits exactly one shape per element, in element order
+    let highlight_color = palette.highlight_color();
+    for highlighted_index in highlighted_indices.iter().copied() {
+        if let Some(shape) = shapes.get_mut(highlighted_index) {
+            *shape.color_mut() = highlight_color;
+        }
+    }
+
+    shapes
+}
+
+// positions whose value differs between two frames of the same replay
+pub fn changed_indices(previous_values: &[u32], current_values: &[u32]) -> Vec<usize> {
+    previous_values
+        .iter()
+        .zip(current_values.iter())
+        .enumerate()
+        .filter(|(_, (previous_value, current_value))| previous_value != current_value)
+        .map(|(index, _)| index)
+        .collect()
+}
+
+// final (sorted) position of every element, used by the disparity view
+pub fn final_positions(values: &[u32]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    // stable so equal values keep their relative order
+    order.sort_by_key(|&index| values[index]);
+
+    let mut positions = vec![0; values.len()];
+    for (final_index, original_index) in order.into_iter().enumerate() {
+        positions[original_index] = final_index;
+    }
+    positions
+}
+
+struct ChartInput<'a> {
+    chart_rect: ChartRect,
+    values: &'a [u32],
+    maximum_value: f32,
+    palette: &'a Palette,
+    is_finished: bool,
+}
+
+impl ChartInput<'_> {
+    fn normalized(&self, value: u32) -> f32 {
+        (value as f32) / self.maximum_value
+    }
+
+    fn color(&self, normalized_value: f32) -> Rgba8 {
+        self.palette.bar_color(normalized_value, self.is_finished)
+    }
+}
+
+fn bar_shapes(chart: &ChartInput, is_mirrored: bool) -> Vec<ChartShape> {
+    let chart_rect = chart.chart_rect;
+    let bar_count = chart.values.len();
+    let bar_width = chart_rect.width() / (bar_count as f32);
+    let bar_spacing_factor = 0.9;
+
+    let mut shapes = Vec::with_capacity(bar_count);
+    for (value_index, value) in chart.values.iter().enumerate() {
+        let normalized_height = chart.normalized(*value);
+        let bar_height = chart_rect.height() * normalized_height.max(0.0);
+
+        let left_position = chart_rect.left + bar_width * (value_index as f32);
+        let right_position = left_position + bar_width * bar_spacing_factor;
+
+        // pyramid bars grow out from the horizontal center line
+        let (top_position, bottom_position) = if is_mirrored {
+            let center_position = chart_rect.center()[1];
+            (center_position - bar_height * 0.5, center_position + bar_height * 0.5)
+        } else {
+            (chart_rect.bottom - bar_height, chart_rect.bottom)
+        };
+
+        shapes.push(ChartShape::Rect {
+            rect: ChartRect {
+                left: left_position,
+                top: top_position,
+                right: right_position,
+                bottom: bottom_position,
+            },
+            corner_radius: 2.0,
+            color: chart.color(normalized_height),
+        });
+    }
+    shapes
+}
+
+fn dot_plot_shapes(chart: &ChartInput) -> Vec<ChartShape> {
+    let chart_rect = chart.chart_rect;
+    let slot_width = chart_rect.width() / (chart.values.len() as f32);
+    let dot_radius = (slot_width * 0.45).clamp(1.0, 4.0);
+
+    // keep dots fully inside the chart
+    let plot_rect = chart_rect.shrink(dot_radius);
+
+    chart.values
+        .iter()
+        .enumerate()
+        .map(|(value_index, value)| {
+            let normalized_height = chart.normalized(*value);
+
+            let x_position = chart_rect.left + slot_width * ((value_index as f32) + 0.5);
+            let y_position = plot_rect.bottom - plot_rect.height() * normalized_height;
+
+            ChartShape::Circle {
+                center: [x_position, y_position],
+                radius: dot_radius,
+                color: chart.color(normalized_height),
+            }
+        })
+        .collect()
+}
+
+fn color_strip_shapes(chart: &ChartInput) -> Vec<ChartShape> {
+    let chart_rect = chart.chart_rect;
+    let strip_width = chart_rect.width() / (chart.values.len() as f32);
+
+    chart.values
+        .iter()
+        .enumerate()
+        .map(|(value_index, value)| {
+            let left_position = chart_rect.left + strip_width * (value_index as f32);
+            // slight overlap hides hairline seams between neighbours
+            let right_position = (left_position + strip_width + 0.5).min(chart_rect.right);
+
+            ChartShape::Rect {
+                rect: ChartRect {
+                    left: left_position,
+                    top: chart_rect.top,
+                    right: right_position,
+                    bottom: chart_rect.bottom,
+                },
+                corner_radius: 0.0,
+                color: chart.color(chart.normalized(*value)),
+            }
+        })
+        .collect()
+}
+
+fn color_wheel_shapes(chart: &ChartInput) -> Vec<ChartShape> {
+    let center = chart.chart_rect.center();
+    let radius = polar_radius(chart.chart_rect);
+    let element_count = chart.values.len();
+
+    // one flat-colored wedge per element
+    chart.values
+        .iter()
+        .enumerate()
+        .map(|(value_index, value)| {
+            let start_angle = polar_angle(value_index, element_count);
+            let end_angle = polar_angle(value_index + 1, element_count);
+
+            ChartShape::Triangle {
+                points: [
+                    center,
+                    polar_point(center, radius, start_angle),
+                    polar_point(center, radius, end_angle),
+                ],
+                color: chart.color(chart.normalized(*value)),
+            }
+        })
+        .collect()
+}
+
+fn spiral_shapes(chart: &ChartInput) -> Vec<ChartShape> {
+    let center = chart.chart_rect.center();
+    let radius = polar_radius(chart.chart_rect);
+    let element_count = chart.values.len();
+    let dot_radius = polar_dot_radius(radius, element_count);
+
+    // angle from position, distance from value: sorted data forms a spiral
+    chart.values
+        .iter()
+        .enumerate()
+        .map(|(value_index, value)| {
+            let normalized_value = chart.normalized(*value);
+            let angle = polar_angle(value_index, element_count);
+
+            ChartShape::Circle {
+                center: polar_point(center, radius * normalized_value, angle),
+                radius: dot_radius,
+                color: chart.color(normalized_value),
+            }
+        })
+        .collect()
+}
+
+fn disparity_dot_shapes(chart: &ChartInput) -> Vec<ChartShape> {
+    let center = chart.chart_rect.center();
+    let radius = polar_radius(chart.chart_rect);
+    let element_count = chart.values.len();
+    let dot_radius = polar_dot_radius(radius, element_count);
+
+    // elements in their final position sit on the rim, misplaced ones move inwards
+    let final_positions = final_positions(chart.values);
+    chart.values
+        .iter()
+        .enumerate()
+        .map(|(value_index, value)| {
+            let normalized_value = chart.normalized(*value);
+            let disparity = value_index.abs_diff(final_positions[value_index]) as f32;
+            let normalized_disparity = disparity / (element_count as f32);
+
+            let angle = polar_angle(value_index, element_count);
+
+            ChartShape::Circle {
+                center: polar_point(center, radius * (1.0 - normalized_disparity), angle),
+                radius: dot_radius,
+                color: chart.color(normalized_value),
+            }
+        })
+        .collect()
+}
+
+// polar chart helpers
+fn polar_radius(chart_rect: ChartRect) -> f32 {
+    chart_rect.width().min(chart_rect.height()) * 0.5 * 0.95
+}
+
+fn polar_angle(index: usize, element_count: usize) -> f32 {
+    // start at twelve o'clock, run clockwise
+    let fraction = (index as f32) / (element_count.max(1) as f32);
+    fraction * std::f32::consts::TAU - std::f32::consts::FRAC_PI_2
+}
+
+fn polar_point(center: [f32; 2], distance: f32, angle: f32) -> [f32; 2] {
+    [center[0] + angle.cos() * distance, center[1] + angle.sin() * distance]
+}
+
+fn polar_dot_radius(radius: f32, element_count: usize) -> f32 {
+    let circumference_per_element = (std::f32::consts::TAU * radius) / (element_count.max(1) as f32);
+    (circumference_per_element * 0.45).clamp(1.0, 4.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_zero_values_give_one_empty_shape_per_element() {
+        let chart_rect = ChartRect { left: 0.0, top: 0.0, right: 100.0, bottom: 50.0 };
+        let palette = Palette::default();
+
+        for chart_style in ChartStyle::ALL {
+            let shapes = chart_shapes(chart_style, chart_rect, &[0; 5], &[3], &palette, false);
+            assert_eq!(shapes.len(), 5, "{}", chart_style.display_name());
+            assert_eq!(*shapes[3].clone().color_mut(), palette.highlight_color());
+        }
+
+        let bars = chart_shapes(ChartStyle::Bars, chart_rect, &[0; 5], &[], &palette, false);
+        for bar in bars {
+            let ChartShape::Rect { rect, .. } = bar else {
+                panic!("bars are rectangles");
+            };
+            assert_eq!(rect.height(), 0.0);
+        }
+    }
+}
@@ -0,0 +1,819 @@
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+
+use sortvis::bench::{ BenchOptions, BenchOutputFormat, SEEDS_RANGE };
+use sortvis::chart::ChartStyle;
+use sortvis::complexity::{ CounterKind, SweepOptions, SWEEP_SIZE_RANGE };
+use sortvis::engine::{ FRAMES_PER_SECOND_RANGE, NUMBER_OF_VALUES_RANGE };
+use sortvis::export::midi::{ STEPS_PER_BEAT_RANGE, TEMPO_RANGE_BPM };
+use sortvis::export::{ ExportFormat, ExportOptions, EXPORT_SIZE_RANGE_PX };
+use sortvis::input::InputDistribution;
+use sortvis::palette::Palette;
+use sortvis::self_check::{ SelfCheckOptions, SELF_CHECK_SIZE_RANGE };
+use sortvis::sonification::{ PitchSource, Scale, SonificationSettings, Waveform };
+use sortvis::sorting_algorithms::instance::{ algorithm_names, AlgorithmInstance };
+use sortvis::sorting_algorithms::SortingAlgorithmKind;
+use sortvis::tournament::{ TournamentMetric, TournamentOptions };
+
+/// The help text, with the ranges read from the constants that enforce them.
+pub fn usage() -> String {
+    format!(
+        "\
+Usage:
+  sortvis [window options]     launch the visualizer window
+  sortvis export [options]     render a run to an animation without the window
+  sortvis bench [options]      count operations and time the algorithms, no window
+  sortvis sweep [options]      fit operation counts over a geometric range of sizes
+  sortvis tournament [options] run every algorithm on many seeded inputs and rank them
+  sortvis check [options]      check every recorded step of the algorithms against their input
+  sortvis algorithms [--markdown]
+                               list the algorithms with their complexity and stability
+  sortvis help                 show this message
+
+Window options (settings given here last for this session and are not saved):
+  --algos <names>       panels to show, with the same syntax as for export
+  --size <n>            values per algorithm, {values_min} to {values_max}
+  --fps <n>             playback speed, {fps_min} to {fps_max}
+  --input <shape>       random, sorted, reversed, nearly-sorted, few-unique
+  --seed <n>            start every run from the same input
+  --scenario <path>     open a scenario file and play its first scenario
+  --replay <path>       open a .sortvis file and play it
+  --autostart           start a run with the settings right away
+  --fullscreen          open the window in fullscreen
+
+Export options:
+  --algos <names>       comma separated algorithms, or 'all' (default: bubble,quick);
+                        add ':lomuto' or ':hoare' to quick and ':<input shape>' to any
+                        algorithm, e.g. quick:lomuto,quick:hoare or merge:reversed
+  --size <n>            values per algorithm, {values_min} to {values_max} (default: 128)
+  --seed <n>            shuffle seed for a reproducible input
+  --input <shape>       random, sorted, reversed, nearly-sorted, few-unique (default: random)
+  --style <style>       bars, pyramid, dots, strip, wheel, spiral, disparity (default: bars)
+  --format <format>     gif, apng, png (numbered frames), svg or midi; inferred from --output
+  --output <path>       output file, or directory for png frames (required)
+  --every <k>           render every k-th step (default: 1)
+  --step <n>            step drawn by an svg snapshot, clamped to the last step (default: 0)
+  --fps <n>             playback speed of the animation, {fps_min} to {fps_max} (default: 30)
+  --width <px>          frame width, {frame_size_min} to {frame_size_max} (default: 960)
+  --height <px>         frame height, {frame_size_min} to {frame_size_max} (default: 540)
+  --hue <0-360>         palette base hue in degrees (default: 210)
+  --saturation <0.2-1>  palette saturation (default: 0.85)
+  --brightness <0.3-1>  palette brightness (default: 0.9)
+  --gradient <0-0.4>    palette gradient strength (default: 0.28)
+  --light               light theme background
+  --audio <path>        also write the sound of the animation to a wav file
+  --pitch <source>      notes follow the element's index or value (default: index)
+  --scale <scale>       major, minor, pentatonic, chromatic or glissando (default: major)
+  --sound <wave>        sine, triangle, square or saw for swaps and writes (default: sine)
+  --compare-sound <wave>
+                        wave for comparisons (default: triangle)
+  --no-compares         do not sound comparisons
+  --note-ms <ms>        note length, {note_ms_min} to {note_ms_max} (default: 45)
+  --tempo <bpm>         tempo of a midi file, {tempo_min} to {tempo_max} (default: 120)
+  --steps-per-beat <n>  exported frames per beat of a midi file, {steps_per_beat_min} to {steps_per_beat_max} (default: 4)
+
+Bench options:
+  --algos <names>       comma separated algorithms, or 'all' (default: all)
+  --sizes <list>        comma separated values per algorithm, each {sweep_size_min} to {sweep_size_max} (default: 1000)
+  --input <shape>       random, sorted, reversed, nearly-sorted, few-unique (default: random)
+  --seeds <n>           runs per algorithm and size, averaged, {seeds_min} to {seeds_max} (default: 1)
+  --seed <n>            first seed; run i uses seed + i (default: 0)
+  --format <format>     table, csv or json (default: table)
+
+Sweep options:
+  --algos <names>       comma separated algorithms, or 'all' (default: quick,merge)
+  --from <n>            smallest size, {sweep_size_min} to {sweep_size_max} (default: 16)
+  --to <n>              largest size, {sweep_size_min} to {sweep_size_max} (default: 4096)
+  --factor <x>          growth between sizes (default: 2)
+  --input <shape>       random, sorted, reversed, nearly-sorted, few-unique (default: random)
+  --seed <n>            input seed (default: 0)
+  --counter <name>      comparisons, swaps, writes or moves (default: comparisons)
+
+Tournament options:
+  --algos <names>       comma separated algorithms, or 'all' (default: all)
+  --size <n>            values per round, {sweep_size_min} to {sweep_size_max} (default: 256)
+  --input <shape>       random, sorted, reversed, nearly-sorted, few-unique (default: random)
+  --seeds <n>           rounds, {seeds_min} to {seeds_max} (default: 20)
+  --seed <n>            first seed; round i uses seed + i (default: 0)
+  --rank-by <metric>    comparisons, swaps, writes, moves or ms (default: comparisons)
+  --format <format>     table, csv or json (default: table)
+  --output <path>       write the results to a file instead of stdout
+
+Check options (exits with an error when a recording is wrong):
+  --algos <names>       algorithms to check, with the same syntax as for export (default: all)
+  --size <n>            values per run, {check_size_min} to {check_size_max} (default: 64)
+  --input <shape>       check only this shape (default: every shape)
+  --seeds <n>           inputs per shape, {seeds_min} to {seeds_max} (default: 3)
+  --seed <n>            first seed; input i uses seed + i (default: 0)",
+        values_min = NUMBER_OF_VALUES_RANGE.start(),
+        values_max = NUMBER_OF_VALUES_RANGE.end(),
+        fps_min = FRAMES_PER_SECOND_RANGE.start(),
+        fps_max = FRAMES_PER_SECOND_RANGE.end(),
+        frame_size_min = EXPORT_SIZE_RANGE_PX.start(),
+        frame_size_max = EXPORT_SIZE_RANGE_PX.end(),
+        note_ms_min = SonificationSettings::NOTE_LENGTH_RANGE_MS.start(),
+        note_ms_max = SonificationSettings::NOTE_LENGTH_RANGE_MS.end(),
+        tempo_min = TEMPO_RANGE_BPM.start(),
+        tempo_max = TEMPO_RANGE_BPM.end(),
+        steps_per_beat_min = STEPS_PER_BEAT_RANGE.start(),
+        steps_per_beat_max = STEPS_PER_BEAT_RANGE.end(),
+        sweep_size_min = SWEEP_SIZE_RANGE.start(),
+        sweep_size_max = SWEEP_SIZE_RANGE.end(),
+        check_size_min = SELF_CHECK_SIZE_RANGE.start(),
+        check_size_max = SELF_CHECK_SIZE_RANGE.end(),
+        seeds_min = SEEDS_RANGE.start(),
+        seeds_max = SEEDS_RANGE.end()
+    )
+}
+
+pub enum Command {
+    Gui(GuiOptions),
+    Export(ExportOptions),
+    Bench(BenchOptions),
+    Sweep(SweepOptions, CounterKind),
+    Tournament(TournamentCommand),
+    Check(SelfCheckOptions),
+    // true for the markdown tables of the README
+    Algorithms(bool),
+    Help,
+}
+
+// settings for the window; None keeps what settings.toml has
+#[derive(Default)]
+pub struct GuiOptions {
+    pub algorithms: Option<Vec<AlgorithmInstance>>,
+    pub number_of_values: Option<usize>,
+    pub frames_per_second: Option<u32>,
+    pub input_distribution: Option<InputDistribution>,
+    pub seed: Option<u64>,
+    pub scenario_path: Option<PathBuf>,
+    pub replay_path: Option<PathBuf>,
+    pub autostart: bool,
+    pub fullscreen: bool,
+}
+
+pub struct TournamentCommand {
+    pub options: TournamentOptions,
+    pub ranking_metric: TournamentMetric,
+    pub output_format: BenchOutputFormat,
+    pub output_path: Option<PathBuf>,
+}
+
+pub fn parse_command(arguments: &[String]) -> Result<Command, String> {
+    let Some(subcommand) = arguments.first() else {
+        return Ok(Command::Gui(GuiOptions::default()));
+    };
+
+    match subcommand.as_str() {
+        "help" | "--help" | "-h" => Ok(Command::Help),
+        flag if flag.starts_with('-') => parse_gui_options(arguments).map(Command::Gui),
+        "export" => parse_export_options(&arguments[1..]).map(Command::Export),
+        "bench" => parse_bench_options(&arguments[1..]).map(Command::Bench),
+        "tournament" => parse_tournament_command(&arguments[1..]).map(Command::Tournament),
+        "sweep" => {
+            let (sweep_options, counter_kind) = parse_sweep_options(&arguments[1..])?;
+            Ok(Command::Sweep(sweep_options, counter_kind))
+        }
+        "check" => parse_check_options(&arguments[1..]).map(Command::Check),
+        "algorithms" => parse_algorithms_flags(&arguments[1..]).map(Command::Algorithms),
+        other => Err(format!("unknown command '{}'", other)),
+    }
+}
+
+fn parse_gui_options(arguments: &[String]) -> Result<GuiOptions, String> {
+    let mut gui_options = GuiOptions::default();
+
+    let mut remaining = OptionArguments::new(arguments);
+    while let Some(flag) = remaining.next_flag()? {
+        match flag.as_str() {
+            "--algos" => {
+                let algorithms = parse_instance_list(&remaining.value_for(&flag)?)?;
+                if algorithms.is_empty() {
+                    return Err("--algos needs at least one algorithm".to_owned());
+                }
+                gui_options.algorithms = Some(algorithms);
+            }
+            "--size" => {
+                let value = remaining.value_for(&flag)?;
+                gui_options.number_of_values = Some(parse_number_in(&flag, &value, NUMBER_OF_VALUES_RANGE)?);
+            }
+            "--fps" => {
+                let frames_per_second =
+                    parse_number_in(&flag, &remaining.value_for(&flag)?, FRAMES_PER_SECOND_RANGE)?;
+                gui_options.frames_per_second = Some(frames_per_second);
+            }
+            "--input" => {
+                gui_options.input_distribution = Some(
+                    parse_input_distribution(&remaining.value_for(&flag)?)?
+                );
+            }
+            "--seed" => {
+                gui_options.seed = Some(parse_number(&flag, &remaining.value_for(&flag)?)?);
+            }
+            "--scenario" => {
+                gui_options.scenario_path = Some(PathBuf::from(remaining.value_for(&flag)?));
+            }
+            "--replay" => {
+                gui_options.replay_path = Some(PathBuf::from(remaining.value_for(&flag)?));
+            }
+            "--autostart" => {
+                gui_options.autostart = true;
+            }
+            "--fullscreen" => {
+                gui_options.fullscreen = true;
+            }
+            other => {
+                return Err(format!("unknown option '{}'", other));
+            }
+        }
+    }
+
+    // each of them decides what plays first
+    let start_count = [
+        gui_options.scenario_path.is_some(),
+        gui_options.replay_path.is_some(),
+        gui_options.autostart,
+    ]
+        .into_iter()
+        .filter(|is_set| *is_set)
+        .count();
+    if start_count > 1 {
+        return Err("use only one of --scenario, --replay and --autostart".to_owned());
+    }
+
+    Ok(gui_options)
+}
+
+fn parse_export_options(arguments: &[String]) -> Result<ExportOptions, String> {
+    let mut algorithms = vec![
+        AlgorithmInstance::new(SortingAlgorithmKind::BubbleSort),
+        AlgorithmInstance::new(SortingAlgorithmKind::QuickSort)
+    ];
+    let mut number_of_values: usize = 128;
+    let mut seed: Option<u64> = None;
+    let mut input_distribution = InputDistribution::Random;
+    let mut chart_style = ChartStyle::Bars;
+    let mut format: Option<ExportFormat> = None;
+    let mut output_path: Option<PathBuf> = None;
+    let mut step_interval: usize = 1;
+    let mut frames_per_second: u32 = 30;
+    let mut snapshot_step: usize = 0;
+    let mut width: u32 = 960;
+    let mut height: u32 = 540;
+    let mut palette = Palette::default();
+    let mut audio_path: Option<PathBuf> = None;
+    let mut sonification = SonificationSettings::default();
+    let mut tempo_bpm: u32 = 120;
+    let mut steps_per_beat: u32 = 4;
+
+    let mut remaining = OptionArguments::new(arguments);
+    while let Some(flag) = remaining.next_flag()? {
+        match flag.as_str() {
+            "--algos" => {
+                algorithms = parse_instance_list(&remaining.value_for(&flag)?)?;
+            }
+            "--size" => {
+                let value = remaining.value_for(&flag)?;
+                number_of_values = parse_number_in(&flag, &value, NUMBER_OF_VALUES_RANGE)?;
+            }
+            "--seed" => {
+                seed = Some(parse_number(&flag, &remaining.value_for(&flag)?)?);
+            }
+            "--input" => {
+                input_distribution = parse_input_distribution(&remaining.value_for(&flag)?)?;
+            }
+            "--audio" => {
+                audio_path = Some(PathBuf::from(remaining.value_for(&flag)?));
+            }
+            "--pitch" => {
+                let name = remaining.value_for(&flag)?;
+                sonification.pitch_source = PitchSource::from_cli_name(&name).ok_or_else(|| {
+                    format!("unknown pitch source '{}' (expected index or value)", name)
+                })?;
+            }
+            "--scale" => {
+                let name = remaining.value_for(&flag)?;
+                sonification.scale = Scale::from_cli_name(&name).ok_or_else(|| {
+                    format!("unknown scale '{}' (expected one of: {})", name, scale_names())
+                })?;
+            }
+            "--sound" => {
+                sonification.change_waveform = parse_waveform(&remaining.value_for(&flag)?)?;
+            }
+            "--compare-sound" => {
+                sonification.compare_waveform = parse_waveform(&remaining.value_for(&flag)?)?;
+            }
+            "--no-compares" => {
+                sonification.play_comparisons = false;
+            }
+            "--note-ms" => {
+                let value = remaining.value_for(&flag)?;
+                sonification.note_length_ms = parse_number_in(&flag, &value, SonificationSettings::NOTE_LENGTH_RANGE_MS)?;
+            }
+            "--tempo" => {
+                tempo_bpm = parse_number_in(&flag, &remaining.value_for(&flag)?, TEMPO_RANGE_BPM)?;
+            }
+            "--steps-per-beat" => {
+                steps_per_beat = parse_number_in(&flag, &remaining.value_for(&flag)?, STEPS_PER_BEAT_RANGE)?;
+            }
+            "--style" => {
+                let name = remaining.value_for(&flag)?;
+                chart_style = ChartStyle::from_cli_name(&name).ok_or_else(|| {
+                    format!("unknown style '{}' (expected one of: {})", name, chart_style_names())
+                })?;
+            }
+            "--format" => {
+                let name = remaining.value_for(&flag)?;
+                format = Some(
+                    ExportFormat::from_cli_name(&name).ok_or_else(|| {
+                        format!("unknown format '{}' (expected gif, apng, png, svg or midi)", name)
+                    })?
+                );
+            }
+            "--output" | "-o" => {
+                output_path = Some(PathBuf::from(remaining.value_for(&flag)?));
+            }
+            "--every" => {
+                step_interval = parse_number(&flag, &remaining.value_for(&flag)?)?;
+            }
+            "--fps" => {
+                let value = remaining.value_for(&flag)?;
+                frames_per_second = parse_number_in(&flag, &value, FRAMES_PER_SECOND_RANGE)?;
+            }
+            "--step" => {
+                snapshot_step = parse_number(&flag, &remaining.value_for(&flag)?)?;
+            }
+            "--width" => {
+                width = parse_number_in(&flag, &remaining.value_for(&flag)?, EXPORT_SIZE_RANGE_PX)?;
+            }
+            "--height" => {
+                height = parse_number_in(&flag, &remaining.value_for(&flag)?, EXPORT_SIZE_RANGE_PX)?;
+            }
+            "--hue" => {
+                let value = remaining.value_for(&flag)?;
+                palette.base_hue_degrees = parse_number_in(&flag, &value, Palette::HUE_RANGE_DEGREES)?;
+            }
+            "--saturation" => {
+                let value = remaining.value_for(&flag)?;
+                palette.saturation = parse_number_in(&flag, &value, Palette::SATURATION_RANGE)?;
+            }
+            "--brightness" => {
+                let value = remaining.value_for(&flag)?;
+                palette.brightness = parse_number_in(&flag, &value, Palette::BRIGHTNESS_RANGE)?;
+            }
+            "--gradient" => {
+                let value = remaining.value_for(&flag)?;
+                palette.gradient_strength = parse_number_in(&flag, &value, Palette::GRADIENT_STRENGTH_RANGE)?;
+            }
+            "--light" => {
+                palette.dark_mode = false;
+            }
+            other => {
+                return Err(format!("unknown export option '{}'", other));
+            }
+        }
+    }
+
+    let output_path = output_path.ok_or_else(|| "missing --output <path>".to_owned())?;
+
+    let format = match format {
+        Some(format) => format,
+        None => infer_format(&output_path)?,
+    };
+
+    if step_interval == 0 {
+        return Err("--every must be at least 1".to_owned());
+    }
+    if audio_path.is_some() && matches!(format, ExportFormat::Svg | ExportFormat::Midi) {
+        return Err("--audio needs an animation format: gif, apng or png".to_owned());
+    }
+
+    Ok(ExportOptions {
+        algorithms,
+        number_of_values,
+        seed,
+        input_distribution,
+        chart_style,
+        palette,
+        width,
+        height,
+        step_interval,
+        frames_per_second,
+        snapshot_step,
+        format,
+        output_path,
+        audio_path,
+        sonification,
+        tempo_bpm,
+        steps_per_beat,
+    })
+}
+
+fn parse_bench_options(arguments: &[String]) -> Result<BenchOptions, String> {
+    let mut algorithms = SortingAlgorithmKind::ALL.to_vec();
+    let mut sizes: Vec<usize> = vec![1000];
+    let mut input_distribution = InputDistribution::Random;
+    let mut seed_count: usize = 1;
+    let mut first_seed: u64 = 0;
+    let mut output_format = BenchOutputFormat::Table;
+
+    let mut remaining = OptionArguments::new(arguments);
+    while let Some(flag) = remaining.next_flag()? {
+        match flag.as_str() {
+            "--algos" => {
+                algorithms = parse_algorithm_list(&remaining.value_for(&flag)?)?;
+            }
+            "--sizes" | "--size" => {
+                sizes = parse_number_list(&flag, &remaining.value_for(&flag)?)?;
+                if let Some(size) = sizes.iter().find(|size| !SWEEP_SIZE_RANGE.contains(size)) {
+                    return Err(
+                        format!(
+                            "{} must be between {} and {}, got {}",
+                            flag,
+                            SWEEP_SIZE_RANGE.start(),
+                            SWEEP_SIZE_RANGE.end(),
+                            size
+                        )
+                    );
+                }
+            }
+            "--input" => {
+                input_distribution = parse_input_distribution(&remaining.value_for(&flag)?)?;
+            }
+            "--seeds" => {
+                seed_count = parse_number_in(&flag, &remaining.value_for(&flag)?, SEEDS_RANGE)?;
+            }
+            "--seed" => {
+                first_seed = parse_number(&flag, &remaining.value_for(&flag)?)?;
+            }
+            "--format" => {
+                let name = remaining.value_for(&flag)?;
+                output_format = BenchOutputFormat::from_cli_name(&name).ok_or_else(|| {
+                    format!("unknown format '{}' (expected table, csv or json)", name)
+                })?;
+            }
+            other => {
+                return Err(format!("unknown bench option '{}'", other));
+            }
+        }
+    }
+
+    if algorithms.is_empty() {
+        return Err("--algos must name at least one algorithm".to_owned());
+    }
+    if sizes.is_empty() {
+        return Err("--sizes must list at least one size".to_owned());
+    }
+
+    Ok(BenchOptions {
+        algorithms,
+        sizes,
+        input_distribution,
+        seed_count,
+        first_seed,
+        output_format,
+    })
+}
+
+fn parse_sweep_options(arguments: &[String]) -> Result<(SweepOptions, CounterKind), String> {
+    let mut sweep_options = SweepOptions::default();
+    let mut counter_kind = CounterKind::Comparisons;
+
+    let mut remaining = OptionArguments::new(arguments);
+    while let Some(flag) = remaining.next_flag()? {
+        match flag.as_str() {
+            "--algos" => {
+                sweep_options.algorithms = parse_algorithm_list(&remaining.value_for(&flag)?)?;
+            }
+            "--from" => {
+                sweep_options.minimum_size = parse_number(&flag, &remaining.value_for(&flag)?)?;
+            }
+            "--to" => {
+                sweep_options.maximum_size = parse_number(&flag, &remaining.value_for(&flag)?)?;
+            }
+            "--factor" => {
+                sweep_options.growth_factor = parse_number(&flag, &remaining.value_for(&flag)?)?;
+            }
+            "--input" => {
+                sweep_options.input_distribution = parse_input_distribution(
+                    &remaining.value_for(&flag)?
+                )?;
+            }
+            "--seed" => {
+                sweep_options.seed = parse_number(&flag, &remaining.value_for(&flag)?)?;
+            }
+            "--counter" => {
+                let name = remaining.value_for(&flag)?;
+                counter_kind = CounterKind::from_cli_name(&name).ok_or_else(|| {
+                    format!(
+                        "unknown counter '{}' (expected comparisons, swaps, writes or moves)",
+                        name
+                    )
+                })?;
+            }
+            other => {
+                return Err(format!("unknown sweep option '{}'", other));
+            }
+        }
+    }
+
+    if sweep_options.algorithms.is_empty() {
+        return Err("--algos must name at least one algorithm".to_owned());
+    }
+    for (flag, size) in [("--from", sweep_options.minimum_size), ("--to", sweep_options.maximum_size)] {
+        if !SWEEP_SIZE_RANGE.contains(&size) {
+            return Err(
+                format!(
+                    "{} must be between {} and {}, got {}",
+                    flag,
+                    SWEEP_SIZE_RANGE.start(),
+                    SWEEP_SIZE_RANGE.end(),
+                    size
+                )
+            );
+        }
+    }
+    if sweep_options.maximum_size < sweep_options.minimum_size {
+        return Err("--to must not be smaller than --from".to_owned());
+    }
+    // also rejects nan, which every comparison would let through
+    if !(sweep_options.growth_factor.is_finite() && sweep_options.growth_factor > 1.0) {
+        return Err(format!("--factor must be a number greater than 1, got {}", sweep_options.growth_factor));
+    }
+
+    Ok((sweep_options, counter_kind))
+}
+
+fn parse_check_options(arguments: &[String]) -> Result<SelfCheckOptions, String> {
+    let mut options = SelfCheckOptions::default();
+
+    let mut remaining = OptionArguments::new(arguments);
+    while let Some(flag) = remaining.next_flag()? {
+        match flag.as_str() {
+            "--algos" => {
+                options.algorithms = parse_instance_list(&remaining.value_for(&flag)?)?;
+            }
+            "--size" => {
+                let value = remaining.value_for(&flag)?;
+                options.number_of_values = parse_number_in(&flag, &value, SELF_CHECK_SIZE_RANGE)?;
+            }
+            "--input" => {
+                options.input_distributions = vec![parse_input_distribution(&remaining.value_for(&flag)?)?];
+            }
+            "--seeds" => {
+                options.seed_count = parse_number_in(&flag, &remaining.value_for(&flag)?, SEEDS_RANGE)?;
+            }
+            "--seed" => {
+                options.first_seed = parse_number(&flag, &remaining.value_for(&flag)?)?;
+            }
+            other => {
+                return Err(format!("unknown check option '{}'", other));
+            }
+        }
+    }
+
+    if options.algorithms.is_empty() {
+        return Err("--algos must name at least one algorithm".to_owned());
+    }
+
+    Ok(options)
+}
+
+fn parse_tournament_command(arguments: &[String]) -> Result<TournamentCommand, String> {
+    let mut options = TournamentOptions::default();
+    let mut ranking_metric = TournamentMetric::Comparisons;
+    let mut output_format = BenchOutputFormat::Table;
+    let mut output_path: Option<PathBuf> = None;
+
+    let mut remaining = OptionArguments::new(arguments);
+    while let Some(flag) = remaining.next_flag()? {
+        match flag.as_str() {
+            "--algos" => {
+                options.algorithms = parse_algorithm_list(&remaining.value_for(&flag)?)?;
+            }
+            "--size" => {
+                let value = remaining.value_for(&flag)?;
+                options.number_of_values = parse_number_in(&flag, &value, SWEEP_SIZE_RANGE)?;
+            }
+            "--input" => {
+                options.input_distribution = parse_input_distribution(&remaining.value_for(&flag)?)?;
+            }
+            "--seeds" => {
+                options.seed_count = parse_number_in(&flag, &remaining.value_for(&flag)?, SEEDS_RANGE)?;
+            }
+            "--seed" => {
+                options.first_seed = parse_number(&flag, &remaining.value_for(&flag)?)?;
+            }
+            "--rank-by" => {
+                let name = remaining.value_for(&flag)?;
+                ranking_metric = TournamentMetric::from_cli_name(&name).ok_or_else(|| {
+                    format!(
+                        "unknown metric '{}' (expected comparisons, swaps, writes, moves or ms)",
+                        name
+                    )
+                })?;
+            }
+            "--format" => {
+                let name = remaining.value_for(&flag)?;
+                output_format = BenchOutputFormat::from_cli_name(&name).ok_or_else(|| {
+                    format!("unknown format '{}' (expected table, csv or json)", name)
+                })?;
+            }
+            "--output" | "-o" => {
+                output_path = Some(PathBuf::from(remaining.value_for(&flag)?));
+            }
+            other => {
+                return Err(format!("unknown tournament option '{}'", other));
+            }
+        }
+    }
+
+    if options.algorithms.is_empty() {
+        return Err("--algos must name at least one algorithm".to_owned());
+    }
+
+    Ok(TournamentCommand {
+        options,
+        ranking_metric,
+        output_format,
+        output_path,
+    })
+}
+
+fn parse_algorithms_flags(arguments: &[String]) -> Result<bool, String> {
+    let mut is_markdown = false;
+    let mut remaining = OptionArguments::new(arguments);
+    while let Some(flag) = remaining.next_flag()? {
+        match flag.as_str() {
+            "--markdown" => {
+                is_markdown = true;
+            }
+            other => {
+                return Err(format!("unknown algorithms option '{}'", other));
+            }
+        }
+    }
+
+    Ok(is_markdown)
+}
+
+fn infer_format(output_path: &std::path::Path) -> Result<ExportFormat, String> {
+    let extension = output_path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_ascii_lowercase());
+
+    match extension.as_deref() {
+        Some("gif") => Ok(ExportFormat::Gif),
+        Some("apng") => Ok(ExportFormat::Apng),
+        // --format png means numbered frames, so a .png file name is not enough to go on
+        Some("png") => {
+            Err("'.png' could mean an apng or numbered frames; use '.apng' or pass --format".to_owned())
+        }
+        Some("svg") => Ok(ExportFormat::Svg),
+        Some("mid") | Some("midi") => Ok(ExportFormat::Midi),
+        // no extension: treat it as a frame directory
+        None => Ok(ExportFormat::PngSequence),
+        Some(other) => Err(format!("cannot infer export format from '.{}', pass --format", other)),
+    }
+}
+
+pub fn parse_algorithm_list(list: &str) -> Result<Vec<SortingAlgorithmKind>, String> {
+    if list.trim().eq_ignore_ascii_case("all") {
+        return Ok(SortingAlgorithmKind::ALL.to_vec());
+    }
+
+    list.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            SortingAlgorithmKind::from_cli_name(name).ok_or_else(|| {
+                format!("unknown algorithm '{}' (expected one of: {})", name, algorithm_names())
+            })
+        })
+        .collect()
+}
+
+// like parse_algorithm_list, with optional ':parameter' suffixes per algorithm
+pub fn parse_instance_list(list: &str) -> Result<Vec<AlgorithmInstance>, String> {
+    if list.trim().eq_ignore_ascii_case("all") {
+        return Ok(SortingAlgorithmKind::ALL.into_iter().map(AlgorithmInstance::new).collect());
+    }
+
+    list.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(AlgorithmInstance::from_spec)
+        .collect()
+}
+
+pub fn parse_input_distribution(name: &str) -> Result<InputDistribution, String> {
+    InputDistribution::from_cli_name(name.trim()).ok_or_else(|| {
+        let input_names = InputDistribution::ALL
+            .iter()
+            .map(|distribution| distribution.cli_name())
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("unknown input '{}' (expected one of: {})", name, input_names)
+    })
+}
+
+pub fn parse_number_list<T: std::str::FromStr>(flag: &str, list: &str) -> Result<Vec<T>, String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| parse_number(flag, value))
+        .collect()
+}
+
+fn parse_waveform(name: &str) -> Result<Waveform, String> {
+    Waveform::from_cli_name(name).ok_or_else(|| {
+        let waveform_names = Waveform::ALL
+            .iter()
+            .map(|waveform| waveform.cli_name())
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("unknown sound '{}' (expected one of: {})", name, waveform_names)
+    })
+}
+
+fn scale_names() -> String {
+    Scale::ALL
+        .iter()
+        .map(|scale| scale.cli_name())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn chart_style_names() -> String {
+    ChartStyle::ALL
+        .iter()
+        .map(|chart_style| chart_style.cli_name())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub fn parse_number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value.trim().parse().map_err(|_| format!("invalid value '{}' for {}", value, flag))
+}
+
+// NaN is in no range, so it is rejected along with the values outside it
+fn parse_number_in<T: std::str::FromStr + PartialOrd + std::fmt::Display>(
+    flag: &str,
+    value: &str,
+    range: RangeInclusive<T>
+) -> Result<T, String> {
+    let number = parse_number(flag, value)?;
+    if !range.contains(&number) {
+        return Err(format!("{} must be between {} and {}, got {}", flag, range.start(), range.end(), number));
+    }
+    Ok(number)
+}
+
+// walks `--flag value` and `--flag=value` pairs
+pub struct OptionArguments<'a> {
+    arguments: std::slice::Iter<'a, String>,
+    pending_value: Option<String>,
+}
+
+impl<'a> OptionArguments<'a> {
+    pub fn new(arguments: &'a [String]) -> Self {
+        Self {
+            arguments: arguments.iter(),
+            pending_value: None,
+        }
+    }
+
+    pub fn next_flag(&mut self) -> Result<Option<String>, String> {
+        if let Some(value) = self.pending_value.take() {
+            return Err(format!("unexpected value '{}'", value));
+        }
+
+        let Some(argument) = self.arguments.next() else {
+            return Ok(None);
+        };
+
+        if !argument.starts_with('-') {
+            return Err(format!("unexpected argument '{}'", argument));
+        }
+
+        match argument.split_once('=') {
+            Some((flag, value)) => {
+                self.pending_value = Some(value.to_owned());
+                Ok(Some(flag.to_owned()))
+            }
+            None => Ok(Some(argument.clone())),
+        }
+    }
+
+    pub fn value_for(&mut self, flag: &str) -> Result<String, String> {
+        if let Some(value) = self.pending_value.take() {
+            return Ok(value);
+        }
+
+        self.arguments
+            .next()
+            .cloned()
+            .ok_or_else(|| format!("missing value for {}", flag))
+    }
+}
@@ -0,0 +1,362 @@
+use crate::bench::measure_sort;
+use crate::input::InputDistribution;
+use crate::sorting_algorithms::recorder::OperationCounts;
+use crate::sorting_algorithms::SortingAlgorithmKind;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CounterKind {
+    #[default]
+    Comparisons,
+    Swaps,
+    Writes,
+    // swaps and writes together: every change to the array
+    Moves,
+}
+
+impl CounterKind {
+    pub const ALL: [CounterKind; 4] = [
+        CounterKind::Comparisons,
+        CounterKind::Swaps,
+        CounterKind::Writes,
+        CounterKind::Moves,
+    ];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            CounterKind::Comparisons => "comparisons",
+            CounterKind::Swaps => "swaps",
+            CounterKind::Writes => "writes",
+            CounterKind::Moves => "swaps + writes",
+        }
+    }
+
+    pub fn cli_name(&self) -> &'static str {
+        match self {
+            CounterKind::Comparisons => "comparisons",
+            CounterKind::Swaps => "swaps",
+            CounterKind::Writes => "writes",
+            CounterKind::Moves => "moves",
+        }
+    }
+
+    pub fn from_cli_name(name: &str) -> Option<CounterKind> {
+        CounterKind::ALL
+            .into_iter()
+            .find(|counter_kind| counter_kind.cli_name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn count(&self, operation_counts: &OperationCounts) -> u64 {
+        match self {
+            CounterKind::Comparisons => operation_counts.comparisons,
+            CounterKind::Swaps => operation_counts.swaps,
+            CounterKind::Writes => operation_counts.writes,
+            CounterKind::Moves => operation_counts.swaps + operation_counts.writes,
+        }
+    }
+}
+
+// growth curves the fitted counts are compared against
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ComplexityModel {
+    Linear,
+    // log2(n!), the comparison lower bound
+    LogFactorial,
+    LinearLogarithmic,
+    Quadratic,
+}
+
+impl ComplexityModel {
+    pub const ALL: [ComplexityModel; 4] = [
+        ComplexityModel::Linear,
+        ComplexityModel::LogFactorial,
+        ComplexityModel::LinearLogarithmic,
+        ComplexityModel::Quadratic,
+    ];
+
+    // the curves drawn behind the measurements
+    pub const REFERENCE_CURVES: [ComplexityModel; 3] = [
+        ComplexityModel::Quadratic,
+        ComplexityModel::LinearLogarithmic,
+        ComplexityModel::LogFactorial,
+    ];
+
+    pub fn formula(&self) -> &'static str {
+        match self {
+            ComplexityModel::Linear => "n",
+            ComplexityModel::LogFactorial => "log₂n!",
+            ComplexityModel::LinearLogarithmic => "n·log₂n",
+            ComplexityModel::Quadratic => "n²",
+        }
+    }
+
+    pub fn evaluate(&self, number_of_values: f64) -> f64 {
+        match self {
+            ComplexityModel::Linear => number_of_values,
+            ComplexityModel::LogFactorial => log2_factorial(number_of_values),
+            ComplexityModel::LinearLogarithmic => number_of_values * number_of_values.log2(),
+            ComplexityModel::Quadratic => number_of_values * number_of_values,
+        }
+    }
+}
+
+// log2(n!) via Stirling's series; exact enough from n = 2 on
+pub fn log2_factorial(number_of_values: f64) -> f64 {
+    if number_of_values < 2.0 {
+        return 0.0;
+    }
+
+    let n = number_of_values;
+    let natural_log = n * n.ln() - n + 0.5 * (std::f64::consts::TAU * n).ln() + 1.0 / (12.0 * n);
+    natural_log / std::f64::consts::LN_2
+}
+
+#[derive(Clone, Debug)]
+pub struct SweepOptions {
+    pub algorithms: Vec<SortingAlgorithmKind>,
+    pub minimum_size: usize,
+    pub maximum_size: usize,
+    // each size is this many times the previous one
+    pub growth_factor: f64,
+    pub input_distribution: InputDistribution,
+    pub seed: u64,
+}
+
+impl Default for SweepOptions {
+    fn default() -> Self {
+        Self {
+            algorithms: vec![SortingAlgorithmKind::QuickSort, SortingAlgorithmKind::MergeSort],
+            minimum_size: 16,
+            maximum_size: 4096,
+            growth_factor: 2.0,
+            input_distribution: InputDistribution::Random,
+            seed: 0,
+        }
+    }
+}
+
+// sizes a sweep may use; the quadratic sorts take minutes near the top already
+pub const SWEEP_SIZE_RANGE: std::ops::RangeInclusive<usize> = 2..=1_000_000;
+
+// minimum, minimum * factor, ... up to and including maximum
+pub fn geometric_sizes(minimum_size: usize, maximum_size: usize, growth_factor: f64) -> Vec<usize> {
+    let growth_factor = growth_factor.max(1.1);
+    let mut sizes = Vec::new();
+
+    // compared as floats: a size past usize::MAX would round to usize::MAX and never stop
+    let mut size = minimum_size.max(2) as f64;
+    while size.round() <= (maximum_size as f64) {
+        let rounded_size = size.round() as usize;
+        if sizes.last() != Some(&rounded_size) {
+            sizes.push(rounded_size);
+        }
+        size *= growth_factor;
+    }
+
+    sizes
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SweepPoint {
+    pub number_of_values: usize,
+    pub operation_counts: OperationCounts,
+}
+
+#[derive(Clone, Debug)]
+pub struct SweepSeries {
+    pub algorithm_kind: SortingAlgorithmKind,
+    pub points: Vec<SweepPoint>,
+}
+
+impl SweepSeries {
+    // (n, count) pairs with a positive count, the only ones that exist on log axes
+    pub fn samples(&self, counter_kind: CounterKind) -> Vec<(f64, f64)> {
+        self.points
+            .iter()
+            .map(|point| {
+                (point.number_of_values as f64, counter_kind.count(&point.operation_counts) as f64)
+            })
+            .filter(|(_, count)| *count > 0.0)
+            .collect()
+    }
+
+    // what to show instead of the fit when fit_complexity finds none
+    pub fn missing_fit_reason(&self, counter_kind: CounterKind) -> String {
+        if self.points.len() < 2 {
+            "need at least two sizes to fit".to_owned()
+        } else if self.samples(counter_kind).is_empty() {
+            format!("no {}", counter_kind.display_name())
+        } else {
+            format!("{} at only one size", counter_kind.display_name())
+        }
+    }
+}
+
+// counts every algorithm at every size; all algorithms see the same input per size
+pub fn run_sweep(options: &SweepOptions) -> Vec<SweepSeries> {
+    let sizes = geometric_sizes(options.minimum_size, options.maximum_size, options.growth_factor);
+
+    let inputs: Vec<Vec<u32>> = sizes
+        .iter()
+        .map(|size| options.input_distribution.generate(*size, Some(options.seed)))
+        .collect();
+
+    options.algorithms
+        .iter()
+        .map(|algorithm_kind| SweepSeries {
+            algorithm_kind: *algorithm_kind,
+            points: inputs
+                .iter()
+                .map(|base_values| SweepPoint {
+                    number_of_values: base_values.len(),
+                    operation_counts: measure_sort(*algorithm_kind, base_values).operation_counts,
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ComplexityFit {
+    // count ≈ constant · n^exponent, a straight line on log-log axes
+    pub exponent: f64,
+    pub constant: f64,
+    // the reference curve that explains the counts best, and its scale
+    pub model: ComplexityModel,
+    pub model_constant: f64,
+}
+
+impl ComplexityFit {
+    // "≈ 1.41·n·log₂n comparisons"
+    pub fn describe(&self, counter_kind: CounterKind) -> String {
+        format!(
+            "≈ {:.2}·{} {}",
+            self.model_constant,
+            self.model.formula(),
+            counter_kind.display_name()
+        )
+    }
+
+    pub fn describe_power_law(&self) -> String {
+        format!("≈ {:.3}·n^{:.2}", self.constant, self.exponent)
+    }
+}
+
+// least squares in log space; needs two distinct sizes with nonzero counts
+pub fn fit_complexity(samples: &[(f64, f64)]) -> Option<ComplexityFit> {
+    let log_samples: Vec<(f64, f64)> = samples
+        .iter()
+        .filter(|(number_of_values, count)| *number_of_values > 1.0 && *count > 0.0)
+        .map(|(number_of_values, count)| (number_of_values.ln(), count.ln()))
+        .collect();
+
+    if log_samples.len() < 2 {
+        return None;
+    }
+
+    let sample_count = log_samples.len() as f64;
+    let mean_x = log_samples.iter().map(|(x, _)| x).sum::<f64>() / sample_count;
+    let mean_y = log_samples.iter().map(|(_, y)| y).sum::<f64>() / sample_count;
+
+    let covariance: f64 = log_samples
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let variance: f64 = log_samples
+        .iter()
+        .map(|(x, _)| (x - mean_x) * (x - mean_x))
+        .sum();
+    if variance <= f64::EPSILON {
+        return None;
+    }
+
+    let exponent = covariance / variance;
+    let constant = (mean_y - exponent * mean_x).exp();
+
+    // for each model the best scale is the mean log ratio; keep the model with the smallest spread
+    let (model, model_constant, _) = ComplexityModel::ALL
+        .into_iter()
+        .map(|model| {
+            let log_ratios: Vec<f64> = samples
+                .iter()
+                .filter(|(number_of_values, count)| *number_of_values > 1.0 && *count > 0.0)
+                .map(|(number_of_values, count)| {
+                    count.ln() - model.evaluate(*number_of_values).ln()
+                })
+                .collect();
+
+            let mean_log_ratio = log_ratios.iter().sum::<f64>() / (log_ratios.len() as f64);
+            let residual: f64 = log_ratios
+                .iter()
+                .map(|log_ratio| (log_ratio - mean_log_ratio).powi(2))
+                .sum();
+
+            (model, mean_log_ratio.exp(), residual)
+        })
+        .min_by(|(_, _, left_residual), (_, _, right_residual)| {
+            left_residual.total_cmp(right_residual)
+        })?;
+
+    Some(ComplexityFit {
+        exponent,
+        constant,
+        model,
+        model_constant,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a series with the given comparison and swap counts at each size
+    fn series(counts: &[(usize, u64, u64)]) -> SweepSeries {
+        SweepSeries {
+            algorithm_kind: SortingAlgorithmKind::InsertionSort,
+            points: counts
+                .iter()
+                .map(|(number_of_values, comparisons, swaps)| SweepPoint {
+                    number_of_values: *number_of_values,
+                    operation_counts: OperationCounts {
+                        comparisons: *comparisons,
+                        swaps: *swaps,
+                        writes: 0,
+                    },
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn counter_zero_at_every_size_is_missing() {
+        let sweep_series = series(&[(16, 120, 0), (32, 496, 0), (64, 2016, 0)]);
+
+        assert!(sweep_series.samples(CounterKind::Swaps).is_empty());
+        assert!(fit_complexity(&sweep_series.samples(CounterKind::Swaps)).is_none());
+        assert_eq!(sweep_series.missing_fit_reason(CounterKind::Swaps), "no swaps");
+        assert_eq!(sweep_series.missing_fit_reason(CounterKind::Moves), "no swaps + writes");
+    }
+
+    #[test]
+    fn counter_at_one_size_is_too_few_sizes() {
+        let sweep_series = series(&[(16, 120, 0), (32, 496, 3)]);
+        assert_eq!(sweep_series.missing_fit_reason(CounterKind::Swaps), "swaps at only one size");
+        assert_eq!(
+            series(&[(16, 120, 0)]).missing_fit_reason(CounterKind::Comparisons),
+            "need at least two sizes to fit"
+        );
+    }
+
+    #[test]
+    fn all_pairs_count_fits_quadratic() {
+        // n(n-1)/2 comparisons, as a bubble sort without early exit makes
+        let counts: Vec<(usize, u64, u64)> = geometric_sizes(16, 4096, 2.0)
+            .into_iter()
+            .map(|size| (size, (size * (size - 1) / 2) as u64, 0))
+            .collect();
+        let fit = fit_complexity(&series(&counts).samples(CounterKind::Comparisons)).unwrap();
+
+        assert_eq!(fit.model, ComplexityModel::Quadratic);
+        assert!((fit.exponent - 2.0).abs() < 0.05, "exponent {}", fit.exponent);
+        assert!((fit.model_constant - 0.5).abs() < 0.05, "constant {}", fit.model_constant);
+    }
+}
@@ -1,23 +1,40 @@
-use std::sync::{ atomic::{ AtomicBool, Ordering }, Arc, Mutex };
+use std::ops::RangeInclusive;
+use std::sync::{ atomic::{ AtomicBool, Ordering }, mpsc::Sender, Arc, Mutex };
 use std::thread;
 use std::time::{ Duration, Instant };
 
-use rand::seq::SliceRandom;
-use rand::rng;
-
+use crate::chart::changed_indices;
+use crate::input::InputDistribution;
+use crate::sonification::{ NoteEvent, StepOperations };
+use crate::sorting_algorithms::instance::AlgorithmInstance;
 use crate::sorting_algorithms::{ SortingAlgorithmKind, SortingAlgorithmReplay };
 use crate::stats::StatsSnapshot;
+use crate::trace::{ SortTrace, TRACE_MAX_ROWS };
+use crate::verification::Verification;
 
+/// What one panel shows at the current step.
 #[derive(Clone)]
 pub struct AlgorithmStateSnapshot {
+    /// Identifies the panel within a run; names repeat when an algorithm runs more than once.
+    pub panel_id: usize,
     pub algorithm_name: String,
     pub current_values: Vec<u32>,
     pub is_finished: bool,
     pub stats: StatsSnapshot,
+    pub current_step: usize,
+    /// Elements written by the step that produced `current_values`.
+    pub highlighted_indices: Vec<usize>,
+    /// Latest marker an imported trace reached, if it has any.
+    pub current_marker: Option<String>,
+    pub trace: Arc<SortTrace>,
+    /// Set once the run has finished: its result checked against its input.
+    pub verification: Option<Arc<Verification>>,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+/// What the worker thread is doing.
+#[derive(Clone, Debug, PartialEq, Default)]
 pub enum EngineState {
+    #[default]
     Idle,
     Preparing {
         algorithms_completed: usize,
@@ -26,28 +43,60 @@ pub enum EngineState {
     Running,
 }
 
-impl Default for EngineState {
-    fn default() -> Self {
-        EngineState::Idle
-    }
-}
-
+/// State the worker publishes for the front end, behind a mutex.
 #[derive(Clone, Default)]
 pub struct EngineSharedState {
     pub algorithm_states: Vec<AlgorithmStateSnapshot>,
     pub engine_state: EngineState,
+    /// The run being played, or the last one played.
+    pub recorded_run: Option<Arc<RecordedRun>>,
+}
+
+/// Array sizes the window and the animation export accept.
+pub const NUMBER_OF_VALUES_RANGE: RangeInclusive<usize> = 32..=2500;
+/// Playback speeds the window and the animation export accept.
+pub const FRAMES_PER_SECOND_RANGE: RangeInclusive<u32> = 10..=120;
+
+/// Everything needed to play a run again: the shared input and one replay per algorithm.
+pub struct RecordedRun {
+    pub input_distribution: InputDistribution,
+    pub frames_per_second: u32,
+    /// Input of every replay whose instance did not choose its own shape.
+    pub base_values: Vec<u32>,
+    pub replays: Vec<SortingAlgorithmReplay>,
+    /// The array each replay was given, in panel order; a correct recording starts with it.
+    pub replay_inputs: Vec<Vec<u32>>,
+    /// The algorithm behind each replay, in panel order; `None` for imported traces.
+    pub algorithm_kinds: Vec<Option<SortingAlgorithmKind>>,
+}
+
+impl RecordedRun {
+    pub fn replay_input(&self, panel_id: usize) -> &[u32] {
+        self.replay_inputs.get(panel_id).unwrap_or(&self.base_values)
+    }
+
+    pub fn algorithm_kind(&self, panel_id: usize) -> Option<SortingAlgorithmKind> {
+        self.algorithm_kinds.get(panel_id).copied().flatten()
+    }
 }
 
+/// Settings for [`EngineController::start_run`].
 pub struct EngineConfig {
     pub number_of_values: usize,
-    pub selected_algorithms: Vec<SortingAlgorithmKind>,
+    pub input_distribution: InputDistribution,
+    /// One panel per instance, in grid order; the same algorithm may appear more than once.
+    pub selected_algorithms: Vec<AlgorithmInstance>,
     pub frames_per_second: u32,
+    /// Seed of the input; a fresh random one when `None`.
+    pub input_seed: Option<u64>,
 }
 
+/// Records runs and plays them back on a worker thread.
 pub struct EngineController {
     shared_state: Arc<Mutex<EngineSharedState>>,
     stop_flag: Arc<AtomicBool>,
     worker_handle: Option<thread::JoinHandle<()>>,
+    note_sender: Option<Sender<Vec<NoteEvent>>>,
 }
 
 impl EngineController {
@@ -56,25 +105,36 @@ impl EngineController {
             shared_state,
             stop_flag: Arc::new(AtomicBool::new(false)),
             worker_handle: None,
+            note_sender: None,
         }
     }
 
+    /// Where runs started from now on send the notes of every tick, e.g. an audio mixer.
+    pub fn set_note_sender(&mut self, note_sender: Option<Sender<Vec<NoteEvent>>>) {
+        self.note_sender = note_sender;
+    }
+
+    /// Stops any current run, then records every selected algorithm on one fresh input and
+    /// plays them back at `frames_per_second`.
     pub fn start_run(&mut self, config: EngineConfig) {
         self.stop();
 
         self.stop_flag.store(false, Ordering::SeqCst);
         let stop_flag_clone = Arc::clone(&self.stop_flag);
         let shared_state_clone = Arc::clone(&self.shared_state);
+        let note_sender = self.note_sender.clone();
 
         let number_of_values = config.number_of_values;
+        let input_distribution = config.input_distribution;
         let selected_algorithms = config.selected_algorithms;
         let frames_per_second = config.frames_per_second.max(1);
+        let input_seed = config.input_seed.unwrap_or_else(rand::random);
         let target_frame_duration = Duration::from_millis((1000 / frames_per_second) as u64);
 
         let worker_handle = thread::spawn(move || {
-            let mut base_values: Vec<u32> = (0..number_of_values as u32).collect();
-            let mut random_generator = rng();
-            base_values.shuffle(&mut random_generator);
+            // instances with their own input shape draw it from the same seed, so e.g. two
+            // few-unique instances still sort the same array
+            let base_values = input_distribution.generate(number_of_values, Some(input_seed));
 
             let algorithms_total = selected_algorithms.len();
 
@@ -89,7 +149,9 @@ impl EngineController {
 
             // generate frames, update progress
             let mut algorithm_replays: Vec<SortingAlgorithmReplay> = Vec::new();
-            for (index, algorithm_kind) in selected_algorithms.into_iter().enumerate() {
+            let mut replay_inputs: Vec<Vec<u32>> = Vec::new();
+            let mut algorithm_kinds: Vec<Option<SortingAlgorithmKind>> = Vec::new();
+            for (index, algorithm_instance) in selected_algorithms.into_iter().enumerate() {
                 // check stop flag
                 if stop_flag_clone.load(Ordering::SeqCst) {
                     let mut locked_state = shared_state_clone.lock().unwrap();
@@ -98,8 +160,17 @@ impl EngineController {
                     return;
                 }
 
-                let replay = SortingAlgorithmReplay::new(algorithm_kind, &base_values);
-                algorithm_replays.push(replay);
+                let instance_values = match algorithm_instance.input_distribution {
+                    Some(instance_distribution) if instance_distribution != input_distribution => {
+                        instance_distribution.generate(number_of_values, Some(input_seed))
+                    }
+                    _ => base_values.clone(),
+                };
+                algorithm_replays.push(
+                    SortingAlgorithmReplay::for_instance(&algorithm_instance, &instance_values)
+                );
+                replay_inputs.push(instance_values);
+                algorithm_kinds.push(Some(algorithm_instance.kind));
 
                 {
                     let mut locked_state = shared_state_clone.lock().unwrap();
@@ -110,71 +181,53 @@ impl EngineController {
                 }
             }
 
-            if algorithm_replays.is_empty() {
-                let mut locked_state = shared_state_clone.lock().unwrap();
-                locked_state.algorithm_states.clear();
-                locked_state.engine_state = EngineState::Idle;
-                return;
-            }
-
-            let total_algorithms = algorithm_replays.len();
-            let mut current_step_index: usize = 0;
-            let mut all_algorithms_finished = false;
-
-            {
-                let mut locked_state = shared_state_clone.lock().unwrap();
-                locked_state.engine_state = EngineState::Running;
-            }
-
-            while !stop_flag_clone.load(Ordering::SeqCst) && !all_algorithms_finished {
-                let frame_start_time = Instant::now();
-                all_algorithms_finished = true;
-
-                let mut snapshots_for_frame: Vec<AlgorithmStateSnapshot> =
-                    Vec::with_capacity(total_algorithms);
-
-                for algorithm_replay in algorithm_replays.iter() {
-                    let (frame_values, is_finished_for_algorithm) =
-                        algorithm_replay.frame_at(current_step_index);
-                    let stats_snapshot = algorithm_replay.stats_snapshot();
-
-                    if !is_finished_for_algorithm {
-                        all_algorithms_finished = false;
-                    }
+            let recorded_run = Arc::new(RecordedRun {
+                input_distribution,
+                frames_per_second,
+                base_values,
+                replays: algorithm_replays,
+                replay_inputs,
+                algorithm_kinds,
+            });
+
+            play_recorded_run(
+                &shared_state_clone,
+                &stop_flag_clone,
+                recorded_run,
+                target_frame_duration,
+                note_sender.as_ref()
+            );
+        });
 
-                    snapshots_for_frame.push(AlgorithmStateSnapshot {
-                        algorithm_name: algorithm_replay.name().to_owned(),
-                        current_values: frame_values,
-                        is_finished: is_finished_for_algorithm,
-                        stats: stats_snapshot,
-                    });
-                }
+        self.worker_handle = Some(worker_handle);
+    }
 
-                current_step_index = current_step_index.saturating_add(1);
+    /// Plays back a run that was recorded earlier, e.g. one loaded from a replay file.
+    pub fn start_replay(&mut self, recorded_run: Arc<RecordedRun>, frames_per_second: u32) {
+        self.stop();
 
-                {
-                    let mut locked_state = shared_state_clone.lock().unwrap();
-                    locked_state.algorithm_states = snapshots_for_frame;
-                    locked_state.engine_state = if all_algorithms_finished {
-                        EngineState::Idle
-                    } else {
-                        EngineState::Running
-                    };
-                }
+        self.stop_flag.store(false, Ordering::SeqCst);
+        let stop_flag_clone = Arc::clone(&self.stop_flag);
+        let shared_state_clone = Arc::clone(&self.shared_state);
+        let note_sender = self.note_sender.clone();
 
-                let frame_elapsed_time = frame_start_time.elapsed();
-                if frame_elapsed_time < target_frame_duration {
-                    thread::sleep(target_frame_duration - frame_elapsed_time);
-                }
-            }
+        let frames_per_second = frames_per_second.max(1);
+        let target_frame_duration = Duration::from_millis((1000 / frames_per_second) as u64);
 
-            let mut locked_state = shared_state_clone.lock().unwrap();
-            locked_state.engine_state = EngineState::Idle;
+        let worker_handle = thread::spawn(move || {
+            play_recorded_run(
+                &shared_state_clone,
+                &stop_flag_clone,
+                recorded_run,
+                target_frame_duration,
+                note_sender.as_ref()
+            );
         });
 
         self.worker_handle = Some(worker_handle);
     }
 
+    /// Stops the worker and waits for it; the last snapshots stay in the shared state.
     pub fn stop(&mut self) {
         self.stop_flag.store(true, Ordering::SeqCst);
 
@@ -184,6 +237,147 @@ impl EngineController {
     }
 }
 
+// steps every replay of the run in lockstep, publishing one snapshot per frame and sending
+// the notes of each step, timed from the moment the frame starts
+fn play_recorded_run(
+    shared_state: &Mutex<EngineSharedState>,
+    stop_flag: &AtomicBool,
+    recorded_run: Arc<RecordedRun>,
+    target_frame_duration: Duration,
+    note_sender: Option<&Sender<Vec<NoteEvent>>>
+) {
+    let algorithm_replays = &recorded_run.replays;
+
+    if algorithm_replays.is_empty() {
+        let mut locked_state = shared_state.lock().unwrap();
+        locked_state.algorithm_states.clear();
+        locked_state.engine_state = EngineState::Idle;
+        return;
+    }
+
+    let algorithm_traces: Vec<Arc<SortTrace>> = algorithm_replays
+        .iter()
+        .map(|replay| Arc::new(SortTrace::from_replay(replay, TRACE_MAX_ROWS)))
+        .collect();
+    let verifications: Vec<Arc<Verification>> = algorithm_replays
+        .iter()
+        .enumerate()
+        .map(|(panel_id, replay)| {
+            let last_step = replay.step_count().saturating_sub(1);
+            Arc::new(
+                Verification::for_algorithm(
+                    recorded_run.algorithm_kind(panel_id),
+                    recorded_run.replay_input(panel_id),
+                    replay.frame(last_step)
+                )
+            )
+        })
+        .collect();
+    let step_operations: Vec<StepOperations> = match note_sender {
+        Some(_) => algorithm_replays.iter().map(StepOperations::from_replay).collect(),
+        None => Vec::new(),
+    };
+
+    let total_algorithms = algorithm_replays.len();
+    let mut current_step_index: usize = 0;
+    let mut all_algorithms_finished = false;
+
+    {
+        let mut locked_state = shared_state.lock().unwrap();
+        locked_state.engine_state = EngineState::Running;
+        // kept after the run ends or is stopped, so it can still be saved
+        locked_state.recorded_run = Some(Arc::clone(&recorded_run));
+    }
+
+    while !stop_flag.load(Ordering::SeqCst) && !all_algorithms_finished {
+        let frame_start_time = Instant::now();
+        all_algorithms_finished = true;
+
+        let mut snapshots_for_frame: Vec<AlgorithmStateSnapshot> =
+            Vec::with_capacity(total_algorithms);
+        let mut notes_for_frame: Vec<NoteEvent> = Vec::new();
+
+        for (panel_id, (algorithm_replay, algorithm_trace)) in algorithm_replays
+            .iter()
+            .zip(algorithm_traces.iter())
+            .enumerate() {
+            let (frame_values, is_finished_for_algorithm) =
+                algorithm_replay.frame_at(current_step_index);
+            let stats_snapshot = algorithm_replay.stats_snapshot();
+
+            if !is_finished_for_algorithm {
+                all_algorithms_finished = false;
+            }
+
+            if let Some(algorithm_step_operations) = step_operations.get(panel_id) {
+                notes_for_frame.extend(
+                    algorithm_step_operations.notes_for_step(
+                        algorithm_replay,
+                        current_step_index,
+                        panel_id,
+                        frame_start_time,
+                        target_frame_duration
+                    )
+                );
+            }
+
+            let highlighted_indices = if
+                current_step_index > 0 &&
+                current_step_index < algorithm_replay.step_count()
+            {
+                changed_indices(
+                    algorithm_replay.frame(current_step_index - 1),
+                    &frame_values
+                )
+            } else {
+                Vec::new()
+            };
+
+            snapshots_for_frame.push(AlgorithmStateSnapshot {
+                panel_id,
+                algorithm_name: algorithm_replay.name().to_owned(),
+                current_values: frame_values,
+                is_finished: is_finished_for_algorithm,
+                stats: stats_snapshot,
+                current_step: current_step_index.min(
+                    algorithm_replay.step_count().saturating_sub(1)
+                ),
+                highlighted_indices,
+                current_marker: algorithm_replay.marker_at(current_step_index).map(str::to_owned),
+                trace: Arc::clone(algorithm_trace),
+                verification: is_finished_for_algorithm.then(|| Arc::clone(&verifications[panel_id])),
+            });
+        }
+
+        current_step_index = current_step_index.saturating_add(1);
+
+        if let Some(note_sender) = note_sender {
+            if !notes_for_frame.is_empty() {
+                // nobody listening is fine, e.g. when the audio device went away
+                let _ = note_sender.send(notes_for_frame);
+            }
+        }
+
+        {
+            let mut locked_state = shared_state.lock().unwrap();
+            locked_state.algorithm_states = snapshots_for_frame;
+            locked_state.engine_state = if all_algorithms_finished {
+                EngineState::Idle
+            } else {
+                EngineState::Running
+            };
+        }
+
+        let frame_elapsed_time = frame_start_time.elapsed();
+        if frame_elapsed_time < target_frame_duration {
+            thread::sleep(target_frame_duration - frame_elapsed_time);
+        }
+    }
+
+    let mut locked_state = shared_state.lock().unwrap();
+    locked_state.engine_state = EngineState::Idle;
+}
+
 impl Drop for EngineController {
     fn drop(&mut self) {
         self.stop();
@@ -0,0 +1,127 @@
+use std::fs::{ self, File };
+use std::io::BufWriter;
+use std::path::{ Path, PathBuf };
+
+use image::codecs::gif::{ GifEncoder, Repeat };
+use image::{ Delay, Frame, RgbaImage };
+
+use crate::export::raster::Canvas;
+use crate::export::{ ExportError, ExportFormat };
+
+// gif quantizer speed: 1 is best quality, 30 is fastest
+const GIF_ENCODER_SPEED: i32 = 10;
+
+// destination for rendered frames, one variant per output format
+pub enum FrameWriter {
+    Gif {
+        encoder: GifEncoder<BufWriter<File>>,
+        frame_delay: Delay,
+    },
+    Apng {
+        writer: png::Writer<BufWriter<File>>,
+    },
+    PngSequence {
+        directory: PathBuf,
+        next_frame_index: usize,
+    },
+}
+
+impl FrameWriter {
+    pub fn create(
+        format: ExportFormat,
+        output_path: &Path,
+        width: u32,
+        height: u32,
+        frame_count: usize,
+        frames_per_second: u32
+    ) -> Result<Self, ExportError> {
+        let frames_per_second = frames_per_second.max(1);
+
+        match format {
+            ExportFormat::Gif => {
+                let file = BufWriter::new(File::create(output_path)?);
+                let mut encoder = GifEncoder::new_with_speed(file, GIF_ENCODER_SPEED);
+                encoder.set_repeat(Repeat::Infinite)?;
+
+                Ok(FrameWriter::Gif {
+                    encoder,
+                    frame_delay: Delay::from_numer_denom_ms(1000, frames_per_second),
+                })
+            }
+            ExportFormat::Apng => {
+                let file = BufWriter::new(File::create(output_path)?);
+                let mut encoder = png::Encoder::new(file, width, height);
+                encoder.set_color(png::ColorType::Rgba);
+                encoder.set_depth(png::BitDepth::Eight);
+                encoder.set_animated(frame_count.max(1) as u32, 0)?;
+                encoder.set_frame_delay(1, frames_per_second.min(u16::MAX as u32) as u16)?;
+
+                Ok(FrameWriter::Apng {
+                    writer: encoder.write_header()?,
+                })
+            }
+            ExportFormat::PngSequence => {
+                fs::create_dir_all(output_path)?;
+
+                Ok(FrameWriter::PngSequence {
+                    directory: output_path.to_path_buf(),
+                    next_frame_index: 0,
+                })
+            }
+            ExportFormat::Svg => {
+                unreachable!("svg snapshots are written by write_svg_snapshot, not frame by frame")
+            }
+            ExportFormat::Midi => {
+                unreachable!("midi files are written by write_midi_file, not frame by frame")
+            }
+        }
+    }
+
+    pub fn write_frame(&mut self, canvas: &Canvas) -> Result<(), ExportError> {
+        let width = canvas.width() as u32;
+        let height = canvas.height() as u32;
+
+        match self {
+            FrameWriter::Gif { encoder, frame_delay } => {
+                let frame_image = RgbaImage::from_raw(width, height, canvas.as_rgba_bytes()).expect(
+                    "canvas byte length matches its size"
+                );
+                encoder.encode_frame(Frame::from_parts(frame_image, 0, 0, *frame_delay))?;
+            }
+            FrameWriter::Apng { writer } => {
+                writer.write_image_data(&canvas.as_rgba_bytes())?;
+            }
+            FrameWriter::PngSequence { directory, next_frame_index } => {
+                let frame_path = directory.join(format!("frame_{:05}.png", next_frame_index));
+                write_png(&frame_path, canvas)?;
+                *next_frame_index += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<(), ExportError> {
+        match self {
+            // the gif trailer is written when the encoder is dropped
+            FrameWriter::Gif { .. } => {}
+            FrameWriter::Apng { writer } => writer.finish()?,
+            FrameWriter::PngSequence { .. } => {}
+        }
+
+        Ok(())
+    }
+}
+
+pub fn write_png(path: &Path, canvas: &Canvas) -> Result<(), ExportError> {
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = png::Encoder::new(file, canvas.width() as u32, canvas.height() as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&canvas.as_rgba_bytes())?;
+    writer.finish()?;
+
+    Ok(())
+}
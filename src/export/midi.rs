@@ -0,0 +1,337 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{ Duration, Instant };
+
+use crate::sonification::{ notes_for_frames, NoteEvent, SonificationSettings };
+use crate::sorting_algorithms::SortingAlgorithmReplay;
+
+pub const TEMPO_RANGE_BPM: std::ops::RangeInclusive<u32> = 20..=400;
+pub const STEPS_PER_BEAT_RANGE: std::ops::RangeInclusive<u32> = 1..=32;
+
+// ticks per quarter note
+const TICKS_PER_BEAT: u32 = 480;
+// channel 10 plays drums in general midi, so algorithms skip it
+const PERCUSSION_CHANNEL: u8 = 9;
+
+// a note as it goes into the file, in ticks
+#[derive(Clone, Copy, Debug)]
+struct MidiNote {
+    start_tick: u32,
+    end_tick: u32,
+    key: u8,
+    velocity: u8,
+}
+
+// the notes the sonification would play, as a standard midi file: a tempo track, then one
+// track and channel per algorithm. exported frame i starts at beat i / steps_per_beat; pitch
+// follows the scale mapping rounded to the nearest key, velocity the bar height.
+// returns the number of notes written.
+pub fn write_midi_file(
+    output_path: &Path,
+    algorithm_replays: &[SortingAlgorithmReplay],
+    step_indices: &[usize],
+    tempo_bpm: u32,
+    steps_per_beat: u32,
+    sonification_settings: &SonificationSettings
+) -> io::Result<usize> {
+    let (midi_bytes, note_count) = midi_file_bytes(
+        algorithm_replays,
+        step_indices,
+        tempo_bpm,
+        steps_per_beat,
+        sonification_settings
+    );
+    fs::write(output_path, midi_bytes)?;
+    Ok(note_count)
+}
+
+pub fn midi_file_bytes(
+    algorithm_replays: &[SortingAlgorithmReplay],
+    step_indices: &[usize],
+    tempo_bpm: u32,
+    steps_per_beat: u32,
+    sonification_settings: &SonificationSettings
+) -> (Vec<u8>, usize) {
+    let tempo_bpm = tempo_bpm.clamp(*TEMPO_RANGE_BPM.start(), *TEMPO_RANGE_BPM.end());
+    let steps_per_beat = steps_per_beat.clamp(*STEPS_PER_BEAT_RANGE.start(), *STEPS_PER_BEAT_RANGE.end());
+    let seconds_per_beat = 60.0 / (tempo_bpm as f64);
+    let ticks_for = |duration: Duration| {
+        (duration.as_secs_f64() / seconds_per_beat * (TICKS_PER_BEAT as f64)).round() as u32
+    };
+
+    // notes are timed as instants; only their distance from this one matters
+    let origin = Instant::now();
+    let frame_duration = Duration::from_secs_f64(seconds_per_beat / (steps_per_beat as f64));
+    let note_events = notes_for_frames(
+        algorithm_replays,
+        step_indices,
+        origin,
+        frame_duration,
+        sonification_settings
+    );
+    let (_, note_length) = sonification_settings.envelope();
+    let note_length_ticks = ticks_for(note_length).max(1);
+
+    let mut tracks = vec![tempo_track(tempo_bpm)];
+    let mut note_count = 0;
+    for (panel_id, replay) in algorithm_replays.iter().enumerate() {
+        let midi_notes = panel_midi_notes(
+            note_events.iter().filter(|note_event| note_event.panel_id == panel_id),
+            |note_event| ticks_for(note_event.time.duration_since(origin)),
+            note_length_ticks,
+            sonification_settings
+        );
+        note_count += midi_notes.len();
+        tracks.push(algorithm_track(replay.name(), panel_channel(panel_id), &midi_notes));
+    }
+
+    let mut midi_bytes = Vec::new();
+    midi_bytes.extend_from_slice(b"MThd");
+    midi_bytes.extend_from_slice(&6u32.to_be_bytes());
+    // format 1: tracks play at the same time
+    midi_bytes.extend_from_slice(&1u16.to_be_bytes());
+    midi_bytes.extend_from_slice(&(tracks.len().min(u16::MAX as usize) as u16).to_be_bytes());
+    midi_bytes.extend_from_slice(&(TICKS_PER_BEAT as u16).to_be_bytes());
+    for track in tracks.iter().take(u16::MAX as usize) {
+        midi_bytes.extend_from_slice(b"MTrk");
+        midi_bytes.extend_from_slice(&(track.len() as u32).to_be_bytes());
+        midi_bytes.extend_from_slice(track);
+    }
+
+    (midi_bytes, note_count)
+}
+
+// channels 1 to 16 without the drum channel; the 16th algorithm and later share them again
+fn panel_channel(panel_id: usize) -> u8 {
+    let channel = (panel_id % 15) as u8;
+    if channel >= PERCUSSION_CHANNEL { channel + 1 } else { channel }
+}
+
+// a key sounds once at a time per channel: a repeated key ends the note before it, and two
+// notes on the same key and tick become one
+fn panel_midi_notes<'a>(
+    note_events: impl Iterator<Item = &'a NoteEvent>,
+    start_tick: impl Fn(&NoteEvent) -> u32,
+    note_length_ticks: u32,
+    sonification_settings: &SonificationSettings
+) -> Vec<MidiNote> {
+    let mut midi_notes: Vec<MidiNote> = Vec::new();
+    let mut last_note_for_key: [Option<usize>; 128] = [None; 128];
+
+    for note_event in note_events {
+        let note_start_tick = start_tick(note_event);
+        let key = midi_key(sonification_settings.frequency(note_event));
+        let velocity = (1.0 + note_event.normalized_value.clamp(0.0, 1.0) * 126.0).round() as u8;
+
+        if let Some(last_note_index) = last_note_for_key[key as usize] {
+            let last_note = &mut midi_notes[last_note_index];
+            if last_note.start_tick == note_start_tick {
+                last_note.velocity = last_note.velocity.max(velocity);
+                continue;
+            }
+            last_note.end_tick = last_note.end_tick.min(note_start_tick);
+        }
+
+        last_note_for_key[key as usize] = Some(midi_notes.len());
+        midi_notes.push(MidiNote {
+            start_tick: note_start_tick,
+            end_tick: note_start_tick + note_length_ticks,
+            key,
+            velocity,
+        });
+    }
+
+    midi_notes
+}
+
+// nearest key; a glissando is rounded to semitones
+fn midi_key(frequency: f32) -> u8 {
+    (69.0 + 12.0 * (frequency / 440.0).log2()).round().clamp(0.0, 127.0) as u8
+}
+
+fn tempo_track(tempo_bpm: u32) -> Vec<u8> {
+    let mut track = Vec::new();
+    push_text_event(&mut track, 0x03, "sortvis");
+
+    let microseconds_per_beat = 60_000_000 / tempo_bpm;
+    track.extend_from_slice(&[0x00, 0xff, 0x51, 0x03]);
+    track.extend_from_slice(&microseconds_per_beat.to_be_bytes()[1..]);
+    // 4/4, 24 clocks per metronome click, 8 thirty-seconds per quarter
+    track.extend_from_slice(&[0x00, 0xff, 0x58, 0x04, 0x04, 0x02, 0x18, 0x08]);
+
+    track.extend_from_slice(&[0x00, 0xff, 0x2f, 0x00]);
+    track
+}
+
+fn algorithm_track(algorithm_name: &str, channel: u8, midi_notes: &[MidiNote]) -> Vec<u8> {
+    let mut track = Vec::new();
+    push_text_event(&mut track, 0x03, algorithm_name);
+
+    // (tick, note off before note on, status, key, velocity)
+    let mut events: Vec<(u32, u8, u8, u8, u8)> = Vec::with_capacity(midi_notes.len() * 2);
+    for midi_note in midi_notes {
+        events.push((midi_note.start_tick, 1, 0x90 | channel, midi_note.key, midi_note.velocity));
+        events.push((midi_note.end_tick, 0, 0x80 | channel, midi_note.key, 0));
+    }
+    events.sort_by_key(|&(tick, order, _, _, _)| (tick, order));
+
+    let mut previous_tick = 0;
+    for (tick, _, status, key, velocity) in events {
+        push_variable_length(&mut track, tick - previous_tick);
+        track.extend_from_slice(&[status, key, velocity]);
+        previous_tick = tick;
+    }
+
+    track.extend_from_slice(&[0x00, 0xff, 0x2f, 0x00]);
+    track
+}
+
+fn push_text_event(track: &mut Vec<u8>, meta_type: u8, text: &str) {
+    track.extend_from_slice(&[0x00, 0xff, meta_type]);
+    push_variable_length(track, text.len() as u32);
+    track.extend_from_slice(text.as_bytes());
+}
+
+// seven bits per byte, most significant first, high bit set on all but the last
+fn push_variable_length(track: &mut Vec<u8>, value: u32) {
+    let mut groups = vec![(value & 0x7f) as u8];
+    let mut remaining = value >> 7;
+    while remaining > 0 {
+        groups.push(((remaining & 0x7f) as u8) | 0x80);
+        remaining >>= 7;
+    }
+    track.extend(groups.iter().rev());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::sorting_algorithms::SortingAlgorithmKind;
+
+    // reads a variable-length quantity at `position`, moving past it
+    fn read_variable_length(bytes: &[u8], position: &mut usize) -> u32 {
+        let mut value = 0u32;
+        loop {
+            let byte = bytes[*position];
+            *position += 1;
+            value = (value << 7) | u32::from(byte & 0x7f);
+            if byte & 0x80 == 0 {
+                return value;
+            }
+        }
+    }
+
+    fn read_u32(bytes: &[u8], position: usize) -> u32 {
+        u32::from_be_bytes([bytes[position], bytes[position + 1], bytes[position + 2], bytes[position + 3]])
+    }
+
+    #[test]
+    fn variable_length_values_use_seven_bits_per_byte() {
+        let cases: [(u32, &[u8]); 7] = [
+            (0, &[0x00]),
+            (0x40, &[0x40]),
+            (0x7f, &[0x7f]),
+            (0x80, &[0x81, 0x00]),
+            (0x2000, &[0xc0, 0x00]),
+            (0x3fff, &[0xff, 0x7f]),
+            (0x0fff_ffff, &[0xff, 0xff, 0xff, 0x7f])
+        ];
+
+        for (value, expected_bytes) in cases {
+            let mut bytes = Vec::new();
+            push_variable_length(&mut bytes, value);
+            assert_eq!(bytes, expected_bytes, "encoding of {:#x}", value);
+
+            let mut position = 0;
+            assert_eq!(read_variable_length(&bytes, &mut position), value);
+            assert_eq!(position, bytes.len());
+        }
+    }
+
+    #[test]
+    fn file_has_a_header_and_one_well_formed_track_per_algorithm() {
+        let values = vec![5, 3, 8, 1, 9, 2, 7, 4, 6, 0];
+        let quick_sort_replay = SortingAlgorithmReplay::new(SortingAlgorithmKind::QuickSort, &values);
+        // a name longer than one length byte can hold
+        let long_name = "Insertion Sort ".repeat(12);
+        let insertion_sort_replay = SortingAlgorithmReplay::new(SortingAlgorithmKind::InsertionSort, &values);
+        let renamed_replay = SortingAlgorithmReplay::from_operations(
+            long_name.clone(),
+            values.clone(),
+            insertion_sort_replay.operations().to_vec(),
+            insertion_sort_replay.stats().clone()
+        ).unwrap();
+        let replays = [quick_sort_replay, renamed_replay];
+
+        let last_step = replays
+            .iter()
+            .map(|replay| replay.step_count() - 1)
+            .max()
+            .unwrap();
+        let step_indices: Vec<usize> = (0..=last_step).collect();
+        let (midi_bytes, note_count) = midi_file_bytes(
+            &replays,
+            &step_indices,
+            120,
+            1,
+            &SonificationSettings::default()
+        );
+
+        assert_eq!(&midi_bytes[0..4], b"MThd");
+        assert_eq!(read_u32(&midi_bytes, 4), 6);
+        assert_eq!(&midi_bytes[8..10], &1u16.to_be_bytes());
+        assert_eq!(&midi_bytes[10..12], &3u16.to_be_bytes());
+        assert_eq!(&midi_bytes[12..14], &(TICKS_PER_BEAT as u16).to_be_bytes());
+
+        let mut position = 14;
+        let mut track_names = Vec::new();
+        let mut note_on_count = 0;
+        let mut microseconds_per_beat = None;
+        while position < midi_bytes.len() {
+            assert_eq!(&midi_bytes[position..position + 4], b"MTrk");
+            let track_end = position + 8 + (read_u32(&midi_bytes, position + 4) as usize);
+            position += 8;
+
+            let mut has_ended = false;
+            while position < track_end {
+                assert!(!has_ended, "events after the end of track");
+                read_variable_length(&midi_bytes, &mut position);
+                let status = midi_bytes[position];
+                position += 1;
+
+                if status == 0xff {
+                    let meta_type = midi_bytes[position];
+                    position += 1;
+                    let length = read_variable_length(&midi_bytes, &mut position) as usize;
+                    let data = &midi_bytes[position..position + length];
+                    match meta_type {
+                        0x03 => track_names.push(String::from_utf8(data.to_vec()).unwrap()),
+                        0x51 => {
+                            microseconds_per_beat = Some(u32::from_be_bytes([0, data[0], data[1], data[2]]));
+                        }
+                        0x2f => has_ended = true,
+                        _ => {}
+                    }
+                    position += length;
+                } else {
+                    assert!(matches!(status & 0xf0, 0x80 | 0x90), "unexpected status {:#x}", status);
+                    assert_ne!(status & 0x0f, PERCUSSION_CHANNEL);
+                    assert!(midi_bytes[position] < 0x80 && midi_bytes[position + 1] < 0x80);
+                    if status & 0xf0 == 0x90 {
+                        note_on_count += 1;
+                    }
+                    position += 2;
+                }
+            }
+            assert_eq!(position, track_end, "track length does not match its events");
+            assert!(has_ended, "track without an end of track event");
+        }
+
+        assert_eq!(track_names, ["sortvis", "Quick Sort", long_name.as_str()]);
+        assert_eq!(microseconds_per_beat, Some(500_000));
+        assert!(note_count > 0);
+        assert_eq!(note_on_count, note_count);
+    }
+}
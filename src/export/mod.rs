@@ -0,0 +1,462 @@
+pub mod encoders;
+pub mod midi;
+pub mod raster;
+pub mod svg;
+pub mod wav;
+
+use std::fmt;
+use std::path::{ Path, PathBuf };
+
+use ab_glyph::FontRef;
+
+use crate::chart::{ changed_indices, chart_shapes, ChartRect, ChartStyle };
+use crate::export::encoders::FrameWriter;
+use crate::export::midi::write_midi_file;
+use crate::export::raster::Canvas;
+use crate::export::svg::{ grid_svg, write_svg };
+use crate::export::wav::{ render_audio_track, write_wav, WAV_SAMPLE_RATE };
+use crate::input::InputDistribution;
+use crate::layout::GridLayout;
+use crate::palette::Palette;
+use crate::sonification::{ PanelVoice, SonificationSettings };
+use crate::sorting_algorithms::instance::AlgorithmInstance;
+use crate::sorting_algorithms::recorder::{ frame_bytes, SortRecorder, MAX_FRAME_BYTES };
+use crate::sorting_algorithms::SortingAlgorithmReplay;
+
+// panel geometry, mirrors the egui group frame and labels
+const PANEL_SPACING: f32 = 8.0;
+const PANEL_PADDING: f32 = 6.0;
+const LABEL_LINE_HEIGHT: f32 = 20.0;
+const CHART_MARGIN_TOP: f32 = 6.0;
+
+// frame widths and heights in pixels; every frame is held as width x height x 4 bytes, and
+// gif stores sizes in 16 bits
+pub const EXPORT_SIZE_RANGE_PX: std::ops::RangeInclusive<u32> = 16..=8192;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Gif,
+    Apng,
+    // numbered frame_00000.png files in a directory
+    PngSequence,
+    // a single vector snapshot of one step
+    Svg,
+    // the notes of the sonification, one track per algorithm
+    Midi,
+}
+
+impl ExportFormat {
+    pub fn from_cli_name(name: &str) -> Option<ExportFormat> {
+        match name.to_ascii_lowercase().as_str() {
+            "gif" => Some(ExportFormat::Gif),
+            "apng" => Some(ExportFormat::Apng),
+            "png" | "png-sequence" | "frames" => Some(ExportFormat::PngSequence),
+            "svg" => Some(ExportFormat::Svg),
+            "midi" | "mid" => Some(ExportFormat::Midi),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ExportOptions {
+    // one panel each; the same algorithm may appear more than once
+    pub algorithms: Vec<AlgorithmInstance>,
+    pub number_of_values: usize,
+    pub seed: Option<u64>,
+    pub input_distribution: InputDistribution,
+    pub chart_style: ChartStyle,
+    pub palette: Palette,
+    pub width: u32,
+    pub height: u32,
+    // render every k-th step (the final step is always included)
+    pub step_interval: usize,
+    pub frames_per_second: u32,
+    // step drawn by snapshot formats, clamped to the end of each replay
+    pub snapshot_step: usize,
+    pub format: ExportFormat,
+    pub output_path: PathBuf,
+    // wav file with the sound of the animation, for muxing with the frames
+    pub audio_path: Option<PathBuf>,
+    pub sonification: SonificationSettings,
+    // midi timing: each exported frame lasts 1 / steps_per_beat beats
+    pub tempo_bpm: u32,
+    pub steps_per_beat: u32,
+}
+
+#[derive(Debug)]
+pub enum ExportError {
+    NoAlgorithms,
+    // the replays of all algorithms together would not fit in memory
+    TooManyFrames {
+        algorithm_name: String,
+        frame_count: usize,
+        number_of_values: usize,
+    },
+    Io(std::io::Error),
+    Image(image::ImageError),
+    Png(png::EncodingError),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::NoAlgorithms => write!(formatter, "no algorithms selected"),
+            ExportError::TooManyFrames { algorithm_name, frame_count, number_of_values } =>
+                write!(
+                    formatter,
+                    "{} takes {} frames of {} values, which brings the run past {} MB; use fewer values or algorithms",
+                    algorithm_name,
+                    frame_count,
+                    number_of_values,
+                    MAX_FRAME_BYTES >> 20
+                ),
+            ExportError::Io(error) => write!(formatter, "i/o error: {}", error),
+            ExportError::Image(error) => write!(formatter, "gif encoding failed: {}", error),
+            ExportError::Png(error) => write!(formatter, "png encoding failed: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl From<std::io::Error> for ExportError {
+    fn from(error: std::io::Error) -> Self {
+        ExportError::Io(error)
+    }
+}
+
+impl From<image::ImageError> for ExportError {
+    fn from(error: image::ImageError) -> Self {
+        ExportError::Image(error)
+    }
+}
+
+impl From<png::EncodingError> for ExportError {
+    fn from(error: png::EncodingError) -> Self {
+        ExportError::Png(error)
+    }
+}
+
+// records every replay and writes the animation; returns the number of frames written, or
+// of notes for midi
+pub fn export_run(options: &ExportOptions) -> Result<usize, ExportError> {
+    if options.algorithms.is_empty() {
+        return Err(ExportError::NoAlgorithms);
+    }
+
+    // instances with their own input shape use the same seed as the rest of the run
+    let input_seed = options.seed.unwrap_or_else(rand::random);
+    let base_values = options.input_distribution.generate(options.number_of_values, Some(input_seed));
+    let instance_inputs: Vec<Vec<u32>> = options.algorithms
+        .iter()
+        .map(|algorithm_instance| {
+            match algorithm_instance.input_distribution {
+                Some(instance_distribution) => {
+                    instance_distribution.generate(options.number_of_values, Some(input_seed))
+                }
+                None => base_values.clone(),
+            }
+        })
+        .collect();
+
+    // every replay is held until the last frame is written, so the limit covers them together
+    let mut total_frame_bytes: usize = 0;
+    for (algorithm_instance, instance_values) in options.algorithms.iter().zip(&instance_inputs) {
+        let frame_count = counted_frames(algorithm_instance, instance_values);
+        total_frame_bytes = total_frame_bytes.saturating_add(frame_bytes(frame_count, instance_values.len()));
+        if total_frame_bytes > MAX_FRAME_BYTES {
+            return Err(ExportError::TooManyFrames {
+                algorithm_name: algorithm_instance.label(),
+                frame_count,
+                number_of_values: instance_values.len(),
+            });
+        }
+    }
+
+    let algorithm_replays: Vec<SortingAlgorithmReplay> = options.algorithms
+        .iter()
+        .zip(&instance_inputs)
+        .map(|(algorithm_instance, instance_values)| {
+            SortingAlgorithmReplay::for_instance(algorithm_instance, instance_values)
+        })
+        .collect();
+
+    if options.format == ExportFormat::Svg {
+        let panels: Vec<PanelSnapshot> = algorithm_replays
+            .iter()
+            .map(|replay| {
+                PanelSnapshot::from_replay(replay, options.snapshot_step, options.chart_style)
+            })
+            .collect();
+
+        write_svg_snapshot(
+            &options.output_path,
+            &panels,
+            &options.palette,
+            options.width,
+            options.height
+        )?;
+        return Ok(1);
+    }
+
+    let step_indices = exported_step_indices(&algorithm_replays, options.step_interval);
+
+    if options.format == ExportFormat::Midi {
+        let note_count = write_midi_file(
+            &options.output_path,
+            &algorithm_replays,
+            &step_indices,
+            options.tempo_bpm,
+            options.steps_per_beat,
+            &options.sonification
+        )?;
+        return Ok(note_count);
+    }
+
+    let mut frame_writer = FrameWriter::create(
+        options.format,
+        &options.output_path,
+        options.width,
+        options.height,
+        step_indices.len(),
+        options.frames_per_second
+    )?;
+
+    let font = export_font();
+    let mut canvas = Canvas::new(
+        options.width as usize,
+        options.height as usize,
+        options.palette.panel_background()
+    );
+
+    for step_index in step_indices.iter().copied() {
+        let panels: Vec<PanelSnapshot> = algorithm_replays
+            .iter()
+            .map(|replay| PanelSnapshot::from_replay(replay, step_index, options.chart_style))
+            .collect();
+
+        render_grid_frame(&mut canvas, &font, &panels, &options.palette);
+        frame_writer.write_frame(&canvas)?;
+    }
+
+    frame_writer.finish()?;
+
+    if let Some(audio_path) = &options.audio_path {
+        // panels sound from their column of the exported grid, as in the window
+        let grid_layout = GridLayout::compute(
+            options.width as f32,
+            options.height as f32,
+            algorithm_replays.len()
+        );
+        let panel_voices: Vec<PanelVoice> = (0..algorithm_replays.len())
+            .map(|panel_index| {
+                let (column_index, _) = grid_layout.cell_position(panel_index);
+                PanelVoice::for_grid_column(column_index, grid_layout.column_count, true)
+            })
+            .collect();
+
+        let samples = render_audio_track(
+            &algorithm_replays,
+            &step_indices,
+            options.frames_per_second,
+            &panel_voices,
+            &options.sonification
+        );
+        write_wav(audio_path, &samples, WAV_SAMPLE_RATE)?;
+    }
+
+    Ok(step_indices.len())
+}
+
+// frames the replay of an instance will keep, from a run that only counts; at most two more
+// than it records, since the input and the sorted result may be frames of their own
+fn counted_frames(algorithm_instance: &AlgorithmInstance, values: &[u32]) -> usize {
+    let mut recorder = SortRecorder::counting_only();
+    algorithm_instance.record(values, &mut recorder);
+    let operation_counts = recorder.operation_counts();
+    (operation_counts.swaps + operation_counts.writes + 2) as usize
+}
+
+// steps 0, k, 2k, ... plus the last step of the longest replay
+pub fn exported_step_indices(
+    algorithm_replays: &[SortingAlgorithmReplay],
+    step_interval: usize
+) -> Vec<usize> {
+    let last_step = algorithm_replays
+        .iter()
+        .map(|replay| replay.step_count().saturating_sub(1))
+        .max()
+        .unwrap_or(0);
+
+    let mut step_indices: Vec<usize> = (0..=last_step).step_by(step_interval.max(1)).collect();
+    if step_indices.last() != Some(&last_step) {
+        step_indices.push(last_step);
+    }
+    step_indices
+}
+
+// writes the given panels as one svg figure, shared by the cli and the window
+pub fn write_svg_snapshot(
+    output_path: &Path,
+    panels: &[PanelSnapshot],
+    palette: &Palette,
+    width: u32,
+    height: u32
+) -> Result<(), ExportError> {
+    let font = export_font();
+    let svg = grid_svg(&font, panels, palette, width, height);
+    write_svg(output_path, &svg)
+}
+
+pub fn export_font() -> FontRef<'static> {
+    FontRef::try_from_slice(epaint_default_fonts::UBUNTU_LIGHT).expect(
+        "bundled egui font is valid"
+    )
+}
+
+// everything needed to draw one panel, wherever the data came from
+#[derive(Clone, Debug)]
+pub struct PanelSnapshot {
+    pub title: String,
+    pub stats_text: String,
+    pub values: Vec<u32>,
+    pub highlighted_indices: Vec<usize>,
+    pub chart_style: ChartStyle,
+    pub is_finished: bool,
+}
+
+impl PanelSnapshot {
+    pub fn from_replay(
+        replay: &SortingAlgorithmReplay,
+        step_index: usize,
+        chart_style: ChartStyle
+    ) -> Self {
+        let step_index = step_index.min(replay.step_count().saturating_sub(1));
+        let is_finished = step_index + 1 >= replay.step_count();
+        let highlighted_indices = if step_index > 0 && !is_finished {
+            changed_indices(replay.frame(step_index - 1), replay.frame(step_index))
+        } else {
+            Vec::new()
+        };
+
+        Self {
+            title: panel_title(replay.name(), is_finished),
+            stats_text: replay.stats_snapshot().summary_line(),
+            values: replay.frame(step_index).to_vec(),
+            highlighted_indices,
+            chart_style,
+            is_finished,
+        }
+    }
+}
+
+pub fn panel_title(algorithm_name: &str, is_finished: bool) -> String {
+    if is_finished {
+        format!("{} (finished)", algorithm_name)
+    } else {
+        algorithm_name.to_owned()
+    }
+}
+
+// where the pieces of one panel go inside an exported image
+#[derive(Clone, Copy, Debug)]
+pub struct PanelGeometry {
+    pub panel_rect: ChartRect,
+    pub title_position: [f32; 2],
+    pub stats_position: [f32; 2],
+    pub chart_rect: ChartRect,
+}
+
+pub const TITLE_FONT_SIZE: f32 = 14.0;
+pub const STATS_FONT_SIZE: f32 = 12.0;
+
+// panel rects for a fixed-size image, laid out like the live window
+pub fn grid_panel_geometry(width: f32, height: f32, panel_count: usize) -> Vec<PanelGeometry> {
+    let grid_layout = GridLayout::compute(width, height, panel_count);
+
+    // the window scrolls when cells hit their minimum size; a fixed-size image shrinks them instead
+    let cell_width = grid_layout.cell_width.min(width / (grid_layout.column_count as f32));
+    let cell_height = grid_layout.cell_height.min(height / (grid_layout.row_count as f32));
+
+    (0..panel_count)
+        .map(|panel_index| {
+            let (column, row) = grid_layout.cell_position(panel_index);
+            let panel_rect = ChartRect {
+                left: (column as f32) * cell_width + PANEL_SPACING * 0.5,
+                top: (row as f32) * cell_height + PANEL_SPACING * 0.5,
+                right: ((column + 1) as f32) * cell_width - PANEL_SPACING * 0.5,
+                bottom: ((row + 1) as f32) * cell_height - PANEL_SPACING * 0.5,
+            };
+
+            let text_left = panel_rect.left + PANEL_PADDING;
+            let title_top = panel_rect.top + PANEL_PADDING;
+
+            PanelGeometry {
+                panel_rect,
+                title_position: [text_left, title_top],
+                stats_position: [text_left, title_top + LABEL_LINE_HEIGHT],
+                chart_rect: ChartRect {
+                    left: panel_rect.left + PANEL_PADDING,
+                    top: title_top + LABEL_LINE_HEIGHT * 2.0 + CHART_MARGIN_TOP,
+                    right: panel_rect.right - PANEL_PADDING,
+                    bottom: panel_rect.bottom - PANEL_PADDING,
+                },
+            }
+        })
+        .collect()
+}
+
+// draws every panel of the grid into the canvas
+pub fn render_grid_frame(
+    canvas: &mut Canvas,
+    font: &FontRef<'_>,
+    panels: &[PanelSnapshot],
+    palette: &Palette
+) {
+    canvas.fill(palette.panel_background());
+
+    let panel_geometry = grid_panel_geometry(
+        canvas.width() as f32,
+        canvas.height() as f32,
+        panels.len()
+    );
+
+    for (panel, geometry) in panels.iter().zip(panel_geometry) {
+        let [title_left, title_top] = geometry.title_position;
+        let [stats_left, stats_top] = geometry.stats_position;
+        canvas.draw_text(
+            font,
+            title_left,
+            title_top,
+            TITLE_FONT_SIZE,
+            &panel.title,
+            palette.text_color()
+        );
+        canvas.draw_text(
+            font,
+            stats_left,
+            stats_top,
+            STATS_FONT_SIZE,
+            &panel.stats_text,
+            palette.text_color()
+        );
+
+        let chart_rect = geometry.chart_rect;
+        if chart_rect.width() <= 0.0 || chart_rect.height() <= 0.0 {
+            continue;
+        }
+
+        canvas.fill_rect(chart_rect, palette.chart_background(panel.is_finished));
+        for shape in chart_shapes(
+            panel.chart_style,
+            chart_rect,
+            &panel.values,
+            &panel.highlighted_indices,
+            palette,
+            panel.is_finished
+        ) {
+            canvas.draw_shape(&shape);
+        }
+    }
+}
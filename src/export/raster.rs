@@ -0,0 +1,218 @@
+use ab_glyph::{ point, Font, FontRef, PxScale, ScaleFont };
+
+use crate::chart::{ ChartRect, ChartShape };
+use crate::palette::Rgba8;
+
+// samples per axis when estimating triangle coverage
+const TRIANGLE_SUPERSAMPLING: usize = 4;
+
+// opaque rgba image the exporters draw frames into
+pub struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgba8>,
+}
+
+impl Canvas {
+    pub fn new(width: usize, height: usize, background: Rgba8) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn as_rgba_bytes(&self) -> Vec<u8> {
+        self.pixels.iter().flatten().copied().collect()
+    }
+
+    pub fn fill(&mut self, color: Rgba8) {
+        self.pixels.fill(color);
+    }
+
+    pub fn draw_shape(&mut self, shape: &ChartShape) {
+        match shape {
+            ChartShape::Rect { rect, color, .. } => self.fill_rect(*rect, *color),
+            ChartShape::Circle { center, radius, color } => {
+                self.fill_circle(*center, *radius, *color);
+            }
+            ChartShape::Triangle { points, color } => self.fill_triangle(*points, *color),
+        }
+    }
+
+    // partial pixels at the edges are blended by covered area, so sub-pixel bars stay visible
+    pub fn fill_rect(&mut self, rect: ChartRect, color: Rgba8) {
+        let Some((first_column, last_column, first_row, last_row)) = self.pixel_span(rect) else {
+            return;
+        };
+
+        for row in first_row..=last_row {
+            let row_coverage = span_overlap(rect.top, rect.bottom, row as f32);
+            for column in first_column..=last_column {
+                let column_coverage = span_overlap(rect.left, rect.right, column as f32);
+                self.blend_pixel(column, row, color, row_coverage * column_coverage);
+            }
+        }
+    }
+
+    pub fn fill_circle(&mut self, center: [f32; 2], radius: f32, color: Rgba8) {
+        let bounds = ChartRect {
+            left: center[0] - radius - 1.0,
+            top: center[1] - radius - 1.0,
+            right: center[0] + radius + 1.0,
+            bottom: center[1] + radius + 1.0,
+        };
+        let Some((first_column, last_column, first_row, last_row)) = self.pixel_span(bounds) else {
+            return;
+        };
+
+        for row in first_row..=last_row {
+            for column in first_column..=last_column {
+                let delta_x = (column as f32) + 0.5 - center[0];
+                let delta_y = (row as f32) + 0.5 - center[1];
+                let distance = (delta_x * delta_x + delta_y * delta_y).sqrt();
+                let coverage = (radius + 0.5 - distance).clamp(0.0, 1.0);
+                self.blend_pixel(column, row, color, coverage);
+            }
+        }
+    }
+
+    pub fn fill_triangle(&mut self, points: [[f32; 2]; 3], color: Rgba8) {
+        let bounds = ChartRect {
+            left: points.iter().map(|point| point[0]).fold(f32::INFINITY, f32::min),
+            top: points.iter().map(|point| point[1]).fold(f32::INFINITY, f32::min),
+            right: points.iter().map(|point| point[0]).fold(f32::NEG_INFINITY, f32::max),
+            bottom: points.iter().map(|point| point[1]).fold(f32::NEG_INFINITY, f32::max),
+        };
+        let Some((first_column, last_column, first_row, last_row)) = self.pixel_span(bounds) else {
+            return;
+        };
+
+        let sample_step = 1.0 / (TRIANGLE_SUPERSAMPLING as f32);
+        let samples_per_pixel = (TRIANGLE_SUPERSAMPLING * TRIANGLE_SUPERSAMPLING) as f32;
+
+        for row in first_row..=last_row {
+            for column in first_column..=last_column {
+                let mut covered_samples = 0;
+                for sample_row in 0..TRIANGLE_SUPERSAMPLING {
+                    for sample_column in 0..TRIANGLE_SUPERSAMPLING {
+                        let sample_point = [
+                            (column as f32) + ((sample_column as f32) + 0.5) * sample_step,
+                            (row as f32) + ((sample_row as f32) + 0.5) * sample_step,
+                        ];
+                        if triangle_contains(points, sample_point) {
+                            covered_samples += 1;
+                        }
+                    }
+                }
+
+                let coverage = (covered_samples as f32) / samples_per_pixel;
+                self.blend_pixel(column, row, color, coverage);
+            }
+        }
+    }
+
+    // draws one line of text with its top-left corner at (left, top)
+    pub fn draw_text(
+        &mut self,
+        font: &FontRef<'_>,
+        left: f32,
+        top: f32,
+        font_size: f32,
+        text: &str,
+        color: Rgba8
+    ) {
+        let scaled_font = font.as_scaled(PxScale::from(font_size));
+        let baseline = top + scaled_font.ascent();
+
+        let mut caret = left;
+        let mut previous_glyph = None;
+        for character in text.chars() {
+            let glyph_id = scaled_font.glyph_id(character);
+            if let Some(previous_glyph) = previous_glyph {
+                caret += scaled_font.kern(previous_glyph, glyph_id);
+            }
+
+            let glyph = glyph_id.with_scale_and_position(font_size, point(caret, baseline));
+            caret += scaled_font.h_advance(glyph_id);
+            previous_glyph = Some(glyph_id);
+
+            let Some(outlined_glyph) = font.outline_glyph(glyph) else {
+                continue;
+            };
+
+            let glyph_bounds = outlined_glyph.px_bounds();
+            outlined_glyph.draw(|glyph_column, glyph_row, coverage| {
+                let column = (glyph_bounds.min.x as i64) + (glyph_column as i64);
+                let row = (glyph_bounds.min.y as i64) + (glyph_row as i64);
+                if column >= 0 && row >= 0 {
+                    self.blend_pixel(column as usize, row as usize, color, coverage);
+                }
+            });
+        }
+    }
+
+    // inclusive pixel range touched by a rect, clipped to the canvas
+    fn pixel_span(&self, rect: ChartRect) -> Option<(usize, usize, usize, usize)> {
+        if self.width == 0 || self.height == 0 {
+            return None;
+        }
+
+        let left = rect.left.max(0.0);
+        let top = rect.top.max(0.0);
+        let right = rect.right.min(self.width as f32);
+        let bottom = rect.bottom.min(self.height as f32);
+        if right <= left || bottom <= top {
+            return None;
+        }
+
+        Some((
+            left.floor() as usize,
+            ((right.ceil() as usize).max(1) - 1).min(self.width - 1),
+            top.floor() as usize,
+            ((bottom.ceil() as usize).max(1) - 1).min(self.height - 1),
+        ))
+    }
+
+    fn blend_pixel(&mut self, column: usize, row: usize, color: Rgba8, coverage: f32) {
+        if column >= self.width || row >= self.height || coverage <= 0.0 {
+            return;
+        }
+
+        let alpha = coverage.min(1.0) * ((color[3] as f32) / 255.0);
+        let pixel = &mut self.pixels[row * self.width + column];
+        for channel in 0..3 {
+            let blended = (color[channel] as f32) * alpha + (pixel[channel] as f32) * (1.0 - alpha);
+            pixel[channel] = blended.round().clamp(0.0, 255.0) as u8;
+        }
+        pixel[3] = 255;
+    }
+}
+
+// how much of the unit pixel span [start, start + 1) lies inside [low, high]
+fn span_overlap(low: f32, high: f32, start: f32) -> f32 {
+    (high.min(start + 1.0) - low.max(start)).clamp(0.0, 1.0)
+}
+
+fn triangle_contains(points: [[f32; 2]; 3], sample_point: [f32; 2]) -> bool {
+    let edge = |from: [f32; 2], to: [f32; 2]| {
+        (to[0] - from[0]) * (sample_point[1] - from[1]) -
+            (to[1] - from[1]) * (sample_point[0] - from[0])
+    };
+
+    let first = edge(points[0], points[1]);
+    let second = edge(points[1], points[2]);
+    let third = edge(points[2], points[0]);
+
+    // accept either winding order
+    (first >= 0.0 && second >= 0.0 && third >= 0.0) ||
+        (first <= 0.0 && second <= 0.0 && third <= 0.0)
+}
@@ -0,0 +1,207 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use ab_glyph::{ Font, FontRef, PxScale, ScaleFont };
+
+use crate::chart::{ chart_shapes, ChartRect, ChartShape };
+use crate::export::{
+    grid_panel_geometry,
+    ExportError,
+    PanelSnapshot,
+    STATS_FONT_SIZE,
+    TITLE_FONT_SIZE,
+};
+use crate::palette::{ Palette, Rgba8 };
+
+// the exported text names the bundled font first so viewers that have it match the raster output
+const SVG_FONT_FAMILY: &str = "Ubuntu, 'DejaVu Sans', sans-serif";
+
+// one vector figure of the grid at a single step, laid out like the raster export
+pub fn grid_svg(
+    font: &FontRef<'_>,
+    panels: &[PanelSnapshot],
+    palette: &Palette,
+    width: u32,
+    height: u32
+) -> String {
+    let mut svg = String::new();
+
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">"
+    );
+    let _ = writeln!(
+        svg,
+        "<rect x=\"0\" y=\"0\" width=\"{}\" height=\"{}\"{}/>",
+        width,
+        height,
+        fill_attributes(palette.panel_background())
+    );
+
+    let panel_geometry = grid_panel_geometry(width as f32, height as f32, panels.len());
+
+    for (panel_index, (panel, geometry)) in panels.iter().zip(panel_geometry).enumerate() {
+        let panel_rect = geometry.panel_rect;
+        let clip_id = format!("panel-clip-{}", panel_index);
+
+        let _ = writeln!(svg, "<g id=\"panel-{}\">", panel_index);
+        let _ = writeln!(
+            svg,
+            "<clipPath id=\"{}\"><rect{}/></clipPath>",
+            clip_id,
+            rect_attributes(panel_rect)
+        );
+
+        let [title_left, title_top] = geometry.title_position;
+        let [stats_left, stats_top] = geometry.stats_position;
+        write_text(&mut svg, font, title_left, title_top, TITLE_FONT_SIZE, &panel.title, palette);
+        write_text(
+            &mut svg,
+            font,
+            stats_left,
+            stats_top,
+            STATS_FONT_SIZE,
+            &panel.stats_text,
+            palette
+        );
+
+        let chart_rect = geometry.chart_rect;
+        if chart_rect.width() > 0.0 && chart_rect.height() > 0.0 {
+            let _ = writeln!(svg, "<g clip-path=\"url(#{})\">", clip_id);
+            let _ = writeln!(
+                svg,
+                "<rect{}{}/>",
+                rect_attributes(chart_rect),
+                fill_attributes(palette.chart_background(panel.is_finished))
+            );
+
+            for shape in chart_shapes(
+                panel.chart_style,
+                chart_rect,
+                &panel.values,
+                &panel.highlighted_indices,
+                palette,
+                panel.is_finished
+            ) {
+                write_shape(&mut svg, &shape);
+            }
+
+            svg.push_str("</g>\n");
+        }
+
+        svg.push_str("</g>\n");
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+pub fn write_svg(output_path: &Path, svg: &str) -> Result<(), ExportError> {
+    fs::write(output_path, svg)?;
+    Ok(())
+}
+
+fn write_shape(svg: &mut String, shape: &ChartShape) {
+    match shape {
+        ChartShape::Rect { rect, corner_radius, color } => {
+            let rounding = if *corner_radius > 0.0 {
+                // same clamp egui applies to thin bars
+                let radius = corner_radius.min(rect.width() * 0.5).min(rect.height() * 0.5);
+                format!(" rx=\"{:.2}\"", radius.max(0.0))
+            } else {
+                String::new()
+            };
+
+            let _ = writeln!(svg, "<rect{}{}{}/>", rect_attributes(*rect), rounding, fill_attributes(*color));
+        }
+        ChartShape::Circle { center, radius, color } => {
+            let _ = writeln!(
+                svg,
+                "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{:.2}\"{}/>",
+                center[0],
+                center[1],
+                radius,
+                fill_attributes(*color)
+            );
+        }
+        ChartShape::Triangle { points, color } => {
+            let [first, second, third] = points;
+            let _ = writeln!(
+                svg,
+                "<polygon points=\"{:.2},{:.2} {:.2},{:.2} {:.2},{:.2}\"{}/>",
+                first[0],
+                first[1],
+                second[0],
+                second[1],
+                third[0],
+                third[1],
+                fill_attributes(*color)
+            );
+        }
+    }
+}
+
+fn write_text(
+    svg: &mut String,
+    font: &FontRef<'_>,
+    left: f32,
+    top: f32,
+    font_size: f32,
+    text: &str,
+    palette: &Palette
+) {
+    // svg positions text by its baseline; the raster export positions it by the top edge
+    let baseline = top + font.as_scaled(PxScale::from(font_size)).ascent();
+
+    let _ = writeln!(
+        svg,
+        "<text x=\"{:.2}\" y=\"{:.2}\" font-family=\"{}\" font-size=\"{}\"{}>{}</text>",
+        left,
+        baseline,
+        SVG_FONT_FAMILY,
+        font_size,
+        fill_attributes(palette.text_color()),
+        escape_xml(text)
+    );
+}
+
+fn rect_attributes(rect: ChartRect) -> String {
+    format!(
+        " x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\"",
+        rect.left,
+        rect.top,
+        rect.width().max(0.0),
+        rect.height().max(0.0)
+    )
+}
+
+fn fill_attributes(color: Rgba8) -> String {
+    let [red, green, blue, alpha] = color;
+    if alpha == 255 {
+        format!(" fill=\"#{:02x}{:02x}{:02x}\"", red, green, blue)
+    } else {
+        format!(
+            " fill=\"#{:02x}{:02x}{:02x}\" fill-opacity=\"{:.3}\"",
+            red,
+            green,
+            blue,
+            (alpha as f32) / 255.0
+        )
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            other => escaped.push(other),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,102 @@
+use std::fs::File;
+use std::io::{ self, BufWriter, Write };
+use std::path::Path;
+use std::time::{ Duration, Instant };
+
+use crate::sonification::{ notes_for_frames, PanelVoice, SonificationSettings, Voice, VoicePool };
+use crate::sorting_algorithms::SortingAlgorithmReplay;
+
+pub const WAV_SAMPLE_RATE: u32 = 44_100;
+
+// the sound of an exported animation, as stereo frames at WAV_SAMPLE_RATE; see
+// notes_for_frames for when each step sounds. nothing depends on the clock, so the same run
+// always renders the same samples.
+pub fn render_audio_track(
+    algorithm_replays: &[SortingAlgorithmReplay],
+    step_indices: &[usize],
+    frames_per_second: u32,
+    panel_voices: &[PanelVoice],
+    sonification_settings: &SonificationSettings
+) -> Vec<[f32; 2]> {
+    // notes are timed as instants; only their distance from this one matters
+    let origin = Instant::now();
+    let frame_duration = Duration::from_secs_f64(1.0 / (frames_per_second.max(1) as f64));
+
+    let mut scheduled_voices: Vec<(u64, Voice)> = notes_for_frames(
+        algorithm_replays,
+        step_indices,
+        origin,
+        frame_duration,
+        sonification_settings
+    )
+        .into_iter()
+        .filter_map(|note_event| {
+            let panel_voice = panel_voices.get(note_event.panel_id).copied().unwrap_or_default();
+            panel_voice.is_audible.then(|| {
+                let start_frame = duration_frames(note_event.time.duration_since(origin));
+                (start_frame, Voice::new(&note_event, &panel_voice, sonification_settings, WAV_SAMPLE_RATE))
+            })
+        })
+        .collect();
+
+    // latest start last, so the next due voice is popped from the end
+    scheduled_voices.sort_by_key(|(start_frame, _)| std::cmp::Reverse(*start_frame));
+
+    let animation_frames = duration_frames(frame_duration * (step_indices.len() as u32));
+    let mut voice_pool = VoicePool::new();
+    let mut samples: Vec<[f32; 2]> = Vec::with_capacity(animation_frames as usize);
+    let mut frame_position: u64 = 0;
+
+    // the last notes ring out past the end of the animation
+    while frame_position < animation_frames || !scheduled_voices.is_empty() || !voice_pool.is_silent() {
+        while
+            let Some((_, voice)) = scheduled_voices.pop_if(|(start_frame, _)| *start_frame <= frame_position)
+        {
+            voice_pool.start(voice);
+        }
+        samples.push(voice_pool.next_frame());
+        frame_position += 1;
+    }
+
+    samples
+}
+
+// 16-bit stereo pcm, the format every video tool accepts
+pub fn write_wav(output_path: &Path, samples: &[[f32; 2]], sample_rate: u32) -> io::Result<()> {
+    let channel_count: u16 = 2;
+    let bytes_per_sample: u16 = 2;
+    let block_align = channel_count * bytes_per_sample;
+    let data_length = u32
+        ::try_from(samples.len() * (block_align as usize))
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "audio track too long for a wav file"))?;
+
+    let mut writer = BufWriter::new(File::create(output_path)?);
+    writer.write_all(b"RIFF")?;
+    writer.write_all(&(36 + data_length).to_le_bytes())?;
+    writer.write_all(b"WAVE")?;
+
+    writer.write_all(b"fmt ")?;
+    writer.write_all(&16u32.to_le_bytes())?;
+    // 1 = integer pcm
+    writer.write_all(&1u16.to_le_bytes())?;
+    writer.write_all(&channel_count.to_le_bytes())?;
+    writer.write_all(&sample_rate.to_le_bytes())?;
+    writer.write_all(&(sample_rate * (block_align as u32)).to_le_bytes())?;
+    writer.write_all(&block_align.to_le_bytes())?;
+    writer.write_all(&(bytes_per_sample * 8).to_le_bytes())?;
+
+    writer.write_all(b"data")?;
+    writer.write_all(&data_length.to_le_bytes())?;
+    for frame in samples {
+        for sample in frame {
+            let pcm_sample = (sample.clamp(-1.0, 1.0) * (i16::MAX as f32)).round() as i16;
+            writer.write_all(&pcm_sample.to_le_bytes())?;
+        }
+    }
+
+    writer.flush()
+}
+
+fn duration_frames(duration: Duration) -> u64 {
+    (duration.as_secs_f64() * (WAV_SAMPLE_RATE as f64)) as u64
+}
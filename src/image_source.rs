@@ -0,0 +1,119 @@
+use std::path::Path;
+
+use image::imageops::FilterType;
+
+// larger images are scaled down so every frame stays cheap to rebuild
+pub const MAXIMUM_IMAGE_SIDE: u32 = 512;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ImageSliceMode {
+    // every pixel column is one element
+    #[default]
+    Columns,
+    // every scanline is one element
+    Rows,
+}
+
+impl ImageSliceMode {
+    pub const ALL: [ImageSliceMode; 2] = [ImageSliceMode::Columns, ImageSliceMode::Rows];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ImageSliceMode::Columns => "Pixel columns",
+            ImageSliceMode::Rows => "Scanlines",
+        }
+    }
+
+    pub fn cli_name(&self) -> &'static str {
+        match self {
+            ImageSliceMode::Columns => "columns",
+            ImageSliceMode::Rows => "rows",
+        }
+    }
+
+    pub fn from_cli_name(name: &str) -> Option<ImageSliceMode> {
+        ImageSliceMode::ALL
+            .into_iter()
+            .find(|slice_mode| slice_mode.cli_name().eq_ignore_ascii_case(name))
+    }
+}
+
+// rgba image whose slices are sorted back into place; an element's value is its original slice index
+#[derive(Clone, Debug)]
+pub struct SourceImage {
+    width: usize,
+    height: usize,
+    pixels: Vec<[u8; 4]>,
+}
+
+impl SourceImage {
+    pub fn load(path: &Path) -> Result<Self, image::ImageError> {
+        let mut decoded_image = image::open(path)?;
+
+        if decoded_image.width() > MAXIMUM_IMAGE_SIDE || decoded_image.height() > MAXIMUM_IMAGE_SIDE {
+            // keeps the aspect ratio
+            decoded_image = decoded_image.resize(
+                MAXIMUM_IMAGE_SIDE,
+                MAXIMUM_IMAGE_SIDE,
+                FilterType::Triangle
+            );
+        }
+
+        let rgba_image = decoded_image.to_rgba8();
+        let width = rgba_image.width() as usize;
+        let height = rgba_image.height() as usize;
+        let pixels = rgba_image.pixels().map(|pixel| pixel.0).collect();
+
+        Ok(Self { width, height, pixels })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn slice_count(&self, slice_mode: ImageSliceMode) -> usize {
+        match slice_mode {
+            ImageSliceMode::Columns => self.width,
+            ImageSliceMode::Rows => self.height,
+        }
+    }
+
+    // rebuild the picture with slice `values[i]` drawn at position i
+    pub fn render_permuted(&self, values: &[u32], slice_mode: ImageSliceMode) -> Vec<[u8; 4]> {
+        let mut permuted_pixels: Vec<[u8; 4]> = Vec::with_capacity(self.pixels.len());
+        let slice_count = self.slice_count(slice_mode);
+
+        for pixel_row in 0..self.height {
+            for pixel_column in 0..self.width {
+                let (slice_position, offset) = match slice_mode {
+                    ImageSliceMode::Columns => (pixel_column, pixel_row),
+                    ImageSliceMode::Rows => (pixel_row, pixel_column),
+                };
+
+                // positions beyond the array (or out-of-range keys) stay transparent
+                let source_slice = values
+                    .get(slice_position)
+                    .map(|&value| value as usize)
+                    .filter(|&slice_index| slice_index < slice_count);
+
+                let pixel = match (source_slice, slice_mode) {
+                    (Some(slice_index), ImageSliceMode::Columns) => {
+                        self.pixels[offset * self.width + slice_index]
+                    }
+                    (Some(slice_index), ImageSliceMode::Rows) => {
+                        self.pixels[slice_index * self.width + offset]
+                    }
+                    (None, _) => [0, 0, 0, 0],
+                };
+
+                permuted_pixels.push(pixel);
+            }
+        }
+
+        permuted_pixels
+    }
+}
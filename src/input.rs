@@ -0,0 +1,130 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{ rng, Rng, SeedableRng };
+
+// distinct values in the few-unique input
+const FEW_UNIQUE_VALUE_COUNT: usize = 8;
+
+// shape of the array every algorithm starts from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum InputDistribution {
+    #[default]
+    Random,
+    Sorted,
+    Reversed,
+    // sorted, then a few short-range swaps
+    NearlySorted,
+    FewUnique,
+}
+
+impl InputDistribution {
+    pub const ALL: [InputDistribution; 5] = [
+        InputDistribution::Random,
+        InputDistribution::Sorted,
+        InputDistribution::Reversed,
+        InputDistribution::NearlySorted,
+        InputDistribution::FewUnique,
+    ];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            InputDistribution::Random => "Random",
+            InputDistribution::Sorted => "Sorted",
+            InputDistribution::Reversed => "Reversed",
+            InputDistribution::NearlySorted => "Nearly sorted",
+            InputDistribution::FewUnique => "Few unique",
+        }
+    }
+
+    pub fn cli_name(&self) -> &'static str {
+        match self {
+            InputDistribution::Random => "random",
+            InputDistribution::Sorted => "sorted",
+            InputDistribution::Reversed => "reversed",
+            InputDistribution::NearlySorted => "nearly-sorted",
+            InputDistribution::FewUnique => "few-unique",
+        }
+    }
+
+    pub fn from_cli_name(name: &str) -> Option<InputDistribution> {
+        InputDistribution::ALL
+            .into_iter()
+            .find(|distribution| distribution.cli_name().eq_ignore_ascii_case(name))
+    }
+
+    // values drawn from 0..n; a seed makes the random parts reproducible
+    pub fn generate(&self, number_of_values: usize, seed: Option<u64>) -> Vec<u32> {
+        let mut random_generator = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_rng(&mut rng()),
+        };
+
+        let mut values: Vec<u32> = (0..number_of_values as u32).collect();
+
+        match self {
+            InputDistribution::Random => {
+                values.shuffle(&mut random_generator);
+            }
+            InputDistribution::Sorted => {}
+            InputDistribution::Reversed => {
+                values.reverse();
+            }
+            InputDistribution::NearlySorted => {
+                // about 5% of the elements move, none of them far
+                let swap_count = (number_of_values / 20).max(1);
+                // at most half the array, so a partner past the end can be taken from before
+                let maximum_distance = 8.min(number_of_values / 2);
+
+                if maximum_distance > 0 {
+                    for _ in 0..swap_count {
+                        let first_index = random_generator.random_range(0..number_of_values);
+                        let distance = random_generator.random_range(1..=maximum_distance);
+                        let second_index = if first_index + distance < number_of_values {
+                            first_index + distance
+                        } else {
+                            first_index - distance
+                        };
+                        values.swap(first_index, second_index);
+                    }
+                }
+            }
+            InputDistribution::FewUnique => {
+                // equal-sized groups, each valued at the group's first element
+                let group_size = number_of_values.div_ceil(FEW_UNIQUE_VALUE_COUNT).max(1);
+                for value in values.iter_mut() {
+                    *value = ((*value as usize) / group_size * group_size) as u32;
+                }
+                values.shuffle(&mut random_generator);
+            }
+        }
+
+        values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_shape_of_a_small_array_is_a_permutation_of_its_size() {
+        for number_of_values in 0..=40 {
+            for input_distribution in InputDistribution::ALL {
+                for seed in 0..20 {
+                    let mut values = input_distribution.generate(number_of_values, Some(seed));
+                    assert_eq!(values.len(), number_of_values);
+                    if input_distribution != InputDistribution::FewUnique {
+                        values.sort_unstable();
+                        assert!(
+                            values.iter().enumerate().all(|(index, value)| *value as usize == index),
+                            "{:?} of {} values with seed {}",
+                            input_distribution,
+                            number_of_values,
+                            seed
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
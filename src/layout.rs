@@ -0,0 +1,56 @@
+// max grid columns
+pub const MAX_GRID_COLUMNS: usize = 4;
+
+pub const MINIMUM_CELL_WIDTH: f32 = 220.0;
+pub const MINIMUM_CELL_HEIGHT: f32 = 160.0;
+
+// how the algorithm panels are arranged; shared by the ui and the exporters
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GridLayout {
+    pub column_count: usize,
+    pub row_count: usize,
+    pub cell_width: f32,
+    pub cell_height: f32,
+}
+
+impl GridLayout {
+    pub fn compute(available_width: f32, available_height: f32, algorithm_count: usize) -> Self {
+        let algorithm_count = algorithm_count.max(1);
+
+        let columns_from_width = (available_width / MINIMUM_CELL_WIDTH).floor().max(1.0) as usize;
+
+        let rows_from_height = (available_height / MINIMUM_CELL_HEIGHT).floor().max(1.0) as usize;
+
+        let columns_capped = columns_from_width.min(MAX_GRID_COLUMNS);
+
+        let column_count = if rows_from_height >= 3 && algorithm_count >= 3 {
+            // enforce min 3 rows if space allows
+            let max_columns_for_three_rows = algorithm_count.div_ceil(3);
+            columns_capped.min(max_columns_for_three_rows).min(algorithm_count)
+        } else if rows_from_height >= 2 && algorithm_count > 1 {
+            // enforce min 2 rows if space allows
+            let max_columns_for_two_rows = algorithm_count.div_ceil(2);
+            columns_capped.min(max_columns_for_two_rows).min(algorithm_count)
+        } else {
+            // insufficient vertical space or single algo
+            columns_capped.min(algorithm_count)
+        };
+
+        let row_count = algorithm_count.div_ceil(column_count).max(1);
+
+        let cell_width = (available_width / (column_count as f32)).max(MINIMUM_CELL_WIDTH);
+        let cell_height = (available_height / (row_count as f32)).max(MINIMUM_CELL_HEIGHT);
+
+        Self {
+            column_count,
+            row_count,
+            cell_width,
+            cell_height,
+        }
+    }
+
+    // grid cell of the nth panel, as (column, row)
+    pub fn cell_position(&self, panel_index: usize) -> (usize, usize) {
+        (panel_index % self.column_count, panel_index / self.column_count)
+    }
+}
@@ -0,0 +1,47 @@
+//! Sorting algorithm visualizer: recorded sorts, replays, statistics and the playback engine.
+//!
+//! Every algorithm runs once through a [`SortRecorder`], which counts comparisons, swaps and
+//! writes and keeps one frame per change. A [`SortingAlgorithmReplay`] holds those frames and
+//! the [`SortStats`] of the run, and the [`EngineController`] plays replays back in lockstep on
+//! a worker thread, publishing [`EngineSharedState`] snapshots for a front end to draw.
+//!
+//! ```no_run
+//! use sortvis::input::InputDistribution;
+//! use sortvis::sorting_algorithms::{ SortingAlgorithmKind, SortingAlgorithmReplay };
+//!
+//! let values = InputDistribution::Random.generate(64, Some(7));
+//! let replay = SortingAlgorithmReplay::new(SortingAlgorithmKind::QuickSort, &values);
+//! println!("{}: {}", replay.name(), replay.stats_snapshot().summary_line());
+//! ```
+//!
+//! The egui front end is behind the `gui` feature and its tones behind `audio`; both are on
+//! by default. Headless users can depend on the crate with `default-features = false`.
+//!
+//! [`SortRecorder`]: sorting_algorithms::recorder::SortRecorder
+//! [`SortingAlgorithmReplay`]: sorting_algorithms::SortingAlgorithmReplay
+//! [`SortStats`]: stats::SortStats
+//! [`EngineController`]: engine::EngineController
+//! [`EngineSharedState`]: engine::EngineSharedState
+
+pub mod bench;
+pub mod chart;
+pub mod complexity;
+pub mod engine;
+pub mod export;
+pub mod image_source;
+pub mod input;
+pub mod layout;
+pub mod live_trace;
+pub mod palette;
+pub mod replay_file;
+pub mod scenario;
+pub mod self_check;
+pub mod sonification;
+pub mod sorting_algorithms;
+pub mod stats;
+pub mod tournament;
+pub mod trace;
+pub mod trace_import;
+pub mod verification;
+#[cfg(feature = "gui")]
+pub mod ui;
@@ -0,0 +1,605 @@
+use std::io::{ BufRead, BufReader, Read };
+use std::process::{ Child, Command, Stdio };
+use std::sync::atomic::{ AtomicBool, Ordering };
+use std::sync::mpsc::{ self, Receiver, SyncSender, TryRecvError };
+use std::sync::{ Arc, Mutex };
+use std::thread;
+use std::time::Instant;
+
+use crate::engine::AlgorithmStateSnapshot;
+use crate::sorting_algorithms::recorder::OperationCounts;
+use crate::stats::SortStats;
+use crate::trace::{ SortTrace, TRACE_MAX_ROWS };
+use crate::trace_import::{ parse_trace_line, TraceEvent };
+use crate::verification::Verification;
+
+// lines buffered between the reader thread and the ui; when it is full the reader stops
+// reading, the pipe or socket fills up and the producer blocks until the ui catches up
+const LIVE_CHANNEL_CAPACITY: usize = 1024;
+
+// the live panel sits next to a recorded run's panels, whose ids count up from 0
+pub const LIVE_PANEL_ID: usize = usize::MAX;
+
+#[cfg(unix)]
+type SocketStream = std::os::unix::net::UnixStream;
+// there are no sockets to connect to on other platforms
+#[cfg(not(unix))]
+type SocketStream = std::convert::Infallible;
+
+// the accepted connection, once there is one; a reader blocked on a quiet producer only
+// notices a stop when the socket is shut down under it
+type SharedSocketStream = Arc<Mutex<Option<SocketStream>>>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum LiveTraceSourceKind {
+    // a shell command whose standard output is the trace
+    #[default]
+    Command,
+    // a unix socket path to listen on for one connection
+    UnixSocket,
+}
+
+impl LiveTraceSourceKind {
+    pub const ALL: [LiveTraceSourceKind; 2] = [
+        LiveTraceSourceKind::Command,
+        LiveTraceSourceKind::UnixSocket,
+    ];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            LiveTraceSourceKind::Command => "Command",
+            LiveTraceSourceKind::UnixSocket => "Unix socket",
+        }
+    }
+
+    pub fn cli_name(&self) -> &'static str {
+        match self {
+            LiveTraceSourceKind::Command => "command",
+            LiveTraceSourceKind::UnixSocket => "socket",
+        }
+    }
+
+    pub fn from_cli_name(name: &str) -> Option<LiveTraceSourceKind> {
+        LiveTraceSourceKind::ALL
+            .into_iter()
+            .find(|source_kind| source_kind.cli_name().eq_ignore_ascii_case(name))
+    }
+}
+
+enum LiveTraceMessage {
+    Connected,
+    Line {
+        line_number: usize,
+        event: TraceEvent,
+    },
+    Invalid {
+        line_number: usize,
+        message: String,
+    },
+    Closed(String),
+    // the source broke before the producer was done, so its array is not a result
+    Failed(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LiveTraceStatus {
+    Waiting,
+    Streaming,
+    Finished(String),
+    Failed(String),
+}
+
+// an operation stream from another program, applied to its own array as it arrives
+pub struct LiveTrace {
+    name: String,
+    receiver: Receiver<LiveTraceMessage>,
+    stop_flag: Arc<AtomicBool>,
+    child: Option<Child>,
+    socket_stream: SharedSocketStream,
+    status: LiveTraceStatus,
+
+    values: Option<Vec<u32>>,
+    initial_values: Option<Vec<u32>>,
+    // set when the producer closes the stream, not when it fails or is stopped
+    verification: Option<Arc<Verification>>,
+    operation_counts: OperationCounts,
+    current_step: usize,
+    highlighted_indices: Vec<usize>,
+    current_marker: Option<String>,
+    connected_at: Option<Instant>,
+    finished_at: Option<Instant>,
+
+    trace_rows: Vec<Vec<u32>>,
+    trace_row_steps: Vec<usize>,
+    // a row is kept every this many steps; doubles whenever the rows fill up
+    trace_sample_interval: usize,
+    // rows were added since trace was last built
+    trace_is_stale: bool,
+    trace: Arc<SortTrace>,
+}
+
+impl LiveTrace {
+    pub fn start(source_kind: LiveTraceSourceKind, target: &str) -> Result<Self, String> {
+        let target = target.trim();
+        if target.is_empty() {
+            return Err("nothing to connect to".to_owned());
+        }
+
+        let (sender, receiver) = mpsc::sync_channel(LIVE_CHANNEL_CAPACITY);
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let socket_stream: SharedSocketStream = Arc::new(Mutex::new(None));
+
+        let (name, child) = match source_kind {
+            LiveTraceSourceKind::Command => {
+                let mut child = shell_command(target)
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::null())
+                    .spawn()
+                    .map_err(|error| format!("could not start '{}': {}", target, error))?;
+
+                let stdout = child.stdout.take().expect("stdout is piped");
+                let _ = sender.send(LiveTraceMessage::Connected);
+                spawn_reader_thread(stdout, sender, Arc::clone(&stop_flag), "program exited");
+
+                let program_name = target.split_whitespace().next().unwrap_or(target);
+                (format!("Live: {}", program_name), Some(child))
+            }
+            LiveTraceSourceKind::UnixSocket => {
+                listen_on_socket(target, sender, Arc::clone(&stop_flag), Arc::clone(&socket_stream))?;
+                (format!("Live: {}", target), None)
+            }
+        };
+
+        Ok(Self {
+            name,
+            receiver,
+            stop_flag,
+            child,
+            socket_stream,
+            status: LiveTraceStatus::Waiting,
+            values: None,
+            initial_values: None,
+            verification: None,
+            operation_counts: OperationCounts::default(),
+            current_step: 0,
+            highlighted_indices: Vec::new(),
+            current_marker: None,
+            connected_at: None,
+            finished_at: None,
+            trace_rows: Vec::new(),
+            trace_row_steps: Vec::new(),
+            trace_sample_interval: 1,
+            trace_is_stale: false,
+            trace: Arc::new(SortTrace::from_sampled_rows(Vec::new(), Vec::new())),
+        })
+    }
+
+    pub fn status(&self) -> &LiveTraceStatus {
+        &self.status
+    }
+
+    pub fn is_active(&self) -> bool {
+        matches!(self.status, LiveTraceStatus::Waiting | LiveTraceStatus::Streaming)
+    }
+
+    // applies queued operations until maximum_steps swaps or writes have been shown
+    pub fn advance(&mut self, maximum_steps: usize) {
+        self.highlighted_indices.clear();
+        if !self.is_active() {
+            return;
+        }
+
+        let mut steps_taken = 0;
+
+        while steps_taken < maximum_steps.max(1) {
+            let message = match self.receiver.try_recv() {
+                Ok(message) => message,
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.finish_stream("disconnected".to_owned());
+                    break;
+                }
+            };
+
+            match message {
+                LiveTraceMessage::Connected => {
+                    self.status = LiveTraceStatus::Streaming;
+                    self.connected_at = Some(Instant::now());
+                }
+                LiveTraceMessage::Line { line_number, event } => {
+                    match self.apply(event) {
+                        Ok(changed_array) => {
+                            if changed_array {
+                                steps_taken += 1;
+                                self.sample_trace_row();
+                            }
+                        }
+                        Err(message) => {
+                            self.fail(format!("line {}: {}", line_number, message));
+                            break;
+                        }
+                    }
+                }
+                LiveTraceMessage::Invalid { line_number, message } => {
+                    self.fail(format!("line {}: {}", line_number, message));
+                    break;
+                }
+                LiveTraceMessage::Failed(message) => {
+                    self.fail(message);
+                    break;
+                }
+                LiveTraceMessage::Closed(reason) => {
+                    let exit_status = self.child
+                        .as_mut()
+                        .and_then(|child| child.try_wait().ok().flatten());
+                    let reason = match exit_status {
+                        Some(exit_status) if !exit_status.success() => format!("{} ({})", reason, exit_status),
+                        _ => reason,
+                    };
+                    self.finish_stream(reason);
+                    break;
+                }
+            }
+        }
+
+        if self.trace_is_stale {
+            self.trace = Arc::new(
+                SortTrace::from_sampled_rows(self.trace_rows.clone(), self.trace_row_steps.clone())
+            );
+            self.trace_is_stale = false;
+        }
+    }
+
+    // None until the stream has sent its init line
+    pub fn snapshot(&self) -> Option<AlgorithmStateSnapshot> {
+        let values = self.values.as_ref()?;
+
+        let elapsed = match (self.connected_at, self.finished_at) {
+            (Some(connected_at), Some(finished_at)) => finished_at.duration_since(connected_at),
+            (Some(connected_at), None) => connected_at.elapsed(),
+            _ => Default::default(),
+        };
+        let stats = SortStats::from_measurements(
+            (self.current_step as u64) + 1,
+            self.operation_counts,
+            elapsed
+        );
+
+        let status_marker = match &self.status {
+            LiveTraceStatus::Failed(message) => Some(format!("error: {}", message)),
+            _ => self.current_marker.clone(),
+        };
+
+        Some(AlgorithmStateSnapshot {
+            panel_id: LIVE_PANEL_ID,
+            algorithm_name: self.name.clone(),
+            current_values: values.clone(),
+            is_finished: !self.is_active(),
+            stats: stats.to_snapshot(),
+            current_step: self.current_step,
+            highlighted_indices: self.highlighted_indices.clone(),
+            current_marker: status_marker,
+            trace: Arc::clone(&self.trace),
+            verification: self.verification.clone(),
+        })
+    }
+
+    pub fn stop(&mut self) {
+        self.finish(LiveTraceStatus::Finished("stopped".to_owned()));
+    }
+
+    // true when the event changed the array
+    fn apply(&mut self, event: TraceEvent) -> Result<bool, String> {
+        if let TraceEvent::Init(initial_values) = event {
+            if self.values.is_some() {
+                return Err("init appears more than once".to_owned());
+            }
+            self.sample_trace_row_of(&initial_values);
+            self.initial_values = Some(initial_values.clone());
+            self.values = Some(initial_values);
+            return Ok(false);
+        }
+
+        let Some(values) = self.values.as_mut() else {
+            return Err("operation before init".to_owned());
+        };
+        let check_index = |index: usize| -> Result<(), String> {
+            if index >= values.len() {
+                return Err(
+                    format!("index {} is out of range for an array of {} values", index, values.len())
+                );
+            }
+            Ok(())
+        };
+
+        match event {
+            TraceEvent::Init(_) => unreachable!("init handled above"),
+            TraceEvent::Compare(first_index, second_index) => {
+                check_index(first_index)?;
+                check_index(second_index)?;
+                self.operation_counts.comparisons += 1;
+                Ok(false)
+            }
+            TraceEvent::Swap(first_index, second_index) => {
+                check_index(first_index)?;
+                check_index(second_index)?;
+                values.swap(first_index, second_index);
+                self.operation_counts.swaps += 1;
+                self.current_step += 1;
+                self.highlighted_indices.extend([first_index, second_index]);
+                Ok(true)
+            }
+            TraceEvent::Write(index, value) => {
+                check_index(index)?;
+                values[index] = value;
+                self.operation_counts.writes += 1;
+                self.current_step += 1;
+                self.highlighted_indices.push(index);
+                Ok(true)
+            }
+            TraceEvent::Marker(name) => {
+                self.current_marker = Some(name);
+                Ok(false)
+            }
+        }
+    }
+
+    fn sample_trace_row(&mut self) {
+        if !self.current_step.is_multiple_of(self.trace_sample_interval) {
+            return;
+        }
+        if let Some(values) = self.values.take() {
+            self.sample_trace_row_of(&values);
+            self.values = Some(values);
+        }
+    }
+
+    fn sample_trace_row_of(&mut self, values: &[u32]) {
+        self.trace_rows.push(values.to_vec());
+        self.trace_row_steps.push(self.current_step);
+        self.trace_is_stale = true;
+
+        // full: drop every other row and sample half as often
+        if self.trace_rows.len() >= TRACE_MAX_ROWS {
+            let mut row_index = 0;
+            self.trace_rows.retain(|_| {
+                row_index += 1;
+                row_index % 2 == 1
+            });
+            let mut step_index = 0;
+            self.trace_row_steps.retain(|_| {
+                step_index += 1;
+                step_index % 2 == 1
+            });
+            self.trace_sample_interval *= 2;
+        }
+    }
+
+    // the producer is done; its last array is its result
+    fn finish_stream(&mut self, reason: String) {
+        self.finish(LiveTraceStatus::Finished(reason));
+        if let (Some(initial_values), Some(values)) = (&self.initial_values, &self.values) {
+            self.verification = Some(Arc::new(Verification::check(initial_values, values)));
+        }
+    }
+
+    fn fail(&mut self, message: String) {
+        self.finish(LiveTraceStatus::Failed(message));
+    }
+
+    // closes the source; the last array stays on screen
+    fn finish(&mut self, status: LiveTraceStatus) {
+        if !self.is_active() {
+            return;
+        }
+
+        self.status = status;
+        self.finished_at = Some(Instant::now());
+        self.stop_flag.store(true, Ordering::SeqCst);
+
+        if let Some(child) = self.child.as_mut() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        if let Some(socket_stream) = self.socket_stream.lock().ok().and_then(|mut stream| stream.take()) {
+            shut_down_socket(&socket_stream);
+        }
+
+        // the reader may be blocked on a full channel; draining lets it notice the stop
+        while self.receiver.try_recv().is_ok() {}
+    }
+}
+
+impl Drop for LiveTrace {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(unix)]
+fn shell_command(command_line: &str) -> Command {
+    let mut command = Command::new("sh");
+    command.arg("-c").arg(command_line);
+    command
+}
+
+#[cfg(not(unix))]
+fn shell_command(command_line: &str) -> Command {
+    let mut command = Command::new("cmd");
+    command.arg("/C").arg(command_line);
+    command
+}
+
+// parses lines until the source closes or the ui goes away; the bounded send is the back-pressure
+fn spawn_reader_thread(
+    source: impl Read + Send + 'static,
+    sender: SyncSender<LiveTraceMessage>,
+    stop_flag: Arc<AtomicBool>,
+    end_of_stream_reason: &'static str
+) {
+    thread::spawn(move || {
+        for (line_index, line) in BufReader::new(source).lines().enumerate() {
+            // dropping the source here hangs up on the producer
+            if stop_flag.load(Ordering::SeqCst) {
+                return;
+            }
+
+            let line_number = line_index + 1;
+            let message = match line {
+                Ok(line) =>
+                    match parse_trace_line(&line) {
+                        Ok(Some(event)) => LiveTraceMessage::Line { line_number, event },
+                        Ok(None) => {
+                            continue;
+                        }
+                        Err(message) => LiveTraceMessage::Invalid { line_number, message },
+                    }
+                Err(error) => {
+                    let message = format!("line {}: read error: {}", line_number, error);
+                    let _ = sender.send(LiveTraceMessage::Failed(message));
+                    return;
+                }
+            };
+
+            if sender.send(message).is_err() {
+                return;
+            }
+        }
+
+        let _ = sender.send(LiveTraceMessage::Closed(end_of_stream_reason.to_owned()));
+    });
+}
+
+#[cfg(unix)]
+fn listen_on_socket(
+    socket_path: &str,
+    sender: SyncSender<LiveTraceMessage>,
+    stop_flag: Arc<AtomicBool>,
+    shared_socket_stream: SharedSocketStream
+) -> Result<(), String> {
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::net::UnixListener;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    let socket_path = PathBuf::from(socket_path);
+
+    // a socket left behind by an earlier session; anything else at the path is left alone
+    if
+        std::fs
+            ::symlink_metadata(&socket_path)
+            .is_ok_and(|metadata| metadata.file_type().is_socket())
+    {
+        let _ = std::fs::remove_file(&socket_path);
+    }
+
+    let listener = UnixListener::bind(&socket_path).map_err(|error| {
+        format!("could not listen on {}: {}", socket_path.display(), error)
+    })?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|error| format!("could not listen on {}: {}", socket_path.display(), error))?;
+
+    thread::spawn(move || {
+        // poll so a stop while nobody has connected yet ends the thread
+        let stream = loop {
+            if stop_flag.load(Ordering::SeqCst) {
+                let _ = std::fs::remove_file(&socket_path);
+                return;
+            }
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    break stream;
+                }
+                Err(error) if error.kind() == std::io::ErrorKind::WouldBlock => {
+                    thread::sleep(Duration::from_millis(50));
+                }
+                Err(error) => {
+                    let _ = sender.send(LiveTraceMessage::Failed(format!("accept failed: {}", error)));
+                    let _ = std::fs::remove_file(&socket_path);
+                    return;
+                }
+            }
+        };
+
+        drop(listener);
+        let _ = std::fs::remove_file(&socket_path);
+
+        // stored before the stop flag is checked: a stop either finds the stream to shut down
+        // or has already set the flag
+        let Ok(stream_handle) = stream.try_clone() else {
+            let _ = sender.send(LiveTraceMessage::Failed("could not share the connection".to_owned()));
+            return;
+        };
+        if let Ok(mut shared_stream) = shared_socket_stream.lock() {
+            *shared_stream = Some(stream_handle);
+        }
+        if stop_flag.load(Ordering::SeqCst) {
+            shut_down_socket(&stream);
+            return;
+        }
+
+        if stream.set_nonblocking(false).is_err() || sender.send(LiveTraceMessage::Connected).is_err() {
+            return;
+        }
+        spawn_reader_thread(stream, sender, stop_flag, "disconnected");
+    });
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn listen_on_socket(
+    _socket_path: &str,
+    _sender: SyncSender<LiveTraceMessage>,
+    _stop_flag: Arc<AtomicBool>,
+    _shared_socket_stream: SharedSocketStream
+) -> Result<(), String> {
+    Err("unix sockets are not available on this platform".to_owned())
+}
+
+// wakes the reader thread from a blocked read and hangs up on the producer
+#[cfg(unix)]
+fn shut_down_socket(socket_stream: &SocketStream) {
+    let _ = socket_stream.shutdown(std::net::Shutdown::Both);
+}
+
+#[cfg(not(unix))]
+fn shut_down_socket(socket_stream: &SocketStream) {
+    match *socket_stream {}
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::os::unix::net::UnixStream;
+    use std::time::Duration;
+
+    #[test]
+    fn stop_hangs_up_on_a_quiet_socket() {
+        let socket_path = std::env::temp_dir().join(format!("sortvis-{}-live.sock", std::process::id()));
+        let mut live_trace = LiveTrace::start(
+            LiveTraceSourceKind::UnixSocket,
+            socket_path.to_str().unwrap()
+        ).unwrap();
+
+        let mut producer = UnixStream::connect(&socket_path).unwrap();
+        producer.write_all(b"init 3 1 2\n").unwrap();
+        for _ in 0..200 {
+            live_trace.advance(1);
+            if live_trace.snapshot().is_some() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(live_trace.status(), &LiveTraceStatus::Streaming);
+
+        // the producer sends nothing more, so only the shutdown can end the reader's read
+        live_trace.stop();
+        producer.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        let mut buffer = [0u8; 16];
+        assert_eq!(producer.read(&mut buffer).unwrap(), 0);
+    }
+}
@@ -1,23 +1,240 @@
-use std::sync::{ Arc, Mutex };
+use std::process::ExitCode;
 
-mod engine;
-mod sorting_algorithms;
-mod stats;
-mod ui;
+mod cli;
 
-use crate::engine::EngineSharedState;
-use crate::ui::ui::SortVisApp;
+use sortvis::bench::{ format_bench_rows, run_bench, spawn_bench_thread };
+use sortvis::complexity::{ fit_complexity, run_sweep };
+use sortvis::export::{ export_run, ExportFormat };
+use sortvis::self_check::run_self_check;
+use sortvis::sorting_algorithms::registry::{ format_algorithm_markdown, format_algorithm_table };
+use sortvis::tournament::{ format_tournament, run_tournament };
 
-fn main() -> eframe::Result<()> {
-    let native_options = eframe::NativeOptions::default();
+use crate::cli::{ Command, GuiOptions, usage };
+
+fn main() -> ExitCode {
+    let arguments: Vec<String> = std::env::args().skip(1).collect();
+
+    match cli::parse_command(&arguments) {
+        Ok(Command::Gui(gui_options)) => {
+            if let Err(error) = run_gui(gui_options) {
+                eprintln!("error: {}", error);
+                return ExitCode::FAILURE;
+            }
+        }
+        Ok(Command::Export(export_options)) => {
+            match export_run(&export_options) {
+                Ok(_) if export_options.format == ExportFormat::Svg => {
+                    eprintln!("wrote snapshot to {}", export_options.output_path.display());
+                }
+                Ok(note_count) if export_options.format == ExportFormat::Midi => {
+                    eprintln!("wrote {} notes to {}", note_count, export_options.output_path.display());
+                }
+                Ok(frame_count) => {
+                    eprintln!(
+                        "wrote {} frames to {}",
+                        frame_count,
+                        export_options.output_path.display()
+                    );
+                    if let Some(audio_path) = &export_options.audio_path {
+                        eprintln!("wrote audio to {}", audio_path.display());
+                    }
+                }
+                Err(error) => {
+                    eprintln!("error: export failed: {}", error);
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        Ok(Command::Bench(bench_options)) => {
+            let bench_rows = run_bench(&bench_options);
+            print!("{}", format_bench_rows(&bench_rows, bench_options.output_format));
+        }
+        Ok(Command::Sweep(sweep_options, counter_kind)) => {
+            let sweep_series = spawn_bench_thread(move || run_sweep(&sweep_options))
+                .join()
+                .expect("sweep thread panicked");
+
+            for series in &sweep_series {
+                let samples = series.samples(counter_kind);
+                let fit_description = match fit_complexity(&samples) {
+                    Some(complexity_fit) => {
+                        format!(
+                            "{}  ({})",
+                            complexity_fit.describe(counter_kind),
+                            complexity_fit.describe_power_law()
+                        )
+                    }
+                    None => series.missing_fit_reason(counter_kind),
+                };
+                println!("{:<22} {}", series.algorithm_kind.display_name(), fit_description);
+            }
+        }
+        Ok(Command::Tournament(tournament_command)) => {
+            let tournament_options = tournament_command.options.clone();
+            let tournament_result = spawn_bench_thread(move || run_tournament(&tournament_options))
+                .join()
+                .expect("tournament thread panicked");
+
+            let formatted_result = format_tournament(
+                &tournament_result,
+                tournament_command.ranking_metric,
+                tournament_command.output_format
+            );
+
+            match &tournament_command.output_path {
+                Some(output_path) => {
+                    if let Err(error) = std::fs::write(output_path, formatted_result) {
+                        eprintln!("error: could not write {}: {}", output_path.display(), error);
+                        return ExitCode::FAILURE;
+                    }
+                    eprintln!("wrote tournament results to {}", output_path.display());
+                }
+                None => print!("{}", formatted_result),
+            }
+        }
+        Ok(Command::Check(self_check_options)) => {
+            let self_check_runs = spawn_bench_thread(move || run_self_check(&self_check_options))
+                .join()
+                .expect("self-check thread panicked");
+
+            let mut failed_run_count = 0;
+            for self_check_run in &self_check_runs {
+                if self_check_run.violations.is_empty() {
+                    continue;
+                }
+                failed_run_count += 1;
+                println!(
+                    "{} on {} input, seed {}:",
+                    self_check_run.algorithm_label,
+                    self_check_run.input_distribution.cli_name(),
+                    self_check_run.seed
+                );
+                for violation in &self_check_run.violations {
+                    println!("  {}", violation);
+                }
+            }
+
+            let step_count: usize = self_check_runs
+                .iter()
+                .map(|self_check_run| self_check_run.step_count)
+                .sum();
+            println!(
+                "checked {} steps in {} runs: {} failed",
+                step_count,
+                self_check_runs.len(),
+                failed_run_count
+            );
+            if failed_run_count > 0 {
+                return ExitCode::FAILURE;
+            }
+        }
+        Ok(Command::Algorithms(is_markdown)) => {
+            if is_markdown {
+                print!("{}", format_algorithm_markdown());
+            } else {
+                print!("{}", format_algorithm_table());
+            }
+        }
+        Ok(Command::Help) => {
+            println!("{}", usage());
+        }
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, usage());
+            return ExitCode::from(2);
+        }
+    }
+
+    ExitCode::SUCCESS
+}
+
+#[cfg(feature = "gui")]
+fn run_gui(gui_options: GuiOptions) -> Result<(), String> {
+    use std::sync::{ Arc, Mutex };
+
+    use sortvis::engine::EngineSharedState;
+    use sortvis::replay_file::read_replay_file;
+    use sortvis::scenario::read_playlist;
+    use sortvis::ui::settings_file::SettingsFile;
+    use sortvis::ui::ui::{ LaunchAction, SortVisApp };
+
+    let (mut settings_file, mut settings_state, window_layout) =
+        SettingsFile::load(SettingsFile::default_path());
+
+    // launch options are for this session; a demo shortcut must not replace the user's settings
+    let has_setting_overrides = gui_options.algorithms.is_some() ||
+        gui_options.number_of_values.is_some() ||
+        gui_options.frames_per_second.is_some() ||
+        gui_options.input_distribution.is_some() ||
+        gui_options.seed.is_some() ||
+        gui_options.scenario_path.is_some() ||
+        gui_options.replay_path.is_some();
+    if has_setting_overrides {
+        settings_file.save_nothing_this_session("Started with settings from the command line");
+    }
+
+    if let Some(algorithms) = gui_options.algorithms {
+        settings_state.algorithm_instances = algorithms;
+    }
+    if let Some(number_of_values) = gui_options.number_of_values {
+        settings_state.number_of_values = number_of_values;
+    }
+    if let Some(frames_per_second) = gui_options.frames_per_second {
+        settings_state.frames_per_second = frames_per_second;
+    }
+    if let Some(input_distribution) = gui_options.input_distribution {
+        settings_state.input_distribution = input_distribution;
+    }
+    if let Some(seed) = gui_options.seed {
+        settings_state.use_fixed_seed = true;
+        settings_state.fixed_seed = seed;
+    }
+
+    // files are read before the window opens, so a typo fails here rather than in a kiosk
+    let launch_action = if let Some(scenario_path) = &gui_options.scenario_path {
+        let playlist = read_playlist(scenario_path).map_err(|error| {
+            format!("could not open {}: {}", scenario_path.display(), error)
+        })?;
+        settings_state.scenario_path = scenario_path.display().to_string();
+        LaunchAction::Playlist(playlist)
+    } else if let Some(replay_path) = &gui_options.replay_path {
+        let recorded_run = read_replay_file(replay_path).map_err(|error| {
+            format!("could not open {}: {}", replay_path.display(), error)
+        })?;
+        settings_state.replay_path = replay_path.display().to_string();
+        LaunchAction::Replay(recorded_run)
+    } else if gui_options.autostart {
+        LaunchAction::Start
+    } else {
+        LaunchAction::None
+    };
+
+    let mut native_options = eframe::NativeOptions::default();
+    if let Some(window_size) = window_layout.window_size {
+        native_options.viewport = native_options.viewport.with_inner_size(window_size);
+    }
+    if gui_options.fullscreen {
+        native_options.viewport = native_options.viewport.with_fullscreen(true);
+    }
 
     eframe::run_native(
         "VisSort",
         native_options,
         Box::new(|creation_context| {
             let shared_state = Arc::new(Mutex::new(EngineSharedState::default()));
-            let app = SortVisApp::new(creation_context, shared_state);
+            let app = SortVisApp::new(
+                creation_context,
+                shared_state,
+                settings_file,
+                settings_state,
+                window_layout,
+                launch_action
+            );
             Ok(Box::new(app))
         })
-    )
+    ).map_err(|error| error.to_string())
+}
+
+#[cfg(not(feature = "gui"))]
+fn run_gui(_gui_options: GuiOptions) -> Result<(), String> {
+    Err("this build has no window; rebuild with the gui feature or use a subcommand".to_owned())
 }
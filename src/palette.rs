@@ -0,0 +1,155 @@
+use std::ops::RangeInclusive;
+
+// unmultiplied srgba color shared by the ui and the offline renderers
+pub type Rgba8 = [u8; 4];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Palette {
+    pub use_custom_palette: bool,
+    pub base_hue_degrees: f32,
+    pub saturation: f32,
+    pub brightness: f32,
+    pub gradient_strength: f32,
+    // only used when the custom palette is off
+    pub dark_mode: bool,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            use_custom_palette: true,
+            base_hue_degrees: 210.0, // ~teal
+            saturation: 0.85,
+            brightness: 0.9,
+            gradient_strength: 0.28,
+            dark_mode: true,
+        }
+    }
+}
+
+impl Palette {
+    // what the settings panel, the settings file and the command line accept
+    pub const HUE_RANGE_DEGREES: RangeInclusive<f32> = 0.0..=360.0;
+    pub const SATURATION_RANGE: RangeInclusive<f32> = 0.2..=1.0;
+    pub const BRIGHTNESS_RANGE: RangeInclusive<f32> = 0.3..=1.0;
+    pub const GRADIENT_STRENGTH_RANGE: RangeInclusive<f32> = 0.0..=0.4;
+
+    pub fn bar_color(&self, normalized_value: f32, is_finished: bool) -> Rgba8 {
+        let clamped_value = normalized_value.clamp(0.0, 1.0);
+
+        // base hue from theme or user setting
+        let base_hue = if self.use_custom_palette {
+            (self.base_hue_degrees / 360.0).rem_euclid(1.0)
+        } else if self.dark_mode {
+            0.58 // teal-ish
+        } else {
+            0.13 // orange-ish
+        };
+
+        // gradient strength controls hue variation
+        let gradient_strength = if self.use_custom_palette { self.gradient_strength } else { 0.18 };
+
+        let hue_variation = gradient_strength * (clamped_value - 0.5);
+        let hue = (base_hue + hue_variation).rem_euclid(1.0);
+
+        // saturation from theme or user, desaturated if finished
+        let saturation = if self.use_custom_palette {
+            let base = self.saturation.clamp(0.0, 1.0);
+            if is_finished {
+                base * 0.7
+            } else {
+                base
+            }
+        } else if is_finished {
+            0.5
+        } else {
+            0.85
+        };
+
+        // brightness from theme or user setting
+        let value_brightness = if self.use_custom_palette {
+            self.brightness.clamp(0.0, 1.0)
+        } else if self.dark_mode {
+            0.9
+        } else {
+            0.7
+        };
+
+        srgba_from_hsv(hue, saturation, value_brightness)
+    }
+
+    // complementary hue, so the elements touched by the last step stand out
+    pub fn highlight_color(&self) -> Rgba8 {
+        let base_hue = if self.use_custom_palette {
+            (self.base_hue_degrees / 360.0).rem_euclid(1.0)
+        } else if self.dark_mode {
+            0.58
+        } else {
+            0.13
+        };
+
+        srgba_from_hsv(base_hue + 0.5, 0.9, 1.0)
+    }
+
+    // verification sweep: positions found in order
+    pub fn verified_color(&self) -> Rgba8 {
+        if self.dark_mode { [80, 200, 120, 255] } else { [40, 160, 80, 255] }
+    }
+
+    // verification sweep: positions out of order or holding a value the input did not have
+    pub fn failed_color(&self) -> Rgba8 {
+        if self.dark_mode { [235, 80, 80, 255] } else { [210, 40, 40, 255] }
+    }
+
+    // matches egui's default extreme_bg_color, brightened when finished
+    pub fn chart_background(&self, is_finished: bool) -> Rgba8 {
+        let base_gray: f32 = if self.dark_mode { 10.0 } else { 255.0 };
+        let factor = if is_finished { 1.05 } else { 0.95 };
+        let gray = (base_gray * factor).round().min(255.0) as u8;
+        [gray, gray, gray, 255]
+    }
+
+    // matches egui's default panel fill
+    pub fn panel_background(&self) -> Rgba8 {
+        if self.dark_mode { [27, 27, 27, 255] } else { [248, 248, 248, 255] }
+    }
+
+    pub fn text_color(&self) -> Rgba8 {
+        if self.dark_mode { [220, 220, 220, 255] } else { [40, 40, 40, 255] }
+    }
+}
+
+// same conversion egui uses for Hsva -> Color32 (hsv in linear space)
+fn srgba_from_hsv(hue: f32, saturation: f32, value: f32) -> Rgba8 {
+    let hue = (hue.fract() + 1.0).fract();
+    let saturation = saturation.clamp(0.0, 1.0);
+
+    let sector = hue * 6.0;
+    let fraction = sector - sector.floor();
+    let p = value * (1.0 - saturation);
+    let q = value * (1.0 - fraction * saturation);
+    let t = value * (1.0 - (1.0 - fraction) * saturation);
+
+    let [red, green, blue] = match (sector.floor() as i32) % 6 {
+        0 => [value, t, p],
+        1 => [q, value, p],
+        2 => [p, value, t],
+        3 => [p, q, value],
+        4 => [t, p, value],
+        _ => [value, p, q],
+    };
+
+    [gamma_from_linear(red), gamma_from_linear(green), gamma_from_linear(blue), 255]
+}
+
+fn gamma_from_linear(linear: f32) -> u8 {
+    if linear <= 0.0 {
+        0
+    } else if linear <= 0.0031308 {
+        (3294.6 * linear + 0.5) as u8
+    } else if linear <= 1.0 {
+        (269.025 * linear.powf(1.0 / 2.4) - 14.025 + 0.5) as u8
+    } else {
+        255
+    }
+}
@@ -0,0 +1,734 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{ self, BufReader, BufWriter, Read, Write };
+use std::path::Path;
+use std::time::Duration;
+
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+use crate::engine::RecordedRun;
+use crate::input::InputDistribution;
+use crate::sorting_algorithms::recorder::{
+    frame_bytes,
+    OperationCounts,
+    SortOperation,
+    MAX_FRAME_BYTES,
+    MAX_OPERATIONS,
+};
+use crate::sorting_algorithms::{ SortingAlgorithmKind, SortingAlgorithmReplay };
+use crate::stats::SortStats;
+
+// .sortvis layout:
+//   magic, format version (u16), oldest reader version that understands the file (u16),
+//   then a zlib stream of sections: 4-byte tag, u64 payload length, payload.
+// readers skip tags they do not know, so new sections only bump the format version;
+// the reader version is raised only when old readers would misread the file.
+// version 2 appends markers to algorithm sections.
+// version 3 repeats the input section before an algorithm that starts from a different array;
+// each algorithm section uses the latest input section, which older readers already do.
+// version 4 appends the algorithm's cli name to algorithm sections, empty when not known.
+// all integers are little endian.
+const MAGIC: &[u8; 8] = b"SORTVIS\0";
+pub const FORMAT_VERSION: u16 = 4;
+const MINIMUM_READER_VERSION: u16 = 1;
+
+const RUN_SECTION: &[u8; 4] = b"RUN ";
+const INPUT_SECTION: &[u8; 4] = b"INPT";
+const ALGORITHM_SECTION: &[u8; 4] = b"ALGO";
+const END_SECTION: &[u8; 4] = b"END ";
+
+// longest section payload accepted; an algorithm section within the operation limit takes at
+// most 9 bytes per operation, and zlib lets a small file claim any length
+const MAX_SECTION_BYTES: u64 = 1 << 29;
+
+const COMPARE_TAG: u8 = 0;
+const SWAP_TAG: u8 = 1;
+const WRITE_TAG: u8 = 2;
+
+#[derive(Debug)]
+pub enum ReplayFileError {
+    Io(io::Error),
+    NotAReplayFile,
+    NewerVersion {
+        file_version: u16,
+        minimum_reader_version: u16,
+    },
+    Corrupt(String),
+}
+
+impl fmt::Display for ReplayFileError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayFileError::Io(error) => write!(formatter, "i/o error: {}", error),
+            ReplayFileError::NotAReplayFile => write!(formatter, "not a .sortvis replay file"),
+            ReplayFileError::NewerVersion { file_version, minimum_reader_version } =>
+                write!(
+                    formatter,
+                    "replay file format {} needs a reader for format {} or newer; this build reads format {}",
+                    file_version,
+                    minimum_reader_version,
+                    FORMAT_VERSION
+                ),
+            ReplayFileError::Corrupt(reason) => write!(formatter, "corrupt replay file: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ReplayFileError {}
+
+impl From<io::Error> for ReplayFileError {
+    fn from(error: io::Error) -> Self {
+        match error.kind() {
+            // a damaged zlib stream or a file cut short
+            io::ErrorKind::InvalidData | io::ErrorKind::InvalidInput =>
+                ReplayFileError::Corrupt(error.to_string()),
+            io::ErrorKind::UnexpectedEof =>
+                ReplayFileError::Corrupt("file ends in the middle of a section".to_owned()),
+            _ => ReplayFileError::Io(error),
+        }
+    }
+}
+
+pub fn write_replay_file(path: &Path, recorded_run: &RecordedRun) -> Result<(), ReplayFileError> {
+    write_replay(BufWriter::new(File::create(path)?), recorded_run)
+}
+
+fn write_replay(mut writer: impl Write, recorded_run: &RecordedRun) -> Result<(), ReplayFileError> {
+    writer.write_all(MAGIC)?;
+    writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+    writer.write_all(&MINIMUM_READER_VERSION.to_le_bytes())?;
+
+    let mut encoder = ZlibEncoder::new(writer, Compression::default());
+
+    let mut run_payload = Vec::new();
+    put_string(&mut run_payload, recorded_run.input_distribution.cli_name());
+    put_u32(&mut run_payload, recorded_run.frames_per_second);
+    write_section(&mut encoder, RUN_SECTION, &run_payload)?;
+
+    write_section(&mut encoder, INPUT_SECTION, &input_payload(&recorded_run.base_values))?;
+
+    let mut current_input: &[u32] = &recorded_run.base_values;
+    for (panel_id, replay) in recorded_run.replays.iter().enumerate() {
+        let replay_input = recorded_run.replay_input(panel_id);
+        if replay_input != current_input {
+            write_section(&mut encoder, INPUT_SECTION, &input_payload(replay_input))?;
+            current_input = replay_input;
+        }
+        write_section(
+            &mut encoder,
+            ALGORITHM_SECTION,
+            &algorithm_payload(replay, recorded_run.algorithm_kind(panel_id))
+        )?;
+    }
+
+    write_section(&mut encoder, END_SECTION, &[])?;
+    encoder.finish()?.flush()?;
+    Ok(())
+}
+
+pub fn read_replay_file(path: &Path) -> Result<RecordedRun, ReplayFileError> {
+    read_replay(BufReader::new(File::open(path)?), LoadBudget::default())
+}
+
+fn read_replay(mut reader: impl Read, mut budget: LoadBudget) -> Result<RecordedRun, ReplayFileError> {
+    let mut magic = [0u8; 8];
+    if reader.read_exact(&mut magic).is_err() || &magic != MAGIC {
+        return Err(ReplayFileError::NotAReplayFile);
+    }
+
+    let mut version_bytes = [0u8; 4];
+    reader.read_exact(&mut version_bytes)?;
+    let file_version = u16::from_le_bytes([version_bytes[0], version_bytes[1]]);
+    let minimum_reader_version = u16::from_le_bytes([version_bytes[2], version_bytes[3]]);
+    if minimum_reader_version > FORMAT_VERSION {
+        return Err(ReplayFileError::NewerVersion { file_version, minimum_reader_version });
+    }
+
+    let mut decoder = ZlibDecoder::new(reader);
+
+    let mut input_distribution = None;
+    let mut frames_per_second = None;
+    // the first input section is the run's input, the latest one the next algorithm's
+    let mut base_values: Option<Vec<u32>> = None;
+    let mut current_input: Option<Vec<u32>> = None;
+    let mut replays = Vec::new();
+    let mut replay_inputs = Vec::new();
+    let mut algorithm_kinds = Vec::new();
+
+    loop {
+        let mut tag = [0u8; 4];
+        decoder.read_exact(&mut tag)?;
+        let mut length_bytes = [0u8; 8];
+        decoder.read_exact(&mut length_bytes)?;
+        let payload_length = u64::from_le_bytes(length_bytes);
+        if payload_length > MAX_SECTION_BYTES {
+            return Err(
+                ReplayFileError::Corrupt(
+                    format!(
+                        "section {} is {} bytes, more than {} MB",
+                        String::from_utf8_lossy(&tag),
+                        payload_length,
+                        MAX_SECTION_BYTES >> 20
+                    )
+                )
+            );
+        }
+
+        let mut payload = Vec::new();
+        (&mut decoder).take(payload_length).read_to_end(&mut payload)?;
+        if (payload.len() as u64) != payload_length {
+            return Err(ReplayFileError::Corrupt("file ends in the middle of a section".to_owned()));
+        }
+        let mut section = SectionReader::new(&tag, &payload);
+
+        match &tag {
+            RUN_SECTION => {
+                // an unknown distribution name only affects the label, so it is not fatal
+                input_distribution = Some(
+                    InputDistribution::from_cli_name(&section.string()?).unwrap_or_default()
+                );
+                frames_per_second = Some(section.u32()?);
+            }
+            INPUT_SECTION => {
+                let value_count = section.length(4)?;
+                let mut values = Vec::with_capacity(value_count);
+                for _ in 0..value_count {
+                    values.push(section.u32()?);
+                }
+                if base_values.is_none() {
+                    base_values = Some(values.clone());
+                }
+                current_input = Some(values);
+            }
+            ALGORITHM_SECTION => {
+                let Some(current_input) = &current_input else {
+                    return Err(
+                        ReplayFileError::Corrupt("algorithm section before the input".to_owned())
+                    );
+                };
+                let (replay, algorithm_kind) = read_algorithm_section(
+                    &mut section,
+                    current_input,
+                    &mut budget
+                )?;
+                replays.push(replay);
+                replay_inputs.push(current_input.clone());
+                algorithm_kinds.push(algorithm_kind);
+            }
+            END_SECTION => {
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let (Some(input_distribution), Some(frames_per_second), Some(base_values)) = (
+        input_distribution,
+        frames_per_second,
+        base_values,
+    ) else {
+        return Err(ReplayFileError::Corrupt("missing run or input section".to_owned()));
+    };
+
+    if replays.is_empty() {
+        return Err(ReplayFileError::Corrupt("no algorithms recorded".to_owned()));
+    }
+
+    Ok(RecordedRun {
+        input_distribution,
+        frames_per_second,
+        base_values,
+        replays,
+        replay_inputs,
+        algorithm_kinds,
+    })
+}
+
+fn input_payload(values: &[u32]) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(8 + values.len() * 4);
+    put_u64(&mut payload, values.len() as u64);
+    for value in values {
+        put_u32(&mut payload, *value);
+    }
+    payload
+}
+
+// name, stats, the operation log, markers, then the algorithm
+fn algorithm_payload(
+    replay: &SortingAlgorithmReplay,
+    algorithm_kind: Option<SortingAlgorithmKind>
+) -> Vec<u8> {
+    let stats = replay.stats();
+    let operation_counts = stats.operation_counts();
+
+    let mut payload = Vec::new();
+    put_string(&mut payload, replay.name());
+    put_u64(&mut payload, stats.total_steps());
+    put_u64(&mut payload, operation_counts.comparisons);
+    put_u64(&mut payload, operation_counts.swaps);
+    put_u64(&mut payload, operation_counts.writes);
+    put_u64(&mut payload, stats.duration().as_nanos().min(u64::MAX as u128) as u64);
+
+    put_u64(&mut payload, replay.operations().len() as u64);
+    for operation in replay.operations() {
+        match *operation {
+            SortOperation::Compare => payload.push(COMPARE_TAG),
+            SortOperation::Swap(first_index, second_index) => {
+                payload.push(SWAP_TAG);
+                put_u32(&mut payload, first_index as u32);
+                put_u32(&mut payload, second_index as u32);
+            }
+            SortOperation::Write(index, value) => {
+                payload.push(WRITE_TAG);
+                put_u32(&mut payload, index as u32);
+                put_u32(&mut payload, value);
+            }
+        }
+    }
+
+    put_u64(&mut payload, replay.markers().len() as u64);
+    for (step_index, marker_name) in replay.markers() {
+        put_u64(&mut payload, *step_index as u64);
+        put_string(&mut payload, marker_name);
+    }
+
+    put_string(&mut payload, algorithm_kind.map(|kind| kind.cli_name()).unwrap_or(""));
+    payload
+}
+
+// what the replays read from a file may still keep in memory; the limits cover the whole run,
+// so many small algorithm sections cannot add up past them
+struct LoadBudget {
+    frame_bytes: usize,
+    operations: usize,
+}
+
+impl Default for LoadBudget {
+    fn default() -> Self {
+        Self {
+            frame_bytes: MAX_FRAME_BYTES,
+            operations: MAX_OPERATIONS,
+        }
+    }
+}
+
+fn read_algorithm_section(
+    section: &mut SectionReader<'_>,
+    base_values: &[u32],
+    budget: &mut LoadBudget
+) -> Result<(SortingAlgorithmReplay, Option<SortingAlgorithmKind>), ReplayFileError> {
+    let algorithm_name = section.string()?;
+    let total_steps = section.u64()?;
+    let operation_counts = OperationCounts {
+        comparisons: section.u64()?,
+        swaps: section.u64()?,
+        writes: section.u64()?,
+    };
+    let duration = Duration::from_nanos(section.u64()?);
+
+    let operation_count = section.length(1)?;
+    if operation_count > budget.operations {
+        return Err(
+            ReplayFileError::Corrupt(
+                format!("'{}' has {} operations, more than the run may hold", algorithm_name, operation_count)
+            )
+        );
+    }
+    budget.operations -= operation_count;
+    let mut operations = Vec::with_capacity(operation_count);
+    for _ in 0..operation_count {
+        operations.push(match section.u8()? {
+            COMPARE_TAG => SortOperation::Compare,
+            SWAP_TAG => SortOperation::Swap(section.u32()? as usize, section.u32()? as usize),
+            WRITE_TAG => SortOperation::Write(section.u32()? as usize, section.u32()?),
+            unknown_tag => {
+                return Err(
+                    ReplayFileError::Corrupt(
+                        format!("unknown operation {} in '{}'", unknown_tag, algorithm_name)
+                    )
+                );
+            }
+        });
+    }
+
+    // the replay keeps a frame per swap or write, so a small file could ask for any amount of
+    // memory; the same limit as for recorded runs applies to all replays together
+    let changing_operation_count = operations
+        .iter()
+        .filter(|operation| !matches!(operation, SortOperation::Compare))
+        .count();
+    let replay_frame_bytes = frame_bytes(changing_operation_count + 1, base_values.len());
+    if replay_frame_bytes > budget.frame_bytes {
+        return Err(
+            ReplayFileError::Corrupt(
+                format!(
+                    "'{}' has {} frames of {} values, which brings the run past {} MB",
+                    algorithm_name,
+                    changing_operation_count + 1,
+                    base_values.len(),
+                    MAX_FRAME_BYTES >> 20
+                )
+            )
+        );
+    }
+    budget.frame_bytes -= replay_frame_bytes;
+
+    // version 1 files end the section here
+    let mut markers = Vec::new();
+    if !section.is_at_end() {
+        let marker_count = section.length(12)?;
+        for _ in 0..marker_count {
+            let step_index = section.u64()? as usize;
+            markers.push((step_index, section.string()?));
+        }
+    }
+
+    // versions before 4 do not say which algorithm it was
+    let algorithm_kind = if section.is_at_end() {
+        None
+    } else {
+        SortingAlgorithmKind::from_cli_name(&section.string()?)
+    };
+
+    let stats = SortStats::from_measurements(total_steps, operation_counts, duration);
+    SortingAlgorithmReplay::from_operations(
+        algorithm_name.clone(),
+        base_values.to_vec(),
+        operations,
+        stats
+    )
+        .map(|replay| (replay.with_markers(markers), algorithm_kind))
+        .map_err(|reason| ReplayFileError::Corrupt(format!("'{}': {}", algorithm_name, reason)))
+}
+
+fn write_section(
+    writer: &mut impl Write,
+    tag: &[u8; 4],
+    payload: &[u8]
+) -> Result<(), ReplayFileError> {
+    writer.write_all(tag)?;
+    writer.write_all(&(payload.len() as u64).to_le_bytes())?;
+    writer.write_all(payload)?;
+    Ok(())
+}
+
+fn put_u32(payload: &mut Vec<u8>, value: u32) {
+    payload.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u64(payload: &mut Vec<u8>, value: u64) {
+    payload.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_string(payload: &mut Vec<u8>, text: &str) {
+    put_u32(payload, text.len() as u32);
+    payload.extend_from_slice(text.as_bytes());
+}
+
+// bounds-checked cursor over one section's payload; fields added later are simply left unread
+struct SectionReader<'a> {
+    tag: &'a [u8; 4],
+    payload: &'a [u8],
+    position: usize,
+}
+
+impl<'a> SectionReader<'a> {
+    fn new(tag: &'a [u8; 4], payload: &'a [u8]) -> Self {
+        Self { tag, payload, position: 0 }
+    }
+
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8], ReplayFileError> {
+        let end = self.position.checked_add(count).filter(|end| *end <= self.payload.len());
+        let Some(end) = end else {
+            return Err(
+                ReplayFileError::Corrupt(
+                    format!("section {} is shorter than its fields", String::from_utf8_lossy(self.tag))
+                )
+            );
+        };
+
+        let bytes = &self.payload[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn is_at_end(&self) -> bool {
+        self.position >= self.payload.len()
+    }
+
+    fn u8(&mut self) -> Result<u8, ReplayFileError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, ReplayFileError> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn u64(&mut self) -> Result<u64, ReplayFileError> {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(self.bytes(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    // an element count, checked against the bytes left so a damaged count cannot allocate wildly
+    fn length(&mut self, minimum_element_size: usize) -> Result<usize, ReplayFileError> {
+        let count = self.u64()?;
+        let remaining_bytes = (self.payload.len() - self.position) as u64;
+        if count.saturating_mul(minimum_element_size as u64) > remaining_bytes {
+            return Err(
+                ReplayFileError::Corrupt(
+                    format!("section {} claims more entries than it holds", String::from_utf8_lossy(self.tag))
+                )
+            );
+        }
+        Ok(count as usize)
+    }
+
+    fn string(&mut self) -> Result<String, ReplayFileError> {
+        let length = self.u32()? as usize;
+        let bytes = self.bytes(length)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| {
+            ReplayFileError::Corrupt("text field is not valid utf-8".to_owned())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replay_bytes(recorded_run: &RecordedRun) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_replay(&mut bytes, recorded_run).unwrap();
+        bytes
+    }
+
+    fn read_bytes(bytes: &[u8]) -> Result<RecordedRun, ReplayFileError> {
+        read_replay(bytes, LoadBudget::default())
+    }
+
+    fn recorded_run() -> RecordedRun {
+        let base_values = InputDistribution::Random.generate(40, Some(7));
+        let reversed_values = InputDistribution::Reversed.generate(40, Some(7));
+        let replays = vec![
+            SortingAlgorithmReplay::new(SortingAlgorithmKind::QuickSort, &base_values),
+            SortingAlgorithmReplay::new(SortingAlgorithmKind::StdSelectNth, &reversed_values)
+                .with_markers(vec![(0, "start".to_owned()), (3, "pivot placed".to_owned())]),
+            SortingAlgorithmReplay::new(SortingAlgorithmKind::MergeSort, &base_values)
+        ];
+
+        RecordedRun {
+            input_distribution: InputDistribution::Random,
+            frames_per_second: 45,
+            base_values: base_values.clone(),
+            replays,
+            replay_inputs: vec![base_values.clone(), reversed_values, base_values],
+            algorithm_kinds: vec![
+                Some(SortingAlgorithmKind::QuickSort),
+                Some(SortingAlgorithmKind::StdSelectNth),
+                None
+            ],
+        }
+    }
+
+    // a file with a valid header around the given sections
+    fn section_bytes(sections: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&MINIMUM_READER_VERSION.to_le_bytes());
+
+        let mut encoder = ZlibEncoder::new(bytes, Compression::default());
+        for (tag, payload) in sections {
+            write_section(&mut encoder, tag, payload).unwrap();
+        }
+        encoder.finish().unwrap()
+    }
+
+    fn run_payload() -> Vec<u8> {
+        let mut payload = Vec::new();
+        put_string(&mut payload, "random");
+        put_u32(&mut payload, 30);
+        payload
+    }
+
+    #[test]
+    fn round_trip_keeps_every_replay() {
+        let written_run = recorded_run();
+        let read_run = read_bytes(&replay_bytes(&written_run)).unwrap();
+
+        assert_eq!(read_run.input_distribution, written_run.input_distribution);
+        assert_eq!(read_run.frames_per_second, written_run.frames_per_second);
+        assert_eq!(read_run.base_values, written_run.base_values);
+        assert_eq!(read_run.replay_inputs, written_run.replay_inputs);
+        assert_eq!(read_run.algorithm_kinds, written_run.algorithm_kinds);
+        assert_eq!(read_run.replays.len(), written_run.replays.len());
+
+        for (read_replay, written_replay) in read_run.replays.iter().zip(&written_run.replays) {
+            assert_eq!(read_replay.name(), written_replay.name());
+            assert_eq!(read_replay.operations(), written_replay.operations());
+            assert_eq!(read_replay.markers(), written_replay.markers());
+            assert_eq!(read_replay.step_count(), written_replay.step_count());
+            assert_eq!(
+                read_replay.frame(read_replay.step_count() - 1),
+                written_replay.frame(written_replay.step_count() - 1)
+            );
+            assert_eq!(
+                read_replay.stats().operation_counts(),
+                written_replay.stats().operation_counts()
+            );
+        }
+    }
+
+    #[test]
+    fn truncated_file_is_corrupt() {
+        let bytes = replay_bytes(&recorded_run());
+
+        for length in [bytes.len() / 2, MAGIC.len() + 6] {
+            assert!(
+                matches!(read_bytes(&bytes[..length]), Err(ReplayFileError::Corrupt(_))),
+                "a file cut to {} of {} bytes was read",
+                length,
+                bytes.len()
+            );
+        }
+    }
+
+    #[test]
+    fn other_files_are_not_replay_files() {
+        assert!(matches!(read_bytes(b"init 3 1 2\nswap 0 1\n"), Err(ReplayFileError::NotAReplayFile)));
+    }
+
+    #[test]
+    fn newer_reader_version_is_refused() {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+        bytes.extend_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+
+        assert!(matches!(read_bytes(&bytes), Err(ReplayFileError::NewerVersion { .. })));
+    }
+
+    #[test]
+    fn damaged_sections_are_corrupt() {
+        let written_run = recorded_run();
+        let mut huge_input = Vec::new();
+        put_u64(&mut huge_input, u64::MAX);
+        let mut swap_out_of_range = written_run.base_values.clone();
+        swap_out_of_range.truncate(2);
+        let quick_sort_replay = &written_run.replays[0];
+        let mut bad_algorithm = algorithm_payload(quick_sort_replay, None);
+        // the operation tag right after the name, five counters and the operation count
+        let first_operation = 4 + quick_sort_replay.name().len() + 5 * 8 + 8;
+        bad_algorithm[first_operation] = 9;
+
+        // a million swaps of a million values: a small file, but a terabyte of frames
+        let mut too_many_frames = Vec::new();
+        put_string(&mut too_many_frames, "Huge");
+        for counter in [1_000_001, 0, 1_000_000, 0, 0] {
+            put_u64(&mut too_many_frames, counter);
+        }
+        put_u64(&mut too_many_frames, 1_000_000);
+        for _ in 0..1_000_000 {
+            too_many_frames.push(SWAP_TAG);
+            put_u32(&mut too_many_frames, 0);
+            put_u32(&mut too_many_frames, 1);
+        }
+
+        let damaged_files: [Vec<(&[u8; 4], Vec<u8>)>; 5] = [
+            // claims more values than the section holds
+            vec![(RUN_SECTION, run_payload()), (INPUT_SECTION, huge_input), (END_SECTION, Vec::new())],
+            // an algorithm with nothing to start from
+            vec![
+                (RUN_SECTION, run_payload()),
+                (ALGORITHM_SECTION, algorithm_payload(quick_sort_replay, None)),
+                (END_SECTION, Vec::new())
+            ],
+            // a swap past the end of the input
+            vec![
+                (RUN_SECTION, run_payload()),
+                (INPUT_SECTION, input_payload(&swap_out_of_range)),
+                (ALGORITHM_SECTION, algorithm_payload(quick_sort_replay, None)),
+                (END_SECTION, Vec::new())
+            ],
+            // an operation tag the format does not have
+            vec![
+                (RUN_SECTION, run_payload()),
+                (INPUT_SECTION, input_payload(&written_run.base_values)),
+                (ALGORITHM_SECTION, bad_algorithm),
+                (END_SECTION, Vec::new())
+            ],
+            // more frames than any replay may keep
+            vec![
+                (RUN_SECTION, run_payload()),
+                (INPUT_SECTION, input_payload(&vec![0; 1_000_000])),
+                (ALGORITHM_SECTION, too_many_frames),
+                (END_SECTION, Vec::new())
+            ]
+        ];
+
+        for (file_index, sections) in damaged_files.iter().enumerate() {
+            let result = read_bytes(&section_bytes(sections));
+            assert!(
+                matches!(result, Err(ReplayFileError::Corrupt(_))),
+                "damaged file {} gave {:?}",
+                file_index,
+                result.map(|read_run| read_run.replays.len())
+            );
+        }
+    }
+
+    #[test]
+    fn algorithm_sections_share_the_limits() {
+        let written_run = recorded_run();
+        let quick_sort_replay = &written_run.replays[0];
+        let replay_frame_bytes = frame_bytes(quick_sort_replay.step_count(), written_run.base_values.len());
+        let replay_operations = quick_sort_replay.operations().len();
+
+        let read_with = |algorithm_count: usize, frame_bytes: usize, operations: usize| {
+            let mut sections = vec![
+                (RUN_SECTION, run_payload()),
+                (INPUT_SECTION, input_payload(&written_run.base_values))
+            ];
+            for _ in 0..algorithm_count {
+                sections.push((ALGORITHM_SECTION, algorithm_payload(quick_sort_replay, None)));
+            }
+            sections.push((END_SECTION, Vec::new()));
+            read_replay(&section_bytes(&sections)[..], LoadBudget { frame_bytes, operations })
+        };
+
+        // one replay fits a budget of exactly its size, the same one twice does not
+        assert_eq!(read_with(1, replay_frame_bytes, replay_operations).unwrap().replays.len(), 1);
+        assert!(
+            matches!(
+                read_with(2, replay_frame_bytes, usize::MAX),
+                Err(ReplayFileError::Corrupt(message)) if message.contains("frames")
+            )
+        );
+        assert!(
+            matches!(
+                read_with(2, usize::MAX, replay_operations),
+                Err(ReplayFileError::Corrupt(message)) if message.contains("operations")
+            )
+        );
+    }
+
+    #[test]
+    fn oversized_section_is_refused_before_reading() {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&MINIMUM_READER_VERSION.to_le_bytes());
+        let mut encoder = ZlibEncoder::new(bytes, Compression::default());
+        encoder.write_all(INPUT_SECTION).unwrap();
+        encoder.write_all(&(MAX_SECTION_BYTES + 1).to_le_bytes()).unwrap();
+
+        let result = read_bytes(&encoder.finish().unwrap());
+        assert!(
+            matches!(&result, Err(ReplayFileError::Corrupt(message)) if message.starts_with("section INPT is")),
+            "{:?}",
+            result.map(|read_run| read_run.replays.len())
+        );
+    }
+}
@@ -0,0 +1,296 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use toml::{ Table, Value };
+
+use crate::chart::ChartStyle;
+use crate::engine::{ FRAMES_PER_SECOND_RANGE, NUMBER_OF_VALUES_RANGE };
+use crate::input::InputDistribution;
+use crate::sorting_algorithms::instance::AlgorithmInstance;
+
+// scenario files are TOML:
+//   optional top-level `auto_advance` and `hold`, then one [[scenario]] table per demo with
+//   `title`, `caption`, `algorithms` (cli specs like "quick:hoare"), `values`, `input`,
+//   `seed`, `fps`, `view` (a chart style) and `hold`.
+// unlike the settings file, mistakes are errors: a typo should show up while preparing a
+// lecture, not as a silently different demo in front of the audience.
+const PLAYLIST_KEYS: [&str; 3] = ["auto_advance", "hold", "scenario"];
+const SCENARIO_KEYS: [&str; 9] = [
+    "title",
+    "caption",
+    "algorithms",
+    "values",
+    "input",
+    "seed",
+    "fps",
+    "view",
+    "hold",
+];
+
+const DEFAULT_HOLD_SECONDS: f32 = 3.0;
+
+/// One prepared demo: what to run, how to show it and what to say about it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Scenario {
+    pub title: String,
+    /// Shown over the grid while the scenario plays.
+    pub caption: String,
+    pub algorithms: Vec<AlgorithmInstance>,
+    pub number_of_values: usize,
+    pub input_distribution: InputDistribution,
+    /// Fixed input seed, so the demo looks the same every time; random when `None`.
+    pub seed: Option<u64>,
+    pub frames_per_second: u32,
+    pub chart_style: ChartStyle,
+    /// Seconds the finished run stays on screen before the playlist moves on.
+    pub hold_seconds: f32,
+}
+
+/// Scenarios played in order, e.g. the demos of one lecture.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Playlist {
+    pub scenarios: Vec<Scenario>,
+    /// Whether a finished scenario moves on to the next one by itself.
+    pub auto_advance: bool,
+}
+
+#[derive(Debug)]
+pub enum ScenarioError {
+    Io(io::Error),
+    Parse(String),
+    /// A key of the playlist, or of the scenario with this 1-based number, is wrong.
+    Invalid {
+        scenario_number: Option<usize>,
+        message: String,
+    },
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioError::Io(error) => write!(formatter, "i/o error: {}", error),
+            ScenarioError::Parse(message) => write!(formatter, "invalid scenario file: {}", message),
+            ScenarioError::Invalid { scenario_number: Some(scenario_number), message } =>
+                write!(formatter, "scenario {}: {}", scenario_number, message),
+            ScenarioError::Invalid { scenario_number: None, message } =>
+                write!(formatter, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {}
+
+impl From<io::Error> for ScenarioError {
+    fn from(error: io::Error) -> Self {
+        ScenarioError::Io(error)
+    }
+}
+
+/// Reads a scenario file; a file with a single `[[scenario]]` is a playlist of one.
+pub fn read_playlist(path: &Path) -> Result<Playlist, ScenarioError> {
+    parse_playlist(&fs::read_to_string(path)?)
+}
+
+/// Parses the contents of a scenario file.
+pub fn parse_playlist(playlist_text: &str) -> Result<Playlist, ScenarioError> {
+    let table: Table = playlist_text.parse().map_err(|error: toml::de::Error| {
+        let message = match error.span() {
+            Some(span) => {
+                let line_number = playlist_text[..span.start.min(playlist_text.len())].matches('\n').count() + 1;
+                format!("line {}: {}", line_number, error.message())
+            }
+            None => error.message().to_owned(),
+        };
+        ScenarioError::Parse(message)
+    })?;
+
+    let invalid = |message: String| ScenarioError::Invalid { scenario_number: None, message };
+
+    check_keys(&table, &PLAYLIST_KEYS).map_err(invalid)?;
+    let auto_advance = optional(&table, "auto_advance", Value::as_bool, "true or false")
+        .map_err(invalid)?
+        .unwrap_or(true);
+    let default_hold_seconds = hold_seconds(&table).map_err(invalid)?.unwrap_or(DEFAULT_HOLD_SECONDS);
+
+    let scenario_tables = match table.get("scenario") {
+        Some(Value::Array(scenario_values)) => scenario_values,
+        Some(_) => {
+            return Err(invalid("'scenario' must be written as [[scenario]] tables".to_owned()));
+        }
+        None => {
+            return Err(invalid("the file has no [[scenario]] tables".to_owned()));
+        }
+    };
+
+    let scenarios = scenario_tables
+        .iter()
+        .enumerate()
+        .map(|(scenario_index, scenario_value)| {
+            let scenario_number = scenario_index + 1;
+            let scenario = match scenario_value.as_table() {
+                Some(scenario_table) => parse_scenario(scenario_table, default_hold_seconds),
+                None => Err("expected a table".to_owned()),
+            };
+            scenario.map_err(|message| ScenarioError::Invalid {
+                scenario_number: Some(scenario_number),
+                message,
+            })
+        })
+        .collect::<Result<Vec<Scenario>, ScenarioError>>()?;
+    if scenarios.is_empty() {
+        return Err(invalid("the file has no [[scenario]] tables".to_owned()));
+    }
+
+    Ok(Playlist { scenarios, auto_advance })
+}
+
+fn parse_scenario(table: &Table, default_hold_seconds: f32) -> Result<Scenario, String> {
+    check_keys(table, &SCENARIO_KEYS)?;
+
+    let title = optional(table, "title", Value::as_str, "a string")?.unwrap_or_default().to_owned();
+    let caption = optional(table, "caption", Value::as_str, "a string")?
+        .unwrap_or_default()
+        .trim()
+        .to_owned();
+
+    let algorithm_specs = optional(table, "algorithms", Value::as_array, "a list of algorithms")?
+        .ok_or_else(|| "'algorithms' is missing".to_owned())?;
+    let algorithms = algorithm_specs
+        .iter()
+        .map(|spec| {
+            spec.as_str()
+                .ok_or_else(|| "'algorithms' must hold strings like \"quick:hoare\"".to_owned())
+                .and_then(AlgorithmInstance::from_spec)
+        })
+        .collect::<Result<Vec<AlgorithmInstance>, String>>()?;
+    if algorithms.is_empty() {
+        return Err("'algorithms' is empty".to_owned());
+    }
+
+    // the same ranges as the settings panel, since a scenario runs like a run started there
+    let number_of_values = match optional(table, "values", Value::as_integer, "a number")? {
+        Some(number_of_values) => {
+            usize::try_from(number_of_values)
+                .ok()
+                .filter(|number_of_values| NUMBER_OF_VALUES_RANGE.contains(number_of_values))
+                .ok_or_else(|| {
+                    format!(
+                        "'values' is {}, expected {} to {} like the settings panel",
+                        number_of_values,
+                        NUMBER_OF_VALUES_RANGE.start(),
+                        NUMBER_OF_VALUES_RANGE.end()
+                    )
+                })?
+        }
+        None => 128,
+    };
+
+    let input_distribution = match optional(table, "input", Value::as_str, "an input shape")? {
+        Some(name) => InputDistribution::from_cli_name(name).ok_or_else(|| {
+            format!("unknown input '{}' (expected one of: {})", name, input_names())
+        })?,
+        None => InputDistribution::default(),
+    };
+
+    let seed = match optional(table, "seed", Value::as_integer, "a number")? {
+        Some(seed) if seed >= 0 => Some(seed as u64),
+        Some(seed) => {
+            return Err(format!("'seed' is {}, expected 0 or more", seed));
+        }
+        None => None,
+    };
+
+    let frames_per_second = match optional(table, "fps", Value::as_integer, "a number")? {
+        Some(frames_per_second) => {
+            u32::try_from(frames_per_second)
+                .ok()
+                .filter(|frames_per_second| FRAMES_PER_SECOND_RANGE.contains(frames_per_second))
+                .ok_or_else(|| {
+                    format!(
+                        "'fps' is {}, expected {} to {} like the settings panel",
+                        frames_per_second,
+                        FRAMES_PER_SECOND_RANGE.start(),
+                        FRAMES_PER_SECOND_RANGE.end()
+                    )
+                })?
+        }
+        None => 60,
+    };
+
+    let chart_style = match optional(table, "view", Value::as_str, "a chart style")? {
+        Some(name) => ChartStyle::from_cli_name(name).ok_or_else(|| {
+            format!("unknown view '{}' (expected one of: {})", name, chart_style_names())
+        })?,
+        None => ChartStyle::default(),
+    };
+
+    Ok(Scenario {
+        title,
+        caption,
+        algorithms,
+        number_of_values,
+        input_distribution,
+        seed,
+        frames_per_second,
+        chart_style,
+        hold_seconds: hold_seconds(table)?.unwrap_or(default_hold_seconds),
+    })
+}
+
+fn check_keys(table: &Table, known_keys: &[&str]) -> Result<(), String> {
+    match table.keys().find(|key| !known_keys.contains(&key.as_str())) {
+        Some(unknown_key) => {
+            Err(format!("unknown key '{}' (expected one of: {})", unknown_key, known_keys.join(", ")))
+        }
+        None => Ok(()),
+    }
+}
+
+// Ok(None) when the key is missing, an error when it has the wrong type
+fn optional<'a, T>(
+    table: &'a Table,
+    key: &str,
+    convert: fn(&'a Value) -> Option<T>,
+    expected: &str
+) -> Result<Option<T>, String> {
+    match table.get(key) {
+        Some(value) => convert(value).map(Some).ok_or_else(|| format!("'{}' must be {}", key, expected)),
+        None => Ok(None),
+    }
+}
+
+// integers are accepted too, e.g. `hold = 5`
+fn hold_seconds(table: &Table) -> Result<Option<f32>, String> {
+    let hold_seconds = match table.get("hold") {
+        Some(Value::Float(hold_seconds)) => *hold_seconds as f32,
+        Some(Value::Integer(hold_seconds)) => *hold_seconds as f32,
+        Some(_) => {
+            return Err("'hold' must be a number of seconds".to_owned());
+        }
+        None => {
+            return Ok(None);
+        }
+    };
+    if !(0.0..=3600.0).contains(&hold_seconds) {
+        return Err(format!("'hold' is {}, expected 0 to 3600 seconds", hold_seconds));
+    }
+    Ok(Some(hold_seconds))
+}
+
+fn input_names() -> String {
+    InputDistribution::ALL
+        .iter()
+        .map(|distribution| distribution.cli_name())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn chart_style_names() -> String {
+    ChartStyle::ALL
+        .iter()
+        .map(|chart_style| chart_style.cli_name())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
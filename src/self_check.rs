@@ -0,0 +1,405 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::input::InputDistribution;
+use crate::sorting_algorithms::instance::AlgorithmInstance;
+use crate::sorting_algorithms::recorder::SortOperation;
+use crate::sorting_algorithms::std_sorts::select_nth_index;
+use crate::sorting_algorithms::{ SortingAlgorithmKind, SortingAlgorithmReplay };
+
+/// A broken run can break every step after it; only this many violations are kept per run.
+pub const MAX_VIOLATIONS_PER_RUN: usize = 20;
+
+/// One place where a recording disagrees with itself or with its input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecorderViolation {
+    pub algorithm_name: String,
+    pub step: usize,
+    /// The array position at fault, when the problem has one.
+    pub index: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for RecorderViolation {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.index {
+            Some(index) => {
+                write!(
+                    formatter,
+                    "{}, step {}, index {}: {}",
+                    self.algorithm_name,
+                    self.step,
+                    index,
+                    self.message
+                )
+            }
+            None => write!(formatter, "{}, step {}: {}", self.algorithm_name, self.step, self.message),
+        }
+    }
+}
+
+/// Checks every step of a recorded run against `input`:
+///
+/// - the first frame is the input;
+/// - each later frame differs from the one before exactly as the next swap or write of the
+///   operation log says, and no step or operation is left over;
+/// - every frame reached by a swap, and the last one, is a permutation of the input, and every
+///   write stores a value of the input (writes may hold a value twice while it moves through
+///   a buffer the array does not show);
+/// - the last frame equals `expected_result`, usually the input sorted by `std`.
+///
+/// Once a step disagrees with the operation log, later steps are not matched against it.
+pub fn check_replay(
+    replay: &SortingAlgorithmReplay,
+    input: &[u32],
+    expected_result: &[u32]
+) -> Vec<RecorderViolation> {
+    let mut violations = Vec::new();
+    let mut report = |step: usize, index: Option<usize>, message: String| {
+        violations.push(RecorderViolation {
+            algorithm_name: replay.name().to_owned(),
+            step,
+            index,
+            message,
+        });
+    };
+
+    let first_frame = replay.frame(0);
+    if first_frame.len() != input.len() {
+        report(0, None, format!("has {} values, the input has {}", first_frame.len(), input.len()));
+        return violations;
+    }
+    let mut sorted_input = input.to_vec();
+    sorted_input.sort_unstable();
+
+    if let Some(index) = first_difference(first_frame, input) {
+        report(
+            0,
+            Some(index),
+            format!("starts with {}, the input has {}", first_frame[index], input[index])
+        );
+    }
+
+    // how often each value is in the current frame, minus how often it is in the input
+    let mut value_surplus: HashMap<u32, i64> = HashMap::new();
+    for (index, value) in first_frame.iter().enumerate() {
+        *value_surplus.entry(*value).or_default() += 1;
+        *value_surplus.entry(input[index]).or_default() -= 1;
+    }
+    let mut unbalanced_value_count = value_surplus
+        .values()
+        .filter(|surplus| **surplus != 0)
+        .count();
+
+    let mut changing_operations = replay
+        .operations()
+        .iter()
+        .filter(|operation| !matches!(operation, SortOperation::Compare));
+    let mut is_following_log = true;
+
+    for step in 1..replay.step_count() {
+        let previous_frame = replay.frame(step - 1);
+        let frame = replay.frame(step);
+        if frame.len() != input.len() {
+            report(step, None, format!("has {} values, the input has {}", frame.len(), input.len()));
+            return violations;
+        }
+
+        for (index, value) in frame.iter().enumerate() {
+            let previous_value = previous_frame[index];
+            if *value != previous_value {
+                for (changed_value, change) in [(previous_value, -1), (*value, 1)] {
+                    let surplus = value_surplus.entry(changed_value).or_default();
+                    let was_balanced = *surplus == 0;
+                    *surplus += change;
+                    if was_balanced {
+                        unbalanced_value_count += 1;
+                    } else if *surplus == 0 {
+                        unbalanced_value_count -= 1;
+                    }
+                }
+            }
+        }
+
+        let operation = if is_following_log { changing_operations.next().copied() } else { None };
+        match operation {
+            None if is_following_log => {
+                report(step, None, "changes the array without a recorded swap or write".to_owned());
+                is_following_log = false;
+            }
+            None => {}
+            Some(operation) => {
+                if let Some((index, message)) = operation_mismatch(operation, previous_frame, frame) {
+                    report(step, index, format!("{}; later steps are not matched", message));
+                    is_following_log = false;
+                } else if let SortOperation::Write(index, value) = operation {
+                    if sorted_input.binary_search(&value).is_err() {
+                        report(step, Some(index), format!("writes {}, which is not in the input", value));
+                    }
+                } else if unbalanced_value_count > 0 {
+                    report(
+                        step,
+                        surplus_index(frame, &value_surplus),
+                        "is not a permutation of the input".to_owned()
+                    );
+                }
+            }
+        }
+    }
+
+    let last_step = replay.step_count().saturating_sub(1);
+    let unmatched_operation_count = changing_operations.count();
+    if is_following_log && unmatched_operation_count > 0 {
+        report(
+            last_step,
+            None,
+            format!("{} swaps or writes are recorded after the last step", unmatched_operation_count)
+        );
+    }
+
+    let last_frame = replay.frame(last_step);
+    if unbalanced_value_count > 0 {
+        report(
+            last_step,
+            surplus_index(last_frame, &value_surplus),
+            "ends without every input value".to_owned()
+        );
+    }
+    if let Some(index) = first_difference(last_frame, expected_result) {
+        report(
+            last_step,
+            Some(index),
+            format!("ends with {}, expected {}", last_frame[index], expected_result[index])
+        );
+    }
+
+    violations.truncate(MAX_VIOLATIONS_PER_RUN);
+    violations
+}
+
+/// What `std` leaves after running the algorithm's job on `input`: the input partitioned
+/// around its median for `select_nth_unstable`, otherwise sorted, also when the algorithm is
+/// not known.
+pub fn expected_result(algorithm_kind: Option<SortingAlgorithmKind>, input: &[u32]) -> Vec<u32> {
+    let mut result = input.to_vec();
+    match algorithm_kind {
+        Some(SortingAlgorithmKind::StdSelectNth) => {
+            if !result.is_empty() {
+                result.select_nth_unstable(select_nth_index(input.len()));
+            }
+        }
+        _ => result.sort_unstable(),
+    }
+    result
+}
+
+// where the step from `previous_frame` to `frame` is not what `operation` does, and how
+fn operation_mismatch(
+    operation: SortOperation,
+    previous_frame: &[u32],
+    frame: &[u32]
+) -> Option<(Option<usize>, String)> {
+    let mut expected_frame = previous_frame.to_vec();
+    match operation {
+        SortOperation::Compare => {}
+        SortOperation::Swap(first_index, second_index) => {
+            if first_index >= frame.len() || second_index >= frame.len() {
+                let message = format!(
+                    "swaps {} and {} in an array of {} values",
+                    first_index,
+                    second_index,
+                    frame.len()
+                );
+                return Some((None, message));
+            }
+            expected_frame.swap(first_index, second_index);
+        }
+        SortOperation::Write(index, value) => {
+            if index >= frame.len() {
+                let message = format!("writes index {} in an array of {} values", index, frame.len());
+                return Some((None, message));
+            }
+            expected_frame[index] = value;
+        }
+    }
+
+    first_difference(frame, &expected_frame).map(|index| {
+        let operation_name = match operation {
+            SortOperation::Compare => "a comparison".to_owned(),
+            SortOperation::Swap(first_index, second_index) => {
+                format!("swap {} {}", first_index, second_index)
+            }
+            SortOperation::Write(index, value) => format!("write {} {}", index, value),
+        };
+        (
+            Some(index),
+            format!("holds {} where {} leaves {}", frame[index], operation_name, expected_frame[index])
+        )
+    })
+}
+
+fn first_difference(values: &[u32], expected_values: &[u32]) -> Option<usize> {
+    values
+        .iter()
+        .zip(expected_values)
+        .position(|(value, expected_value)| value != expected_value)
+}
+
+// the first position holding a value the frame has more often than the input
+fn surplus_index(frame: &[u32], value_surplus: &HashMap<u32, i64>) -> Option<usize> {
+    frame.iter().position(|value| value_surplus.get(value).is_some_and(|surplus| *surplus > 0))
+}
+
+/// Sizes [`run_self_check`] accepts: every run keeps a frame per swap or write while it is
+/// checked, which for the O(n²) sorts at 500 values is already about 250 MB.
+pub const SELF_CHECK_SIZE_RANGE: std::ops::RangeInclusive<usize> = 32..=500;
+
+/// Settings for [`run_self_check`].
+#[derive(Clone, Debug)]
+pub struct SelfCheckOptions {
+    pub algorithms: Vec<AlgorithmInstance>,
+    /// See [`SELF_CHECK_SIZE_RANGE`] for why this should stay small.
+    pub number_of_values: usize,
+    /// Every algorithm sorts each of these, unless its instance chose its own shape.
+    pub input_distributions: Vec<InputDistribution>,
+    // inputs per shape, seeded first_seed, first_seed + 1, ...
+    pub seed_count: usize,
+    pub first_seed: u64,
+}
+
+impl Default for SelfCheckOptions {
+    fn default() -> Self {
+        Self {
+            algorithms: SortingAlgorithmKind::ALL
+                .into_iter()
+                .map(AlgorithmInstance::new)
+                .collect(),
+            number_of_values: 64,
+            input_distributions: InputDistribution::ALL.to_vec(),
+            seed_count: 3,
+            first_seed: 0,
+        }
+    }
+}
+
+/// One checked recording and the input it sorted.
+#[derive(Clone, Debug)]
+pub struct SelfCheckRun {
+    pub algorithm_label: String,
+    pub input_distribution: InputDistribution,
+    pub seed: u64,
+    pub step_count: usize,
+    pub violations: Vec<RecorderViolation>,
+}
+
+/// Records and checks every algorithm on every input shape and seed.
+pub fn run_self_check(options: &SelfCheckOptions) -> Vec<SelfCheckRun> {
+    let mut runs = Vec::new();
+
+    for algorithm_instance in &options.algorithms {
+        let input_distributions = match algorithm_instance.input_distribution {
+            Some(input_distribution) => vec![input_distribution],
+            None => options.input_distributions.clone(),
+        };
+
+        for input_distribution in input_distributions {
+            for seed_offset in 0..options.seed_count.max(1) {
+                let seed = options.first_seed.wrapping_add(seed_offset as u64);
+                let input = input_distribution.generate(options.number_of_values, Some(seed));
+                let replay = SortingAlgorithmReplay::for_instance(algorithm_instance, &input);
+
+                runs.push(SelfCheckRun {
+                    algorithm_label: algorithm_instance.label(),
+                    input_distribution,
+                    seed,
+                    step_count: replay.step_count(),
+                    violations: check_replay(
+                        &replay,
+                        &input,
+                        &expected_result(Some(algorithm_instance.kind), &input)
+                    ),
+                });
+            }
+        }
+    }
+
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::sorting_algorithms::recorder::OperationCounts;
+    use crate::stats::SortStats;
+
+    fn replay(input: &[u32], operations: Vec<SortOperation>) -> SortingAlgorithmReplay {
+        let stats = SortStats::from_measurements(
+            operations.len() as u64 + 1,
+            OperationCounts::default(),
+            Duration::ZERO
+        );
+        SortingAlgorithmReplay::from_operations("Test".to_owned(), input.to_vec(), operations, stats).unwrap()
+    }
+
+    // (step, index) of every violation, in order
+    fn places(violations: &[RecorderViolation]) -> Vec<(usize, Option<usize>)> {
+        violations
+            .iter()
+            .map(|violation| (violation.step, violation.index))
+            .collect()
+    }
+
+    #[test]
+    fn correct_recordings_have_no_violations() {
+        let input = InputDistribution::Random.generate(64, Some(3));
+        for algorithm_kind in [SortingAlgorithmKind::QuickSort, SortingAlgorithmKind::StdSelectNth] {
+            let replay = SortingAlgorithmReplay::new(algorithm_kind, &input);
+            let violations = check_replay(&replay, &input, &expected_result(Some(algorithm_kind), &input));
+            assert_eq!(violations, Vec::new(), "{:?}", algorithm_kind);
+        }
+    }
+
+    #[test]
+    fn wrong_swap_leaves_a_wrong_last_frame() {
+        let input = [2, 1, 3];
+        let replay = replay(&input, vec![SortOperation::Compare, SortOperation::Swap(1, 2)]);
+        let violations = check_replay(&replay, &input, &expected_result(None, &input));
+
+        assert_eq!(places(&violations), [(1, Some(0))]);
+        assert_eq!(violations[0].message, "ends with 2, expected 1");
+    }
+
+    #[test]
+    fn duplicated_value_is_not_a_permutation() {
+        let input = [3, 1, 2];
+        let replay = replay(&input, vec![SortOperation::Write(0, 1), SortOperation::Swap(1, 2)]);
+        let violations = check_replay(&replay, &input, &expected_result(None, &input));
+
+        // the write itself may hold a value twice; the swap after it may not
+        assert_eq!(places(&violations), [(2, Some(0)), (2, Some(0)), (2, Some(2))]);
+        assert_eq!(violations[0].message, "is not a permutation of the input");
+        assert_eq!(violations[1].message, "ends without every input value");
+        assert_eq!(violations[2].message, "ends with 1, expected 3");
+    }
+
+    #[test]
+    fn written_value_must_come_from_the_input() {
+        let input = [1, 2];
+        let replay = replay(&input, vec![SortOperation::Write(1, 9), SortOperation::Write(1, 2)]);
+        let violations = check_replay(&replay, &input, &expected_result(None, &input));
+
+        assert_eq!(places(&violations), [(1, Some(1))]);
+        assert_eq!(violations[0].message, "writes 9, which is not in the input");
+    }
+
+    #[test]
+    fn first_frame_must_be_the_input() {
+        let replay = replay(&[1, 2, 3], Vec::new());
+        let violations = check_replay(&replay, &[1, 3, 2], &[1, 2, 3]);
+
+        assert_eq!(places(&violations), [(0, Some(1))]);
+        assert_eq!(violations[0].message, "starts with 2, the input has 3");
+    }
+}
@@ -0,0 +1,701 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use std::time::{ Duration, Instant };
+
+use crate::sorting_algorithms::recorder::SortOperation;
+use crate::sorting_algorithms::SortingAlgorithmReplay;
+
+/// Most notes one panel plays per engine tick; longer runs of operations are thinned out.
+pub const MAX_NOTES_PER_TICK: usize = 4;
+
+/// Most notes of one verification sweep.
+pub const MAX_SWEEP_NOTES: usize = 48;
+
+/// Notes a [`VoicePool`] plays at once.
+pub const VOICE_COUNT: usize = 48;
+
+// semitones of each scale degree above the root
+const C_MAJOR_DEGREES: [i32; 7] = [0, 2, 4, 5, 7, 9, 11];
+const A_MINOR_DEGREES: [i32; 7] = [0, 2, 3, 5, 7, 8, 10];
+const PENTATONIC_DEGREES: [i32; 5] = [0, 2, 4, 7, 9];
+const CHROMATIC_DEGREES: [i32; 12] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+// every scale spans three octaves from a4
+const OCTAVE_COUNT: i32 = 3;
+const BASE_FREQUENCY: f32 = 440.0;
+
+// spatial audio constants
+const LEFT_EAR_POS: [f32; 3] = [-1.0, 0.0, 0.0];
+const RIGHT_EAR_POS: [f32; 3] = [1.0, 0.0, 0.0];
+// pushes the sound emitter to the front of the listener
+const EMITTER_Z: f32 = 1.0;
+
+/// What a note's pitch follows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum PitchSource {
+    /// The element's position: the same place always sounds the same.
+    #[default]
+    Index,
+    /// The element's value: the pitch rises as the array gets sorted.
+    Value,
+}
+
+impl PitchSource {
+    pub const ALL: [PitchSource; 2] = [PitchSource::Index, PitchSource::Value];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            PitchSource::Index => "Position",
+            PitchSource::Value => "Value",
+        }
+    }
+
+    pub fn cli_name(&self) -> &'static str {
+        match self {
+            PitchSource::Index => "index",
+            PitchSource::Value => "value",
+        }
+    }
+
+    pub fn from_cli_name(name: &str) -> Option<PitchSource> {
+        PitchSource::ALL
+            .into_iter()
+            .find(|pitch_source| pitch_source.cli_name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// Pitches a note can take, over three octaves from A4.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Scale {
+    #[default]
+    Major,
+    Minor,
+    Pentatonic,
+    Chromatic,
+    /// No steps: the pitch glides with the position or value.
+    Continuous,
+}
+
+impl Scale {
+    pub const ALL: [Scale; 5] = [
+        Scale::Major,
+        Scale::Minor,
+        Scale::Pentatonic,
+        Scale::Chromatic,
+        Scale::Continuous,
+    ];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Scale::Major => "Major",
+            Scale::Minor => "Minor",
+            Scale::Pentatonic => "Pentatonic",
+            Scale::Chromatic => "Chromatic",
+            Scale::Continuous => "Glissando",
+        }
+    }
+
+    pub fn cli_name(&self) -> &'static str {
+        match self {
+            Scale::Major => "major",
+            Scale::Minor => "minor",
+            Scale::Pentatonic => "pentatonic",
+            Scale::Chromatic => "chromatic",
+            Scale::Continuous => "glissando",
+        }
+    }
+
+    pub fn from_cli_name(name: &str) -> Option<Scale> {
+        Scale::ALL.into_iter().find(|scale| scale.cli_name().eq_ignore_ascii_case(name))
+    }
+
+    /// Frequency for a position on the scale, 0 the lowest note and 1 the highest.
+    pub fn frequency(&self, normalized_pitch: f32) -> f32 {
+        let x = normalized_pitch.clamp(0.0, 1.0);
+        let degrees: &[i32] = match self {
+            Scale::Major => &C_MAJOR_DEGREES,
+            Scale::Minor => &A_MINOR_DEGREES,
+            Scale::Pentatonic => &PENTATONIC_DEGREES,
+            Scale::Chromatic => &CHROMATIC_DEGREES,
+            Scale::Continuous => {
+                return BASE_FREQUENCY * (2.0f32).powf(x * (OCTAVE_COUNT as f32));
+            }
+        };
+
+        // e.g. 3 octaves of c major = 21 steps, mapped to 0..20
+        let degrees_per_octave = degrees.len() as i32;
+        let total_steps = OCTAVE_COUNT * degrees_per_octave;
+        let step_index = (x * ((total_steps - 1) as f32)).round() as i32;
+        // split into octave and degree
+        let octave = step_index / degrees_per_octave;
+        let degree_index = (step_index % degrees_per_octave).max(0);
+        // total = octave * 12 + degree
+        let total_semitones = octave * 12 + degrees[degree_index as usize];
+        freq_from_semitones(BASE_FREQUENCY, total_semitones)
+    }
+}
+
+/// Shape of a note's wave; each sounds as a different instrument.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Waveform {
+    #[default]
+    Sine,
+    Triangle,
+    Square,
+    Saw,
+}
+
+impl Waveform {
+    pub const ALL: [Waveform; 4] = [Waveform::Sine, Waveform::Triangle, Waveform::Square, Waveform::Saw];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Waveform::Sine => "Sine",
+            Waveform::Triangle => "Triangle",
+            Waveform::Square => "Square",
+            Waveform::Saw => "Saw",
+        }
+    }
+
+    pub fn cli_name(&self) -> &'static str {
+        match self {
+            Waveform::Sine => "sine",
+            Waveform::Triangle => "triangle",
+            Waveform::Square => "square",
+            Waveform::Saw => "saw",
+        }
+    }
+
+    pub fn from_cli_name(name: &str) -> Option<Waveform> {
+        Waveform::ALL.into_iter().find(|waveform| waveform.cli_name().eq_ignore_ascii_case(name))
+    }
+
+    /// The wave at `phase` (in cycles, 0..1), between -1 and 1. Square and saw are scaled
+    /// down to sound about as loud as the sine.
+    pub fn sample(&self, phase: f32) -> f32 {
+        match self {
+            Waveform::Sine => (phase * std::f32::consts::TAU).sin(),
+            Waveform::Triangle => 1.0 - 4.0 * (phase - 0.5).abs(),
+            Waveform::Square => {
+                if phase < 0.5 { 0.5 } else { -0.5 }
+            }
+            Waveform::Saw => 0.6 * (2.0 * phase - 1.0),
+        }
+    }
+}
+
+/// How operations are turned into sound.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SonificationSettings {
+    pub pitch_source: PitchSource,
+    pub scale: Scale,
+    /// Timbre of comparisons.
+    pub compare_waveform: Waveform,
+    /// Timbre of swaps and writes.
+    pub change_waveform: Waveform,
+    pub play_comparisons: bool,
+    /// From the start of a note until it has faded out.
+    pub note_length_ms: f32,
+}
+
+impl Default for SonificationSettings {
+    fn default() -> Self {
+        Self {
+            pitch_source: PitchSource::Index,
+            scale: Scale::Major,
+            compare_waveform: Waveform::Triangle,
+            change_waveform: Waveform::Sine,
+            play_comparisons: true,
+            note_length_ms: 45.0,
+        }
+    }
+}
+
+impl SonificationSettings {
+    pub const NOTE_LENGTH_RANGE_MS: std::ops::RangeInclusive<f32> = 10.0..=500.0;
+
+    /// Whether a note of this kind is played at all.
+    pub fn plays(&self, note_kind: NoteKind) -> bool {
+        self.play_comparisons || note_kind != NoteKind::Compare
+    }
+
+    /// Sweep notes always follow the position, so a sweep rises whichever source is chosen.
+    pub fn frequency(&self, note_event: &NoteEvent) -> f32 {
+        let normalized_pitch = match (note_event.kind, self.pitch_source) {
+            (NoteKind::Sweep, _) | (_, PitchSource::Index) => note_event.normalized_index,
+            (_, PitchSource::Value) => note_event.normalized_value,
+        };
+        self.scale.frequency(normalized_pitch)
+    }
+
+    pub fn waveform(&self, note_kind: NoteKind) -> Waveform {
+        match note_kind {
+            NoteKind::Compare => self.compare_waveform,
+            NoteKind::Swap | NoteKind::Write | NoteKind::Sweep => self.change_waveform,
+        }
+    }
+
+    /// Attack and total length of a note.
+    pub fn envelope(&self) -> (Duration, Duration) {
+        let note_length = Duration::from_secs_f32(
+            self.note_length_ms.clamp(
+                *Self::NOTE_LENGTH_RANGE_MS.start(),
+                *Self::NOTE_LENGTH_RANGE_MS.end()
+            ) / 1000.0
+        );
+        let attack = Duration::from_millis(5).min(note_length / 4);
+        (attack, note_length)
+    }
+}
+
+/// How one panel is heard: where it sits in the stereo field and whether it plays at all.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PanelVoice {
+    /// Centre of the panel's notes, 0 far left and 1 far right.
+    pub pan: f32,
+    /// Width of the stereo field the panel's element positions are spread over.
+    pub pan_width: f32,
+    pub is_audible: bool,
+}
+
+impl Default for PanelVoice {
+    // a single panel spread over the whole field, as positions alone used to be
+    fn default() -> Self {
+        Self {
+            pan: 0.5,
+            pan_width: 1.0,
+            is_audible: true,
+        }
+    }
+}
+
+impl PanelVoice {
+    /// A panel in `column_index` of a grid with `column_count` columns sounds from that
+    /// column's share of the stereo field, left to right.
+    pub fn for_grid_column(column_index: usize, column_count: usize, is_audible: bool) -> Self {
+        let column_count = column_count.max(1);
+        Self {
+            pan: ((column_index.min(column_count - 1) as f32) + 0.5) / (column_count as f32),
+            pan_width: 1.0 / (column_count as f32),
+            is_audible,
+        }
+    }
+
+    /// Stereo position of a note at `normalized_index` within the panel's array.
+    pub fn note_pan(&self, normalized_index: f32) -> f32 {
+        (self.pan + (normalized_index.clamp(0.0, 1.0) - 0.5) * self.pan_width).clamp(0.0, 1.0)
+    }
+}
+
+/// The operation a note stands for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NoteKind {
+    Compare,
+    Swap,
+    Write,
+    /// A position passed by the verification sweep at the end of a run.
+    Sweep,
+}
+
+/// One note of the sonification, timed on the engine clock.
+#[derive(Clone, Copy, Debug)]
+pub struct NoteEvent {
+    /// When the note starts; notes of one tick are spread over the tick.
+    pub time: Instant,
+    pub panel_id: usize,
+    pub kind: NoteKind,
+    /// Position of the element, 0 at the left end of the array and 1 at the right.
+    pub normalized_index: f32,
+    /// Value of the element relative to the largest in the array.
+    pub normalized_value: f32,
+}
+
+/// Which logged operations led to each frame of a replay, so a step can sound every
+/// comparison made on the way to its swap or write.
+pub struct StepOperations {
+    // one range per frame; all empty when frames and operations do not line up, e.g. for
+    // frames recorded directly
+    ranges: Vec<Range<usize>>,
+    maximum_value: u32,
+}
+
+impl StepOperations {
+    pub fn from_replay(replay: &SortingAlgorithmReplay) -> Self {
+        let operations = replay.operations();
+        let step_count = replay.step_count();
+        let maximum_value = replay.frame(0).iter().copied().max().unwrap_or(0);
+
+        // every swap and write pushes one frame after the input
+        let changing_operation_count = operations
+            .iter()
+            .filter(|operation| !matches!(operation, SortOperation::Compare))
+            .count();
+        if changing_operation_count + 1 != step_count {
+            return Self {
+                ranges: Vec::new(),
+                maximum_value,
+            };
+        }
+
+        let mut ranges = Vec::with_capacity(step_count);
+        ranges.push(0..0);
+        let mut range_start = 0;
+        for (operation_index, operation) in operations.iter().enumerate() {
+            if !matches!(operation, SortOperation::Compare) {
+                ranges.push(range_start..operation_index + 1);
+                range_start = operation_index + 1;
+            }
+        }
+        // comparisons after the last change, e.g. a final pass that finds nothing to do
+        if let Some(last_range) = ranges.last_mut() {
+            if step_count > 1 {
+                last_range.end = operations.len();
+            }
+        }
+
+        Self { ranges, maximum_value }
+    }
+
+    /// Notes for reaching `step_index`, spread from `tick_time` over `tick_duration`.
+    pub fn notes_for_step(
+        &self,
+        replay: &SortingAlgorithmReplay,
+        step_index: usize,
+        panel_id: usize,
+        tick_time: Instant,
+        tick_duration: Duration
+    ) -> Vec<NoteEvent> {
+        if step_index == 0 || step_index >= replay.step_count() {
+            return Vec::new();
+        }
+
+        let values = replay.frame(step_index);
+        let Some(operation_range) = self.ranges.get(step_index).filter(|range| !range.is_empty()) else {
+            // no operation log to follow: sound the elements that changed
+            let changed_indices = crate::chart::changed_indices(replay.frame(step_index - 1), values);
+            return notes_for_changes(panel_id, values, &changed_indices, tick_time, tick_duration);
+        };
+
+        let operations = &replay.operations()[operation_range.clone()];
+        let selected_operations = thin_out(operations, MAX_NOTES_PER_TICK);
+
+        // comparisons carry no position, so they sound at the element the step goes on to change
+        let changed_index = match operations.last() {
+            Some(SortOperation::Swap(first_index, _)) | Some(SortOperation::Write(first_index, _)) => {
+                *first_index
+            }
+            _ => 0,
+        };
+
+        let note_count = selected_operations.len();
+        selected_operations
+            .into_iter()
+            .enumerate()
+            .map(|(note_index, operation)| {
+                let (kind, index) = match *operation {
+                    SortOperation::Compare => (NoteKind::Compare, changed_index),
+                    SortOperation::Swap(first_index, _) => (NoteKind::Swap, first_index),
+                    SortOperation::Write(index, _) => (NoteKind::Write, index),
+                };
+                NoteEvent {
+                    time: tick_time + tick_duration.mul_f32((note_index as f32) / (note_count as f32)),
+                    panel_id,
+                    kind,
+                    normalized_index: normalized_position(index, values.len()),
+                    normalized_value: normalized_value(values.get(index).copied(), self.maximum_value),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Notes of an animation whose frame `i` shows `step_indices[i]` for `frame_duration` from
+/// `start_time` on, for offline rendering. The steps since the previous frame sound during
+/// it, with at most [`MAX_NOTES_PER_TICK`] notes per panel like one tick of the engine.
+pub fn notes_for_frames(
+    algorithm_replays: &[SortingAlgorithmReplay],
+    step_indices: &[usize],
+    start_time: Instant,
+    frame_duration: Duration,
+    sonification_settings: &SonificationSettings
+) -> Vec<NoteEvent> {
+    let step_operations: Vec<StepOperations> = algorithm_replays
+        .iter()
+        .map(StepOperations::from_replay)
+        .collect();
+
+    let mut note_events = Vec::new();
+    let mut previous_step_index = 0;
+    for (frame_index, step_index) in step_indices.iter().copied().enumerate() {
+        let frame_time = start_time + frame_duration.mul_f64(frame_index as f64);
+        let first_step_index = previous_step_index + 1;
+        previous_step_index = step_index;
+        if step_index < first_step_index {
+            continue;
+        }
+        let step_duration = frame_duration / ((step_index - first_step_index + 1) as u32);
+
+        for (panel_id, (replay, operations)) in algorithm_replays.iter().zip(&step_operations).enumerate() {
+            let frame_notes: Vec<NoteEvent> = (first_step_index..=step_index)
+                .flat_map(|sounded_step_index| {
+                    let step_offset = (sounded_step_index - first_step_index) as u32;
+                    operations.notes_for_step(
+                        replay,
+                        sounded_step_index,
+                        panel_id,
+                        frame_time + step_duration * step_offset,
+                        step_duration
+                    )
+                })
+                .filter(|note_event| sonification_settings.plays(note_event.kind))
+                .collect();
+            note_events.extend(thin_out(&frame_notes, MAX_NOTES_PER_TICK).into_iter().copied());
+        }
+    }
+
+    note_events
+}
+
+/// The verification sweep across a finished array: one note per position from left to right,
+/// at most [`MAX_SWEEP_NOTES`] spread over `sweep_duration`, rising in pitch.
+pub fn notes_for_sweep(
+    panel_id: usize,
+    values: &[u32],
+    start_time: Instant,
+    sweep_duration: Duration
+) -> Vec<NoteEvent> {
+    let maximum_value = values.iter().copied().max().unwrap_or(0);
+    let indices: Vec<usize> = (0..values.len()).collect();
+    let swept_indices = thin_out(&indices, MAX_SWEEP_NOTES);
+
+    let note_count = swept_indices.len();
+    swept_indices
+        .into_iter()
+        .enumerate()
+        .map(|(note_index, &index)| NoteEvent {
+            time: start_time + sweep_duration.mul_f32((note_index as f32) / (note_count as f32)),
+            panel_id,
+            kind: NoteKind::Sweep,
+            normalized_index: normalized_position(index, values.len()),
+            normalized_value: normalized_value(values.get(index).copied(), maximum_value),
+        })
+        .collect()
+}
+
+/// Write notes for elements that changed, e.g. on a live panel, at most
+/// [`MAX_NOTES_PER_TICK`] spread over the tick.
+pub fn notes_for_changes(
+    panel_id: usize,
+    values: &[u32],
+    changed_indices: &[usize],
+    tick_time: Instant,
+    tick_duration: Duration
+) -> Vec<NoteEvent> {
+    let maximum_value = values.iter().copied().max().unwrap_or(0);
+    let selected_indices = thin_out(changed_indices, MAX_NOTES_PER_TICK);
+    let note_count = selected_indices.len();
+
+    selected_indices
+        .into_iter()
+        .enumerate()
+        .map(|(note_index, &index)| NoteEvent {
+            time: tick_time + tick_duration.mul_f32((note_index as f32) / (note_count as f32)),
+            panel_id,
+            kind: NoteKind::Write,
+            normalized_index: normalized_position(index, values.len()),
+            normalized_value: normalized_value(values.get(index).copied(), maximum_value),
+        })
+        .collect()
+}
+
+/// One sounding note: a wave with a linear attack and release, panned in stereo.
+#[derive(Clone, Debug)]
+pub struct Voice {
+    panel_id: usize,
+    phase: f32,
+    // phase advance per frame, in cycles
+    phase_step: f32,
+    amplitude: f32,
+    gains: [f32; 2],
+    waveform: Waveform,
+    attack_frames: u32,
+    length_frames: u32,
+    age_frames: u32,
+}
+
+impl Voice {
+    pub fn new(
+        note_event: &NoteEvent,
+        panel_voice: &PanelVoice,
+        sonification_settings: &SonificationSettings,
+        sample_rate: u32
+    ) -> Self {
+        let frame_count = |duration: Duration| (duration.as_secs_f64() * (sample_rate as f64)) as u32;
+        let (attack, length) = sonification_settings.envelope();
+        Self {
+            panel_id: note_event.panel_id,
+            phase: 0.0,
+            phase_step: sonification_settings.frequency(note_event) / (sample_rate as f32),
+            amplitude: note_amplitude(note_event.normalized_value),
+            gains: stereo_gains(panel_voice.note_pan(note_event.normalized_index)),
+            waveform: sonification_settings.waveform(note_event.kind),
+            attack_frames: frame_count(attack),
+            length_frames: frame_count(length).max(1),
+            age_frames: 0,
+        }
+    }
+
+    /// Left and right samples of the next frame.
+    pub fn next_frame(&mut self) -> [f32; 2] {
+        let attack_frames = self.attack_frames as f32;
+        let length_frames = self.length_frames as f32;
+        let age = self.age_frames as f32;
+
+        // linear attack, then a linear release to silence
+        let envelope = if age < attack_frames {
+            age / attack_frames
+        } else {
+            ((length_frames - age) / (length_frames - attack_frames)).max(0.0)
+        };
+
+        let sample = self.waveform.sample(self.phase) * self.amplitude * envelope;
+        self.phase = (self.phase + self.phase_step).fract();
+        self.age_frames += 1;
+        [sample * self.gains[0], sample * self.gains[1]]
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.age_frames >= self.length_frames
+    }
+}
+
+/// Up to [`VOICE_COUNT`] voices mixed into one stereo stream, shared by the live mixer and
+/// offline rendering so both sound the same.
+#[derive(Clone, Debug, Default)]
+pub struct VoicePool {
+    voices: Vec<Voice>,
+}
+
+impl VoicePool {
+    pub fn new() -> Self {
+        Self { voices: Vec::with_capacity(VOICE_COUNT) }
+    }
+
+    /// Starts a voice. When all are busy, the panel holding the most loses its oldest, so a
+    /// busy panel cannot drown out the others.
+    pub fn start(&mut self, voice: Voice) {
+        if self.voices.len() >= VOICE_COUNT {
+            let mut voices_per_panel: HashMap<usize, usize> = HashMap::new();
+            for playing_voice in &self.voices {
+                *voices_per_panel.entry(playing_voice.panel_id).or_default() += 1;
+            }
+            let busiest_panel_id = voices_per_panel
+                .into_iter()
+                // on a tie the new note's own panel gives up a voice
+                .max_by_key(|&(panel_id, voice_count)| (voice_count, panel_id == voice.panel_id))
+                .map(|(panel_id, _)| panel_id);
+            let oldest_voice_index = self.voices
+                .iter()
+                .enumerate()
+                .filter(|(_, playing_voice)| Some(playing_voice.panel_id) == busiest_panel_id)
+                .max_by_key(|(_, playing_voice)| playing_voice.age_frames)
+                .map(|(voice_index, _)| voice_index)
+                .unwrap_or(0);
+            self.voices.swap_remove(oldest_voice_index);
+        }
+        self.voices.push(voice);
+    }
+
+    /// Mixes the next frame of every voice; many voices at once bend smoothly instead of
+    /// clipping.
+    pub fn next_frame(&mut self) -> [f32; 2] {
+        let mut frame = [0.0, 0.0];
+        for voice in &mut self.voices {
+            let [left_sample, right_sample] = voice.next_frame();
+            frame[0] += left_sample;
+            frame[1] += right_sample;
+        }
+        self.voices.retain(|voice| !voice.is_finished());
+        [frame[0].tanh(), frame[1].tanh()]
+    }
+
+    pub fn is_silent(&self) -> bool {
+        self.voices.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.voices.clear();
+    }
+}
+
+/// Left and right gains of an emitter in front of the listener at `normalized_pan` (0 is
+/// far left, 1 far right), the way rodio's `Spatial` source computes them.
+pub fn stereo_gains(normalized_pan: f32) -> [f32; 2] {
+    let emitter_pos = emitter_position_from_normalized_pan(normalized_pan);
+
+    let left_dist_sq = dist_sq(LEFT_EAR_POS, emitter_pos);
+    let right_dist_sq = dist_sq(RIGHT_EAR_POS, emitter_pos);
+    let max_diff = dist_sq(LEFT_EAR_POS, RIGHT_EAR_POS).sqrt();
+    let left_dist = left_dist_sq.sqrt();
+    let right_dist = right_dist_sq.sqrt();
+
+    let left_diff_modifier = (((left_dist - right_dist) / max_diff + 1.0) / 4.0 + 0.5).min(1.0);
+    let right_diff_modifier = (((right_dist - left_dist) / max_diff + 1.0) / 4.0 + 0.5).min(1.0);
+    let left_dist_modifier = (1.0 / left_dist_sq).min(1.0);
+    let right_dist_modifier = (1.0 / right_dist_sq).min(1.0);
+
+    [left_diff_modifier * left_dist_modifier, right_diff_modifier * right_dist_modifier]
+}
+
+/// Peak amplitude of a note; taller bars are louder.
+pub fn note_amplitude(normalized_value: f32) -> f32 {
+    0.05 + 0.15 * normalized_value.clamp(0.0, 1.0) // 0.05–0.20
+}
+
+fn emitter_position_from_normalized_pan(normalized_pan: f32) -> [f32; 3] {
+    // 0..1 -> -1..1
+    let x = normalized_pan.clamp(0.0, 1.0) * 2.0 - 1.0;
+    [x, 0.0, EMITTER_Z]
+}
+
+fn dist_sq(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(a, b)| (a - b) * (a - b))
+        .sum()
+}
+
+fn freq_from_semitones(base_freq: f32, semitone_offset: i32) -> f32 {
+    base_freq * (2.0f32).powf((semitone_offset as f32) / 12.0)
+}
+
+// horizontal: 0.0 left, 1.0 right
+fn normalized_position(index: usize, length: usize) -> f32 {
+    if length > 1 {
+        (index as f32) / ((length - 1) as f32)
+    } else {
+        0.5
+    }
+}
+
+fn normalized_value(value: Option<u32>, maximum_value: u32) -> f32 {
+    match value {
+        Some(value) if maximum_value > 0 => (value as f32) / (maximum_value as f32),
+        _ => 0.0,
+    }
+}
+
+// at most `limit` items, evenly spaced and always keeping the first and the last one
+fn thin_out<T>(items: &[T], limit: usize) -> Vec<&T> {
+    if items.len() <= limit {
+        return items.iter().collect();
+    }
+
+    if limit <= 1 {
+        return items.iter().take(limit).collect();
+    }
+
+    let last_index = items.len() - 1;
+    (0..limit)
+        .map(|item_number| &items[item_number * last_index / (limit - 1)])
+        .collect()
+}
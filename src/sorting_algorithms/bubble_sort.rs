@@ -1,11 +1,13 @@
-pub fn bubble_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Vec<u32>>) {
+use crate::sorting_algorithms::recorder::SortRecorder;
+
+pub fn bubble_sort_with_recording(initial_values: &[u32], recorder: &mut SortRecorder) {
     let mut values = initial_values.to_vec();
 
     if values.is_empty() {
         return;
     }
 
-    frames.push(values.clone());
+    recorder.record_frame(&values);
 
     let length = values.len();
     let mut is_swapped = true;
@@ -16,16 +18,12 @@ pub fn bubble_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Vec<u
         for left_index in 0..length.saturating_sub(1) {
             let right_index = left_index + 1;
 
-            if values[left_index] > values[right_index] {
-                values.swap(left_index, right_index);
-                frames.push(values.clone());
+            if recorder.greater(values[left_index], values[right_index]) {
+                recorder.swap(&mut values, left_index, right_index);
                 is_swapped = true;
             }
         }
     }
 
-    if frames.last().map(|last_frame| last_frame.as_slice()) != Some(values.as_slice()) {
-        frames.push(values);
-    }
-
-}
\ No newline at end of file
+    recorder.finish(&values);
+}
@@ -1,16 +1,16 @@
-pub fn cocktail_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Vec<u32>>) {
+use crate::sorting_algorithms::recorder::SortRecorder;
+
+pub fn cocktail_sort_with_recording(initial_values: &[u32], recorder: &mut SortRecorder) {
     let mut values = initial_values.to_vec();
     if values.is_empty() {
         return;
     }
 
-    frames.push(values.clone());
+    recorder.record_frame(&values);
 
     let length = values.len();
     if length <= 1 {
-        if frames.last().map(|last_frame| last_frame.as_slice()) != Some(values.as_slice()) {
-            frames.push(values);
-        }
+        recorder.finish(&values);
         return;
     }
 
@@ -24,9 +24,8 @@ pub fn cocktail_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Vec
         // Forward pass: left -> right
         for left_index in start_index..end_index {
             let right_index = left_index + 1;
-            if values[left_index] > values[right_index] {
-                values.swap(left_index, right_index);
-                frames.push(values.clone());
+            if recorder.greater(values[left_index], values[right_index]) {
+                recorder.swap(&mut values, left_index, right_index);
                 has_swapped_in_pass = true;
             }
         }
@@ -46,9 +45,8 @@ pub fn cocktail_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Vec
         let mut right_index = end_index;
         while right_index > start_index {
             let left_index = right_index - 1;
-            if values[left_index] > values[right_index] {
-                values.swap(left_index, right_index);
-                frames.push(values.clone());
+            if recorder.greater(values[left_index], values[right_index]) {
+                recorder.swap(&mut values, left_index, right_index);
                 has_swapped_in_pass = true;
             }
 
@@ -64,7 +62,5 @@ pub fn cocktail_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Vec
         }
     }
 
-    if frames.last().map(|last_frame| last_frame.as_slice()) != Some(values.as_slice()) {
-        frames.push(values);
-    }
+    recorder.finish(&values);
 }
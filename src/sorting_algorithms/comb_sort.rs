@@ -1,16 +1,16 @@
-pub fn comb_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Vec<u32>>) {
+use crate::sorting_algorithms::recorder::SortRecorder;
+
+pub fn comb_sort_with_recording(initial_values: &[u32], recorder: &mut SortRecorder) {
     let mut values = initial_values.to_vec();
     if values.is_empty() {
         return;
     }
 
-    frames.push(values.clone());
+    recorder.record_frame(&values);
 
     let length = values.len();
     if length <= 1 {
-        if frames.last().map(|last_frame| last_frame.as_slice()) != Some(values.as_slice()) {
-            frames.push(values);
-        }
+        recorder.finish(&values);
         return;
     }
 
@@ -29,16 +29,13 @@ pub fn comb_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Vec<u32
         let mut left_index: usize = 0;
         while left_index + gap_size < length {
             let right_index = left_index + gap_size;
-            if values[left_index] > values[right_index] {
-                values.swap(left_index, right_index);
-                frames.push(values.clone());
+            if recorder.greater(values[left_index], values[right_index]) {
+                recorder.swap(&mut values, left_index, right_index);
                 swap_performed_in_pass = true;
             }
             left_index += 1;
         }
     }
 
-    if frames.last().map(|last_frame| last_frame.as_slice()) != Some(values.as_slice()) {
-        frames.push(values);
-    }
+    recorder.finish(&values);
 }
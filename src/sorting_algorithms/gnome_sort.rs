@@ -1,16 +1,16 @@
-pub fn gnome_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Vec<u32>>) {
+use crate::sorting_algorithms::recorder::SortRecorder;
+
+pub fn gnome_sort_with_recording(initial_values: &[u32], recorder: &mut SortRecorder) {
     let mut values = initial_values.to_vec();
     if values.is_empty() {
         return;
     }
 
-    frames.push(values.clone());
+    recorder.record_frame(&values);
 
     let length = values.len();
     if length <= 1 {
-        if frames.last().map(|last_frame| last_frame.as_slice()) != Some(values.as_slice()) {
-            frames.push(values);
-        }
+        recorder.finish(&values);
         return;
     }
 
@@ -18,12 +18,11 @@ pub fn gnome_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Vec<u3
     let mut next_index: usize = 2;
 
     while current_index < length {
-        if values[current_index - 1] <= values[current_index] {
+        if recorder.less_or_equal(values[current_index - 1], values[current_index]) {
             current_index = next_index;
             next_index += 1;
         } else {
-            values.swap(current_index - 1, current_index);
-            frames.push(values.clone());
+            recorder.swap(&mut values, current_index - 1, current_index);
 
             if current_index > 1 {
                 current_index -= 1;
@@ -34,7 +33,5 @@ pub fn gnome_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Vec<u3
         }
     }
 
-    if frames.last().map(|last_frame| last_frame.as_slice()) != Some(values.as_slice()) {
-        frames.push(values);
-    }
+    recorder.finish(&values);
 }
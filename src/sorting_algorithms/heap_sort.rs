@@ -1,19 +1,34 @@
-pub fn heap_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Vec<u32>>) {
+use crate::sorting_algorithms::recorder::SortRecorder;
+
+pub fn heap_sort_with_recording(initial_values: &[u32], recorder: &mut SortRecorder) {
     let mut values = initial_values.to_vec();
     if values.is_empty() {
         return;
     }
 
-    frames.push(values.clone());
+    recorder.record_frame(&values);
 
     let length = values.len();
+    heap_sort_range(&mut values, 0, length, recorder);
+
+    recorder.finish(&values);
+}
+
+// heap sorts values[start_index..end_index] in place; intro sort falls back to this
+pub fn heap_sort_range(
+    values: &mut [u32],
+    start_index: usize,
+    end_index: usize,
+    recorder: &mut SortRecorder,
+) {
+    let length = end_index.saturating_sub(start_index);
 
     // build max heap
     if length > 1 {
         let mut heap_index = length / 2;
         while heap_index > 0 {
             heap_index -= 1;
-            sift_down(&mut values, heap_index, length, frames);
+            sift_down(values, start_index, heap_index, length, recorder);
         }
     }
 
@@ -22,22 +37,19 @@ pub fn heap_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Vec<u32
     while unsorted_size > 1 {
         unsorted_size -= 1;
 
-        values.swap(0, unsorted_size);
-        frames.push(values.clone());
-
-        sift_down(&mut values, 0, unsorted_size, frames);
-    }
+        recorder.swap(values, start_index, start_index + unsorted_size);
 
-    if frames.last().map(|last_frame| last_frame.as_slice()) != Some(values.as_slice()) {
-        frames.push(values);
+        sift_down(values, start_index, 0, unsorted_size, recorder);
     }
 }
 
+// heap indices are relative to heap_start_index
 fn sift_down(
-    values: &mut Vec<u32>,
+    values: &mut [u32],
+    heap_start_index: usize,
     start_index: usize,
     heap_size: usize,
-    frames: &mut Vec<Vec<u32>>,
+    recorder: &mut SortRecorder,
 ) {
     let mut root_index = start_index;
 
@@ -51,11 +63,22 @@ fn sift_down(
 
         let mut index_of_largest = root_index;
 
-        if values[left_child_index] > values[index_of_largest] {
+        if
+            recorder.greater(
+                values[heap_start_index + left_child_index],
+                values[heap_start_index + index_of_largest]
+            )
+        {
             index_of_largest = left_child_index;
         }
 
-        if right_child_index < heap_size && values[right_child_index] > values[index_of_largest] {
+        if
+            right_child_index < heap_size &&
+            recorder.greater(
+                values[heap_start_index + right_child_index],
+                values[heap_start_index + index_of_largest]
+            )
+        {
             index_of_largest = right_child_index;
         }
 
@@ -63,8 +86,7 @@ fn sift_down(
             break;
         }
 
-        values.swap(root_index, index_of_largest);
-        frames.push(values.clone());
+        recorder.swap(values, heap_start_index + root_index, heap_start_index + index_of_largest);
 
         root_index = index_of_largest;
     }
@@ -1,10 +1,12 @@
-pub fn insertion_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Vec<u32>>) {
+use crate::sorting_algorithms::recorder::SortRecorder;
+
+pub fn insertion_sort_with_recording(initial_values: &[u32], recorder: &mut SortRecorder) {
     let mut values = initial_values.to_vec();
     if values.is_empty() {
         return;
     }
 
-    frames.push(values.clone());
+    recorder.record_frame(&values);
 
     let length = values.len();
 
@@ -12,17 +14,14 @@ pub fn insertion_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Ve
         let current_value = values[unsorted_index];
         let mut insert_index = unsorted_index;
 
-        while insert_index > 0 && values[insert_index - 1] > current_value {
-            values[insert_index] = values[insert_index - 1];
+        while insert_index > 0 && recorder.greater(values[insert_index - 1], current_value) {
+            let shifted_value = values[insert_index - 1];
+            recorder.write(&mut values, insert_index, shifted_value);
             insert_index -= 1;
-            frames.push(values.clone());
         }
 
-        values[insert_index] = current_value;
-        frames.push(values.clone());
+        recorder.write(&mut values, insert_index, current_value);
     }
 
-    if frames.last().map(|last_frame| last_frame.as_slice()) != Some(values.as_slice()) {
-        frames.push(values);
-    }
+    recorder.finish(&values);
 }
@@ -0,0 +1,114 @@
+use crate::input::InputDistribution;
+use crate::sorting_algorithms::quick_sort::PartitionScheme;
+use crate::sorting_algorithms::recorder::SortRecorder;
+use crate::sorting_algorithms::SortingAlgorithmKind;
+
+/// One panel of a comparison: an algorithm with its own parameters and, optionally, its own
+/// input shape. A run may hold the same algorithm several times, e.g. Quick Sort with Lomuto
+/// and with Hoare partitioning, or one sort on random and on reversed input.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlgorithmInstance {
+    pub kind: SortingAlgorithmKind,
+    /// Only used by algorithms whose registry entry has `record_partitioned`.
+    pub partition_scheme: PartitionScheme,
+    /// Replaces the run's input shape for this instance.
+    pub input_distribution: Option<InputDistribution>,
+    /// Shown instead of the generated label when not blank.
+    pub custom_label: String,
+}
+
+impl AlgorithmInstance {
+    pub fn new(kind: SortingAlgorithmKind) -> Self {
+        Self {
+            kind,
+            partition_scheme: PartitionScheme::default(),
+            input_distribution: None,
+            custom_label: String::new(),
+        }
+    }
+
+    pub fn has_partition_scheme(&self) -> bool {
+        self.kind.info().record_partitioned.is_some()
+    }
+
+    /// Panel title: the custom label, or the generated one.
+    pub fn label(&self) -> String {
+        if self.custom_label.trim().is_empty() {
+            self.generated_label()
+        } else {
+            self.custom_label.trim().to_owned()
+        }
+    }
+
+    /// The algorithm's name with its parameters, e.g. `Quick Sort (Hoare, Reversed)`.
+    pub fn generated_label(&self) -> String {
+        let mut details: Vec<&str> = Vec::new();
+        if self.has_partition_scheme() {
+            details.push(self.partition_scheme.display_name());
+        }
+        if let Some(input_distribution) = self.input_distribution {
+            details.push(input_distribution.display_name());
+        }
+
+        if details.is_empty() {
+            self.kind.display_name().to_owned()
+        } else {
+            format!("{} ({})", self.kind.display_name(), details.join(", "))
+        }
+    }
+
+    /// Parses `name[:parameter...]`, e.g. `quick:hoare:reversed`, where each parameter is a
+    /// partition scheme or an input shape. Errors name the offending part.
+    pub fn from_spec(spec: &str) -> Result<Self, String> {
+        let mut parts = spec.split(':').map(str::trim);
+        let name = parts.next().unwrap_or_default();
+        let algorithm_kind = SortingAlgorithmKind::from_cli_name(name).ok_or_else(|| {
+            format!("unknown algorithm '{}' (expected one of: {})", name, algorithm_names())
+        })?;
+
+        let mut algorithm_instance = AlgorithmInstance::new(algorithm_kind);
+        for parameter in parts {
+            if let Some(partition_scheme) = PartitionScheme::from_cli_name(parameter) {
+                if !algorithm_instance.has_partition_scheme() {
+                    return Err(format!("{} has no partition scheme to set in '{}'", name, spec));
+                }
+                algorithm_instance.partition_scheme = partition_scheme;
+            } else if let Some(input_distribution) = InputDistribution::from_cli_name(parameter) {
+                algorithm_instance.input_distribution = Some(input_distribution);
+            } else {
+                return Err(
+                    format!(
+                        "unknown parameter '{}' in '{}' (expected a partition scheme or input shape)",
+                        parameter,
+                        spec
+                    )
+                );
+            }
+        }
+        Ok(algorithm_instance)
+    }
+
+    /// Runs this instance's algorithm with its parameters on a copy of `base_values`.
+    pub fn record(&self, base_values: &[u32], recorder: &mut SortRecorder) {
+        let algorithm_info = self.kind.info();
+        match algorithm_info.record_partitioned {
+            Some(record_partitioned) => record_partitioned(base_values, self.partition_scheme, recorder),
+            None => (algorithm_info.record)(base_values, recorder),
+        }
+    }
+}
+
+impl From<SortingAlgorithmKind> for AlgorithmInstance {
+    fn from(kind: SortingAlgorithmKind) -> Self {
+        AlgorithmInstance::new(kind)
+    }
+}
+
+/// Comma separated cli names of every algorithm, for error messages.
+pub fn algorithm_names() -> String {
+    SortingAlgorithmKind::ALL
+        .iter()
+        .map(|algorithm_kind| algorithm_kind.cli_name())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
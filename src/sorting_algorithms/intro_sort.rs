@@ -1,30 +1,29 @@
-use crate::sorting_algorithms::heap_sort::heap_sort_with_recording;
+use crate::sorting_algorithms::heap_sort::heap_sort_range;
+use crate::sorting_algorithms::recorder::SortRecorder;
 
-pub fn intro_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Vec<u32>>) {
+pub fn intro_sort_with_recording(initial_values: &[u32], recorder: &mut SortRecorder) {
     let mut values = initial_values.to_vec();
     if values.is_empty() {
         return;
     }
 
-    frames.push(values.clone());
+    recorder.record_frame(&values);
 
     let length = values.len();
     if length > 1 {
         let depth_limit = (2.0 * (length as f64).log2().floor()) as usize;
-        intro_sort_recursive(&mut values, 0, length, depth_limit, frames);
+        intro_sort_recursive(&mut values, 0, length, depth_limit, recorder);
     }
 
-    if frames.last().map(|last_frame| last_frame.as_slice()) != Some(values.as_slice()) {
-        frames.push(values);
-    }
+    recorder.finish(&values);
 }
 
 fn intro_sort_recursive(
-    values: &mut Vec<u32>,
+    values: &mut [u32],
     start_index: usize,
     end_index: usize,
     depth_limit: usize,
-    frames: &mut Vec<Vec<u32>>,
+    recorder: &mut SortRecorder,
 ) {
     let range_length = end_index.saturating_sub(start_index);
     if range_length <= 1 {
@@ -32,11 +31,11 @@ fn intro_sort_recursive(
     }
 
     if depth_limit == 0 {
-        heap_sort_range(values, start_index, end_index, frames);
+        heap_sort_range(values, start_index, end_index, recorder);
         return;
     }
 
-    let pivot_final_index = partition_range(values, start_index, end_index, frames);
+    let pivot_final_index = partition_range(values, start_index, end_index, recorder);
 
     if pivot_final_index > start_index {
         intro_sort_recursive(
@@ -44,7 +43,7 @@ fn intro_sort_recursive(
             start_index,
             pivot_final_index,
             depth_limit - 1,
-            frames,
+            recorder,
         );
     }
 
@@ -54,16 +53,16 @@ fn intro_sort_recursive(
             pivot_final_index + 1,
             end_index,
             depth_limit - 1,
-            frames,
+            recorder,
         );
     }
 }
 
 fn partition_range(
-    values: &mut Vec<u32>,
+    values: &mut [u32],
     start_index: usize,
     end_index: usize,
-    frames: &mut Vec<Vec<u32>>,
+    recorder: &mut SortRecorder,
 ) -> usize {
     let pivot_index = end_index - 1;
     let pivot_value = values[pivot_index];
@@ -71,50 +70,17 @@ fn partition_range(
     let mut store_index = start_index;
 
     for scan_index in start_index..pivot_index {
-        if values[scan_index] < pivot_value {
+        if recorder.less(values[scan_index], pivot_value) {
             if scan_index != store_index {
-                values.swap(scan_index, store_index);
-                frames.push(values.clone());
+                recorder.swap(values, scan_index, store_index);
             }
             store_index += 1;
         }
     }
 
     if store_index != pivot_index {
-        values.swap(store_index, pivot_index);
-        frames.push(values.clone());
+        recorder.swap(values, store_index, pivot_index);
     }
 
     store_index
 }
-
-fn heap_sort_range(
-    values: &mut Vec<u32>,
-    start_index: usize,
-    end_index: usize,
-    frames: &mut Vec<Vec<u32>>,
-) {
-    if end_index <= start_index {
-        return;
-    }
-
-    let segment: Vec<u32> = values[start_index..end_index].to_vec();
-    let mut local_frames: Vec<Vec<u32>> = Vec::new();
-
-    heap_sort_with_recording(&segment, &mut local_frames);
-
-    // map segment frames to full array
-    for (frame_index, segment_state) in local_frames.into_iter().enumerate() {
-        // skip initial frame to avoid duplication
-        if frame_index == 0 {
-            continue;
-        }
-
-        if segment_state.len() != end_index - start_index {
-            continue;
-        }
-
-        values[start_index..end_index].clone_from_slice(&segment_state);
-        frames.push(values.clone());
-    }
-}
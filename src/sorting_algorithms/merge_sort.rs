@@ -1,26 +1,26 @@
-pub fn merge_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Vec<u32>>) {
+use crate::sorting_algorithms::recorder::SortRecorder;
+
+pub fn merge_sort_with_recording(initial_values: &[u32], recorder: &mut SortRecorder) {
     let mut values = initial_values.to_vec();
     if values.is_empty() {
         return;
     }
 
-    frames.push(values.clone());
+    recorder.record_frame(&values);
 
     let length = values.len();
     if length > 1 {
-        merge_sort_recursive(&mut values, 0, length, frames);
+        merge_sort_recursive(&mut values, 0, length, recorder);
     }
 
-    if frames.last().map(|last_frame| last_frame.as_slice()) != Some(values.as_slice()) {
-        frames.push(values);
-    }
+    recorder.finish(&values);
 }
 
 fn merge_sort_recursive(
-    values: &mut Vec<u32>,
+    values: &mut [u32],
     start_index: usize,
     end_index: usize,
-    frames: &mut Vec<Vec<u32>>,
+    recorder: &mut SortRecorder,
 ) {
     if end_index.saturating_sub(start_index) <= 1 {
         return;
@@ -28,18 +28,19 @@ fn merge_sort_recursive(
 
     let middle_index = start_index + (end_index - start_index) / 2;
 
-    merge_sort_recursive(values, start_index, middle_index, frames);
-    merge_sort_recursive(values, middle_index, end_index, frames);
+    merge_sort_recursive(values, start_index, middle_index, recorder);
+    merge_sort_recursive(values, middle_index, end_index, recorder);
 
-    merge_ranges(values, start_index, middle_index, end_index, frames);
+    merge_ranges(values, start_index, middle_index, end_index, recorder);
 }
 
-fn merge_ranges(
-    values: &mut Vec<u32>,
+// merges two sorted neighbouring ranges through a buffer; only the copy back counts as writes
+pub fn merge_ranges(
+    values: &mut [u32],
     start_index: usize,
     middle_index: usize,
     end_index: usize,
-    frames: &mut Vec<Vec<u32>>,
+    recorder: &mut SortRecorder,
 ) {
     let mut left_index = start_index;
     let mut right_index = middle_index;
@@ -47,7 +48,7 @@ fn merge_ranges(
     let mut temporary_values: Vec<u32> = Vec::with_capacity(end_index - start_index);
 
     while left_index < middle_index && right_index < end_index {
-        if values[left_index] <= values[right_index] {
+        if recorder.less_or_equal(values[left_index], values[right_index]) {
             temporary_values.push(values[left_index]);
             left_index += 1;
         } else {
@@ -67,7 +68,6 @@ fn merge_ranges(
     }
 
     for (offset, temporary_value) in temporary_values.into_iter().enumerate() {
-        values[start_index + offset] = temporary_value;
-        frames.push(values.clone());
+        recorder.write(values, start_index + offset, temporary_value);
     }
 }
@@ -10,25 +10,23 @@ pub mod comb_sort;
 pub mod gnome_sort;
 pub mod intro_sort;
 pub mod tim_sort;
+pub mod recorder;
+pub mod tracked;
+pub mod std_sorts;
+pub mod registry;
+pub mod instance;
 
 use std::time::Instant;
 
-use crate::sorting_algorithms::bubble_sort::bubble_sort_with_recording;
-use crate::sorting_algorithms::quick_sort::quick_sort_with_recording;
-use crate::sorting_algorithms::insertion_sort::insertion_sort_with_recording;
-use crate::sorting_algorithms::merge_sort::merge_sort_with_recording;
-use crate::sorting_algorithms::heap_sort::heap_sort_with_recording;
-use crate::sorting_algorithms::selection_sort::selection_sort_with_recording;
-use crate::sorting_algorithms::shell_sort::shell_sort_with_recording;
-use crate::sorting_algorithms::cocktail_sort::cocktail_sort_with_recording;
-use crate::sorting_algorithms::comb_sort::comb_sort_with_recording;
-use crate::sorting_algorithms::gnome_sort::gnome_sort_with_recording;
-use crate::sorting_algorithms::intro_sort::intro_sort_with_recording;
-use crate::sorting_algorithms::tim_sort::tim_sort_with_recording;
+use crate::sorting_algorithms::instance::AlgorithmInstance;
+use crate::sorting_algorithms::registry::{ AlgorithmInfo, ALGORITHMS };
+use crate::sorting_algorithms::recorder::{ SortOperation, SortRecorder };
 
 use crate::stats::{ SortStats, StatsSnapshot };
 
-#[derive(Clone, Copy, Debug)]
+/// Every algorithm sortvis can record; its metadata lives in [`registry::ALGORITHMS`].
+#[allow(clippy::enum_variant_names)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SortingAlgorithmKind {
     BubbleSort,
     QuickSort,
@@ -42,98 +40,175 @@ pub enum SortingAlgorithmKind {
     CocktailSort,
     CombSort,
     GnomeSort,
+    /// std sorts over tracked elements
+    StdStableSort,
+    StdUnstableSort,
+    StdSelectNth,
+    StdBinaryHeap,
 }
 
 impl SortingAlgorithmKind {
-    pub fn display_name(&self) -> &'static str {
-        match self {
-            SortingAlgorithmKind::BubbleSort => "Bubble Sort",
-            SortingAlgorithmKind::QuickSort => "Quick Sort",
-            SortingAlgorithmKind::InsertionSort => "Insertion Sort",
-            SortingAlgorithmKind::SelectionSort => "Selection Sort",
-            SortingAlgorithmKind::MergeSort => "Merge Sort",
-            SortingAlgorithmKind::HeapSort => "Heap Sort",
-            SortingAlgorithmKind::ShellSort => "Shell Sort",
-            SortingAlgorithmKind::IntroSort => "Intro Sort",
-            SortingAlgorithmKind::TimSort => "Tim Sort",
-            SortingAlgorithmKind::CocktailSort => "Cocktail Shaker Sort",
-            SortingAlgorithmKind::CombSort => "Comb Sort",
-            SortingAlgorithmKind::GnomeSort => "Gnome Sort",
+    /// All kinds, in registry order.
+    pub const ALL: [SortingAlgorithmKind; ALGORITHMS.len()] = {
+        let mut algorithm_kinds = [SortingAlgorithmKind::BubbleSort; ALGORITHMS.len()];
+        let mut algorithm_index = 0;
+        while algorithm_index < ALGORITHMS.len() {
+            algorithm_kinds[algorithm_index] = ALGORITHMS[algorithm_index].kind;
+            algorithm_index += 1;
         }
+        algorithm_kinds
+    };
+
+    /// This algorithm's registry entry.
+    pub fn info(&self) -> &'static AlgorithmInfo {
+        ALGORITHMS
+            .iter()
+            .find(|algorithm_info| algorithm_info.kind == *self)
+            .expect("every algorithm kind has a registry entry")
+    }
+
+    /// Short lowercase name used on the command line, e.g. `quick`.
+    pub fn cli_name(&self) -> &'static str {
+        self.info().cli_name
+    }
+
+    /// Case-insensitive inverse of [`cli_name`](Self::cli_name).
+    pub fn from_cli_name(name: &str) -> Option<SortingAlgorithmKind> {
+        SortingAlgorithmKind::ALL
+            .into_iter()
+            .find(|algorithm_kind| algorithm_kind.cli_name().eq_ignore_ascii_case(name))
+    }
+
+    /// Name shown in panel titles and tables, e.g. `Quick Sort`.
+    pub fn display_name(&self) -> &'static str {
+        self.info().display_name
     }
 }
 
+/// One recorded run: a frame per step, the operation log, optional markers and the stats.
 pub struct SortingAlgorithmReplay {
     algorithm_name: String,
     frames: Vec<Vec<u32>>,
+    operations: Vec<SortOperation>,
+    // named points in the run as (step, name), in step order
+    markers: Vec<(usize, String)>,
     stats: SortStats,
 }
 
 impl SortingAlgorithmReplay {
+    /// Sorts a copy of `base_values`, recording every frame and operation.
     pub fn new(algorithm_kind: SortingAlgorithmKind, base_values: &[u32]) -> Self {
-        let mut frames: Vec<Vec<u32>> = Vec::new();
+        Self::record(algorithm_kind.display_name().to_owned(), base_values, |recorder| {
+            run_sort(algorithm_kind, base_values, recorder);
+        })
+    }
+
+    /// Like [`new`](Self::new), with the instance's parameters and named after its label.
+    pub fn for_instance(algorithm_instance: &AlgorithmInstance, base_values: &[u32]) -> Self {
+        Self::record(algorithm_instance.label(), base_values, |recorder| {
+            algorithm_instance.record(base_values, recorder);
+        })
+    }
+
+    fn record(
+        algorithm_name: String,
+        base_values: &[u32],
+        run: impl FnOnce(&mut SortRecorder)
+    ) -> Self {
+        let mut recorder = SortRecorder::new();
         let start_time = Instant::now();
 
-        match algorithm_kind {
-            SortingAlgorithmKind::BubbleSort => {
-                bubble_sort_with_recording(base_values, &mut frames);
-            }
-            SortingAlgorithmKind::QuickSort => {
-                quick_sort_with_recording(base_values, &mut frames);
-            }
-            SortingAlgorithmKind::InsertionSort => {
-                insertion_sort_with_recording(base_values, &mut frames);
-            }
-            SortingAlgorithmKind::SelectionSort => {
-                selection_sort_with_recording(base_values, &mut frames);
-            }
-            SortingAlgorithmKind::MergeSort => {
-                merge_sort_with_recording(base_values, &mut frames);
-            }
-            SortingAlgorithmKind::HeapSort => {
-                heap_sort_with_recording(base_values, &mut frames);
-            }
-            SortingAlgorithmKind::ShellSort => {
-                shell_sort_with_recording(base_values, &mut frames);
-            }
-            SortingAlgorithmKind::IntroSort => {
-                intro_sort_with_recording(base_values, &mut frames);
-            }
-            SortingAlgorithmKind::TimSort => {
-                tim_sort_with_recording(base_values, &mut frames);
-            }
-            SortingAlgorithmKind::CocktailSort => {
-                cocktail_sort_with_recording(base_values, &mut frames);
-            }
-            SortingAlgorithmKind::CombSort => {
-                comb_sort_with_recording(base_values, &mut frames);
-            }
-            SortingAlgorithmKind::GnomeSort => {
-                gnome_sort_with_recording(base_values, &mut frames);
-            }
-        }
+        run(&mut recorder);
 
         let end_time = Instant::now();
 
+        let operation_counts = recorder.operation_counts();
+        let (mut frames, operations) = recorder.into_frames_and_operations();
         if frames.is_empty() {
             frames.push(base_values.to_vec());
         }
 
         let total_steps = frames.len() as u64;
         let duration = end_time.duration_since(start_time);
-        let stats = SortStats::from_measurements(total_steps, duration);
+        let stats = SortStats::from_measurements(total_steps, operation_counts, duration);
 
         SortingAlgorithmReplay {
-            algorithm_name: algorithm_kind.display_name().to_owned(),
+            algorithm_name,
             frames,
+            operations,
+            markers: Vec::new(),
             stats,
         }
     }
 
+    /// Rebuilds the frames of a saved run by applying its operation log to the input, without
+    /// running the algorithm again. Fails when an operation indexes past the end of the array.
+    pub fn from_operations(
+        algorithm_name: String,
+        initial_values: Vec<u32>,
+        operations: Vec<SortOperation>,
+        stats: SortStats
+    ) -> Result<Self, String> {
+        let mut current_values = initial_values.clone();
+        let mut frames = vec![initial_values];
+
+        for (operation_index, operation) in operations.iter().enumerate() {
+            match *operation {
+                SortOperation::Compare => {
+                    continue;
+                }
+                SortOperation::Swap(first_index, second_index) => {
+                    if first_index >= current_values.len() || second_index >= current_values.len() {
+                        return Err(
+                            format!(
+                                "operation {} swaps {} and {} in an array of {} values",
+                                operation_index,
+                                first_index,
+                                second_index,
+                                current_values.len()
+                            )
+                        );
+                    }
+                    current_values.swap(first_index, second_index);
+                }
+                SortOperation::Write(index, value) => {
+                    if index >= current_values.len() {
+                        return Err(
+                            format!(
+                                "operation {} writes index {} in an array of {} values",
+                                operation_index,
+                                index,
+                                current_values.len()
+                            )
+                        );
+                    }
+                    current_values[index] = value;
+                }
+            }
+            frames.push(current_values.clone());
+        }
+
+        Ok(SortingAlgorithmReplay {
+            algorithm_name,
+            frames,
+            operations,
+            markers: Vec::new(),
+            stats,
+        })
+    }
+
+    /// Attaches `(step, name)` markers, e.g. from an imported trace.
+    pub fn with_markers(mut self, mut markers: Vec<(usize, String)>) -> Self {
+        markers.sort_by_key(|(step_index, _)| *step_index);
+        self.markers = markers;
+        self
+    }
+
     pub fn name(&self) -> &str {
         &self.algorithm_name
     }
 
+    /// A copy of the frame at `frame_index`, clamped to the last one, and whether it is the last.
     pub fn frame_at(&self, frame_index: usize) -> (Vec<u32>, bool) {
         let last_index = self.frames.len().saturating_sub(1);
 
@@ -149,7 +224,47 @@ impl SortingAlgorithmReplay {
         }
     }
 
+    /// Number of frames, including the initial array.
+    pub fn step_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// The frame at `frame_index`, clamped to the last one.
+    pub fn frame(&self, frame_index: usize) -> &[u32] {
+        let last_index = self.frames.len().saturating_sub(1);
+        self.frames.get(frame_index.min(last_index)).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Display-ready copy of the stats.
     pub fn stats_snapshot(&self) -> StatsSnapshot {
         self.stats.to_snapshot()
     }
+
+    pub fn stats(&self) -> &SortStats {
+        &self.stats
+    }
+
+    /// Every comparison, swap and write, in order.
+    pub fn operations(&self) -> &[SortOperation] {
+        &self.operations
+    }
+
+    pub fn markers(&self) -> &[(usize, String)] {
+        &self.markers
+    }
+
+    /// The latest marker reached at `frame_index`, if any.
+    pub fn marker_at(&self, frame_index: usize) -> Option<&str> {
+        let reached_count = self.markers.partition_point(|(step_index, _)| *step_index <= frame_index);
+        reached_count.checked_sub(1).map(|marker_index| self.markers[marker_index].1.as_str())
+    }
+}
+
+/// Runs one algorithm on a copy of `base_values`, reporting every operation to the recorder.
+pub fn run_sort(
+    algorithm_kind: SortingAlgorithmKind,
+    base_values: &[u32],
+    recorder: &mut SortRecorder
+) {
+    (algorithm_kind.info().record)(base_values, recorder);
 }
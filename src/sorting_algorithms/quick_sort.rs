@@ -1,41 +1,86 @@
-pub fn quick_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Vec<u32>>) {
+use crate::sorting_algorithms::recorder::SortRecorder;
+
+// how an array is split around the pivot
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum PartitionScheme {
+    // last element as pivot, one scan from the left
+    #[default]
+    Lomuto,
+    // middle element as pivot, two scans towards each other; fewer swaps
+    Hoare,
+}
+
+impl PartitionScheme {
+    pub const ALL: [PartitionScheme; 2] = [PartitionScheme::Lomuto, PartitionScheme::Hoare];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            PartitionScheme::Lomuto => "Lomuto",
+            PartitionScheme::Hoare => "Hoare",
+        }
+    }
+
+    pub fn cli_name(&self) -> &'static str {
+        match self {
+            PartitionScheme::Lomuto => "lomuto",
+            PartitionScheme::Hoare => "hoare",
+        }
+    }
+
+    pub fn from_cli_name(name: &str) -> Option<PartitionScheme> {
+        PartitionScheme::ALL
+            .into_iter()
+            .find(|partition_scheme| partition_scheme.cli_name().eq_ignore_ascii_case(name))
+    }
+}
+
+pub fn quick_sort_with_recording(initial_values: &[u32], recorder: &mut SortRecorder) {
+    quick_sort_with_partition(initial_values, PartitionScheme::Lomuto, recorder);
+}
+
+pub fn quick_sort_with_partition(
+    initial_values: &[u32],
+    partition_scheme: PartitionScheme,
+    recorder: &mut SortRecorder
+) {
     let mut values = initial_values.to_vec();
-    
+
     if values.is_empty() {
         return;
     }
 
-    frames.push(values.clone());
+    recorder.record_frame(&values);
 
     let length = values.len();
 
     if length > 1 {
-        quick_sort_recursive(&mut values, 0, length - 1, frames);
+        match partition_scheme {
+            PartitionScheme::Lomuto => quick_sort_recursive(&mut values, 0, length - 1, recorder),
+            PartitionScheme::Hoare => hoare_quick_sort_recursive(&mut values, 0, length - 1, recorder),
+        }
     }
 
-    if frames.last().map(|last_frame| last_frame.as_slice()) != Some(values.as_slice()) {
-        frames.push(values);
-    }
+    recorder.finish(&values);
 }
 
 fn quick_sort_recursive(
     values: &mut [u32],
     low_index: usize,
     high_index: usize,
-    frames: &mut Vec<Vec<u32>>,
+    recorder: &mut SortRecorder,
 ) {
     if low_index >= high_index {
         return;
     }
 
-    let partition_index = partition(values, low_index, high_index, frames);
+    let partition_index = partition(values, low_index, high_index, recorder);
 
     if partition_index > 0 {
-        quick_sort_recursive(values, low_index, partition_index - 1, frames);
+        quick_sort_recursive(values, low_index, partition_index - 1, recorder);
     }
 
     if partition_index < high_index {
-        quick_sort_recursive(values, partition_index + 1, high_index, frames);
+        quick_sort_recursive(values, partition_index + 1, high_index, recorder);
     }
 }
 
@@ -43,25 +88,70 @@ fn partition(
     values: &mut [u32],
     low_index: usize,
     high_index: usize,
-    frames: &mut Vec<Vec<u32>>,
+    recorder: &mut SortRecorder,
 ) -> usize {
     let pivot_value = values[high_index];
     let mut store_index = low_index;
 
     for scan_index in low_index..high_index {
-        if values[scan_index] < pivot_value {
+        if recorder.less(values[scan_index], pivot_value) {
             if scan_index != store_index {
-                values.swap(store_index, scan_index);
-                frames.push(values.to_vec());
+                recorder.swap(values, store_index, scan_index);
             }
             store_index += 1;
         }
     }
 
     if store_index != high_index {
-        values.swap(store_index, high_index);
-        frames.push(values.to_vec());
+        recorder.swap(values, store_index, high_index);
     }
 
     store_index
-}
\ No newline at end of file
+}
+
+fn hoare_quick_sort_recursive(
+    values: &mut [u32],
+    low_index: usize,
+    high_index: usize,
+    recorder: &mut SortRecorder,
+) {
+    if low_index >= high_index {
+        return;
+    }
+
+    let split_index = hoare_partition(values, low_index, high_index, recorder);
+
+    // unlike lomuto, the pivot is not final: both halves keep their boundary element
+    hoare_quick_sort_recursive(values, low_index, split_index, recorder);
+    hoare_quick_sort_recursive(values, split_index + 1, high_index, recorder);
+}
+
+// returns split_index such that low..=split_index holds nothing greater than
+// split_index+1..=high_index; split_index is always below high_index
+fn hoare_partition(
+    values: &mut [u32],
+    low_index: usize,
+    high_index: usize,
+    recorder: &mut SortRecorder,
+) -> usize {
+    let pivot_value = values[low_index + (high_index - low_index) / 2];
+    let mut left_index = low_index;
+    let mut right_index = high_index;
+
+    loop {
+        while recorder.less(values[left_index], pivot_value) {
+            left_index += 1;
+        }
+        while recorder.greater(values[right_index], pivot_value) {
+            right_index -= 1;
+        }
+
+        if left_index >= right_index {
+            return right_index;
+        }
+
+        recorder.swap(values, left_index, right_index);
+        left_index += 1;
+        right_index -= 1;
+    }
+}
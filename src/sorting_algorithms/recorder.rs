@@ -0,0 +1,137 @@
+/// Most memory the frames of a run may take, over all of its replays: a replay keeps a copy
+/// of the array for every swap or write, so long runs of the O(n²) sorts and long traces
+/// quickly reach gigabytes.
+pub const MAX_FRAME_BYTES: usize = 1 << 30;
+
+/// Most entries the operation logs of a run may hold, over all of its replays: comparisons
+/// keep no frame but still take an entry each, about 800 MB at this limit.
+pub const MAX_OPERATIONS: usize = 1 << 25;
+
+/// Bytes held by `frame_count` frames of `array_length` values; saturates instead of
+/// overflowing, so it can be compared with [`MAX_FRAME_BYTES`] for any input.
+pub fn frame_bytes(frame_count: usize, array_length: usize) -> usize {
+    frame_count.saturating_mul(array_length).saturating_mul(std::mem::size_of::<u32>())
+}
+
+/// Operation totals for one run; a swap counts once, not as two writes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OperationCounts {
+    pub comparisons: u64,
+    pub swaps: u64,
+    pub writes: u64,
+}
+
+/// One entry of a run's operation log; replaying the swaps and writes over the input
+/// reproduces every frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortOperation {
+    Compare,
+    Swap(usize, usize),
+    Write(usize, u32),
+}
+
+/// Every algorithm reads, compares and mutates its values through this, so counts and frames
+/// always agree with each other.
+pub struct SortRecorder {
+    frames: Vec<Vec<u32>>,
+    operations: Vec<SortOperation>,
+    // off for benchmarks: large inputs would need one full copy of the array per step
+    is_recording_frames: bool,
+    operation_counts: OperationCounts,
+}
+
+impl SortRecorder {
+    /// Records frames and the operation log as well as counting.
+    pub fn new() -> Self {
+        Self {
+            frames: Vec::new(),
+            operations: Vec::new(),
+            is_recording_frames: true,
+            operation_counts: OperationCounts::default(),
+        }
+    }
+
+    /// Counts operations only; for benchmarks on inputs too large to keep a frame per step.
+    pub fn counting_only() -> Self {
+        Self {
+            is_recording_frames: false,
+            ..Self::new()
+        }
+    }
+
+    pub fn greater(&mut self, left_value: u32, right_value: u32) -> bool {
+        self.operation_counts.comparisons += 1;
+        self.log_operation(SortOperation::Compare);
+        left_value > right_value
+    }
+
+    pub fn less(&mut self, left_value: u32, right_value: u32) -> bool {
+        self.operation_counts.comparisons += 1;
+        self.log_operation(SortOperation::Compare);
+        left_value < right_value
+    }
+
+    pub fn less_or_equal(&mut self, left_value: u32, right_value: u32) -> bool {
+        self.operation_counts.comparisons += 1;
+        self.log_operation(SortOperation::Compare);
+        left_value <= right_value
+    }
+
+    /// A comparison made outside these helpers, e.g. by `Ord` on a tracked element.
+    pub fn record_comparison(&mut self) {
+        self.operation_counts.comparisons += 1;
+        self.log_operation(SortOperation::Compare);
+    }
+
+    pub fn swap(&mut self, values: &mut [u32], first_index: usize, second_index: usize) {
+        values.swap(first_index, second_index);
+        self.operation_counts.swaps += 1;
+        self.log_operation(SortOperation::Swap(first_index, second_index));
+        self.push_frame(values);
+    }
+
+    pub fn write(&mut self, values: &mut [u32], index: usize, value: u32) {
+        values[index] = value;
+        self.operation_counts.writes += 1;
+        self.log_operation(SortOperation::Write(index, value));
+        self.push_frame(values);
+    }
+
+    /// Frames that are not caused by an operation: the input and the sorted result.
+    pub fn record_frame(&mut self, values: &[u32]) {
+        self.push_frame(values);
+    }
+
+    /// Records the final array unless it is already the last frame.
+    pub fn finish(&mut self, values: &[u32]) {
+        if self.frames.last().map(|last_frame| last_frame.as_slice()) != Some(values) {
+            self.push_frame(values);
+        }
+    }
+
+    pub fn operation_counts(&self) -> OperationCounts {
+        self.operation_counts
+    }
+
+    pub fn into_frames_and_operations(self) -> (Vec<Vec<u32>>, Vec<SortOperation>) {
+        (self.frames, self.operations)
+    }
+
+    fn log_operation(&mut self, operation: SortOperation) {
+        if self.is_recording_frames {
+            self.operations.push(operation);
+        }
+    }
+
+    fn push_frame(&mut self, values: &[u32]) {
+        if self.is_recording_frames {
+            self.frames.push(values.to_vec());
+        }
+    }
+}
+
+impl Default for SortRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,387 @@
+use std::fmt::Write as _;
+
+use crate::sorting_algorithms::bubble_sort::bubble_sort_with_recording;
+use crate::sorting_algorithms::cocktail_sort::cocktail_sort_with_recording;
+use crate::sorting_algorithms::comb_sort::comb_sort_with_recording;
+use crate::sorting_algorithms::gnome_sort::gnome_sort_with_recording;
+use crate::sorting_algorithms::heap_sort::heap_sort_with_recording;
+use crate::sorting_algorithms::insertion_sort::insertion_sort_with_recording;
+use crate::sorting_algorithms::intro_sort::intro_sort_with_recording;
+use crate::sorting_algorithms::merge_sort::merge_sort_with_recording;
+use crate::sorting_algorithms::quick_sort::{
+    quick_sort_with_partition,
+    quick_sort_with_recording,
+    PartitionScheme,
+};
+use crate::sorting_algorithms::recorder::SortRecorder;
+use crate::sorting_algorithms::selection_sort::selection_sort_with_recording;
+use crate::sorting_algorithms::shell_sort::shell_sort_with_recording;
+use crate::sorting_algorithms::std_sorts::{
+    std_binary_heap_with_recording,
+    std_select_nth_with_recording,
+    std_stable_sort_with_recording,
+    std_unstable_sort_with_recording,
+};
+use crate::sorting_algorithms::tim_sort::tim_sort_with_recording;
+use crate::sorting_algorithms::SortingAlgorithmKind;
+
+/// Group an algorithm is listed under in the settings panel and the docs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlgorithmCategory {
+    Basic,
+    Classic,
+    Hybrid,
+    StandardLibrary,
+}
+
+impl AlgorithmCategory {
+    pub const ALL: [AlgorithmCategory; 4] = [
+        AlgorithmCategory::Basic,
+        AlgorithmCategory::Classic,
+        AlgorithmCategory::Hybrid,
+        AlgorithmCategory::StandardLibrary,
+    ];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            AlgorithmCategory::Basic => "Basic O(n²) comparison sorts",
+            AlgorithmCategory::Classic => "Classic O(n log n) sorts",
+            AlgorithmCategory::Hybrid => "Hybrid / practical sorts",
+            AlgorithmCategory::StandardLibrary => "Rust standard library",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            AlgorithmCategory::Basic => "Simple, educational sorts – good for seeing the fundamentals.",
+            AlgorithmCategory::Classic => "Divide-and-conquer algorithms used in textbooks and interviews.",
+            AlgorithmCategory::Hybrid => "More realistic, production-style algorithms.",
+            AlgorithmCategory::StandardLibrary =>
+                "Unmodified std sorts; moves are inferred from what they compare.",
+        }
+    }
+}
+
+/// Everything sortvis knows about one algorithm. Complexities describe the implementation
+/// in this crate, as plain text without the O( ).
+pub struct AlgorithmInfo {
+    pub kind: SortingAlgorithmKind,
+    pub display_name: &'static str,
+    pub cli_name: &'static str,
+    pub category: AlgorithmCategory,
+    pub best_case: &'static str,
+    pub average_case: &'static str,
+    pub worst_case: &'static str,
+    pub extra_space: &'static str,
+    pub is_stable: bool,
+    pub is_in_place: bool,
+    pub record: fn(&[u32], &mut SortRecorder),
+    // set for algorithms whose partition scheme can be chosen per instance
+    pub record_partitioned: Option<fn(&[u32], PartitionScheme, &mut SortRecorder)>,
+}
+
+impl AlgorithmInfo {
+    /// One line with complexity, space and stability, used for hover texts.
+    pub fn summary(&self) -> String {
+        format!(
+            "best {}, average {}, worst {}; {} extra space; {}, {}",
+            self.best_case,
+            self.average_case,
+            self.worst_case,
+            self.extra_space,
+            if self.is_stable { "stable" } else { "unstable" },
+            if self.is_in_place { "in place" } else { "not in place" }
+        )
+    }
+}
+
+/// Every algorithm, in the order the settings panel and the grid list them. Adding an algorithm
+/// means a `SortingAlgorithmKind` variant and an entry here.
+pub const ALGORITHMS: [AlgorithmInfo; 16] = [
+    AlgorithmInfo {
+        kind: SortingAlgorithmKind::BubbleSort,
+        display_name: "Bubble Sort",
+        cli_name: "bubble",
+        category: AlgorithmCategory::Basic,
+        best_case: "n",
+        average_case: "n²",
+        worst_case: "n²",
+        extra_space: "1",
+        is_stable: true,
+        is_in_place: true,
+        record: bubble_sort_with_recording,
+        record_partitioned: None,
+    },
+    AlgorithmInfo {
+        kind: SortingAlgorithmKind::QuickSort,
+        display_name: "Quick Sort",
+        cli_name: "quick",
+        category: AlgorithmCategory::Classic,
+        best_case: "n log n",
+        average_case: "n log n",
+        worst_case: "n²",
+        extra_space: "log n",
+        is_stable: false,
+        is_in_place: true,
+        record: quick_sort_with_recording,
+        record_partitioned: Some(quick_sort_with_partition),
+    },
+    AlgorithmInfo {
+        kind: SortingAlgorithmKind::InsertionSort,
+        display_name: "Insertion Sort",
+        cli_name: "insertion",
+        category: AlgorithmCategory::Basic,
+        best_case: "n",
+        average_case: "n²",
+        worst_case: "n²",
+        extra_space: "1",
+        is_stable: true,
+        is_in_place: true,
+        record: insertion_sort_with_recording,
+        record_partitioned: None,
+    },
+    AlgorithmInfo {
+        kind: SortingAlgorithmKind::SelectionSort,
+        display_name: "Selection Sort",
+        cli_name: "selection",
+        category: AlgorithmCategory::Basic,
+        best_case: "n²",
+        average_case: "n²",
+        worst_case: "n²",
+        extra_space: "1",
+        is_stable: false,
+        is_in_place: true,
+        record: selection_sort_with_recording,
+        record_partitioned: None,
+    },
+    AlgorithmInfo {
+        kind: SortingAlgorithmKind::MergeSort,
+        display_name: "Merge Sort",
+        cli_name: "merge",
+        category: AlgorithmCategory::Classic,
+        best_case: "n log n",
+        average_case: "n log n",
+        worst_case: "n log n",
+        extra_space: "n",
+        is_stable: true,
+        is_in_place: false,
+        record: merge_sort_with_recording,
+        record_partitioned: None,
+    },
+    AlgorithmInfo {
+        kind: SortingAlgorithmKind::HeapSort,
+        display_name: "Heap Sort",
+        cli_name: "heap",
+        category: AlgorithmCategory::Classic,
+        best_case: "n log n",
+        average_case: "n log n",
+        worst_case: "n log n",
+        extra_space: "1",
+        is_stable: false,
+        is_in_place: true,
+        record: heap_sort_with_recording,
+        record_partitioned: None,
+    },
+    AlgorithmInfo {
+        kind: SortingAlgorithmKind::ShellSort,
+        display_name: "Shell Sort",
+        cli_name: "shell",
+        category: AlgorithmCategory::Basic,
+        best_case: "n log n",
+        average_case: "n^1.5",
+        worst_case: "n²",
+        extra_space: "1",
+        is_stable: false,
+        is_in_place: true,
+        record: shell_sort_with_recording,
+        record_partitioned: None,
+    },
+    AlgorithmInfo {
+        kind: SortingAlgorithmKind::IntroSort,
+        display_name: "Intro Sort",
+        cli_name: "intro",
+        category: AlgorithmCategory::Classic,
+        best_case: "n log n",
+        average_case: "n log n",
+        worst_case: "n log n",
+        extra_space: "log n",
+        is_stable: false,
+        is_in_place: true,
+        record: intro_sort_with_recording,
+        record_partitioned: None,
+    },
+    AlgorithmInfo {
+        kind: SortingAlgorithmKind::TimSort,
+        display_name: "Tim Sort",
+        cli_name: "tim",
+        category: AlgorithmCategory::Hybrid,
+        best_case: "n log n",
+        average_case: "n log n",
+        worst_case: "n log n",
+        extra_space: "n",
+        is_stable: true,
+        is_in_place: false,
+        record: tim_sort_with_recording,
+        record_partitioned: None,
+    },
+    AlgorithmInfo {
+        kind: SortingAlgorithmKind::CocktailSort,
+        display_name: "Cocktail Shaker Sort",
+        cli_name: "cocktail",
+        category: AlgorithmCategory::Basic,
+        best_case: "n",
+        average_case: "n²",
+        worst_case: "n²",
+        extra_space: "1",
+        is_stable: true,
+        is_in_place: true,
+        record: cocktail_sort_with_recording,
+        record_partitioned: None,
+    },
+    AlgorithmInfo {
+        kind: SortingAlgorithmKind::CombSort,
+        display_name: "Comb Sort",
+        cli_name: "comb",
+        category: AlgorithmCategory::Basic,
+        best_case: "n log n",
+        average_case: "n²/2^p",
+        worst_case: "n²",
+        extra_space: "1",
+        is_stable: false,
+        is_in_place: true,
+        record: comb_sort_with_recording,
+        record_partitioned: None,
+    },
+    AlgorithmInfo {
+        kind: SortingAlgorithmKind::GnomeSort,
+        display_name: "Gnome Sort",
+        cli_name: "gnome",
+        category: AlgorithmCategory::Basic,
+        best_case: "n",
+        average_case: "n²",
+        worst_case: "n²",
+        extra_space: "1",
+        is_stable: true,
+        is_in_place: true,
+        record: gnome_sort_with_recording,
+        record_partitioned: None,
+    },
+    AlgorithmInfo {
+        kind: SortingAlgorithmKind::StdStableSort,
+        display_name: "std slice::sort",
+        cli_name: "std-sort",
+        category: AlgorithmCategory::StandardLibrary,
+        best_case: "n",
+        average_case: "n log n",
+        worst_case: "n log n",
+        extra_space: "n",
+        is_stable: true,
+        is_in_place: false,
+        record: std_stable_sort_with_recording,
+        record_partitioned: None,
+    },
+    AlgorithmInfo {
+        kind: SortingAlgorithmKind::StdUnstableSort,
+        display_name: "std sort_unstable",
+        cli_name: "std-unstable",
+        category: AlgorithmCategory::StandardLibrary,
+        best_case: "n",
+        average_case: "n log n",
+        worst_case: "n log n",
+        extra_space: "log n",
+        is_stable: false,
+        is_in_place: true,
+        record: std_unstable_sort_with_recording,
+        record_partitioned: None,
+    },
+    // partitions around the median only, so the result is not sorted
+    AlgorithmInfo {
+        kind: SortingAlgorithmKind::StdSelectNth,
+        display_name: "std select_nth_unstable",
+        cli_name: "std-select-nth",
+        category: AlgorithmCategory::StandardLibrary,
+        best_case: "n",
+        average_case: "n",
+        worst_case: "n",
+        extra_space: "1",
+        is_stable: false,
+        is_in_place: true,
+        record: std_select_nth_with_recording,
+        record_partitioned: None,
+    },
+    AlgorithmInfo {
+        kind: SortingAlgorithmKind::StdBinaryHeap,
+        display_name: "std BinaryHeap",
+        cli_name: "std-heap",
+        category: AlgorithmCategory::StandardLibrary,
+        best_case: "n log n",
+        average_case: "n log n",
+        worst_case: "n log n",
+        extra_space: "1",
+        is_stable: false,
+        is_in_place: true,
+        record: std_binary_heap_with_recording,
+        record_partitioned: None,
+    },
+];
+
+/// The registry as an aligned text table, for `sortvis algorithms`.
+pub fn format_algorithm_table() -> String {
+    let mut table = format!(
+        "{:<16} {:<24} {:<9} {:<9} {:<9} {:<6} {:<7} {}\n",
+        "name",
+        "algorithm",
+        "best",
+        "average",
+        "worst",
+        "space",
+        "stable",
+        "in place"
+    );
+    for algorithm_info in &ALGORITHMS {
+        let _ = writeln!(
+            table,
+            "{:<16} {:<24} {:<9} {:<9} {:<9} {:<6} {:<7} {}",
+            algorithm_info.cli_name,
+            algorithm_info.display_name,
+            algorithm_info.best_case,
+            algorithm_info.average_case,
+            algorithm_info.worst_case,
+            algorithm_info.extra_space,
+            yes_no(algorithm_info.is_stable),
+            yes_no(algorithm_info.is_in_place)
+        );
+    }
+    table
+}
+
+/// The registry as one markdown table per category; the README's algorithm list is this output.
+pub fn format_algorithm_markdown() -> String {
+    let mut markdown = String::new();
+    for category in AlgorithmCategory::ALL {
+        let _ = writeln!(markdown, "**{}**\n", category.display_name());
+        markdown.push_str("| Algorithm | CLI name | Best | Average | Worst | Extra space | Stable | In place |\n");
+        markdown.push_str("|---|---|---|---|---|---|---|---|\n");
+
+        for algorithm_info in ALGORITHMS.iter().filter(|algorithm_info| algorithm_info.category == category) {
+            let _ = writeln!(
+                markdown,
+                "| {} | `{}` | O({}) | O({}) | O({}) | O({}) | {} | {} |",
+                algorithm_info.display_name,
+                algorithm_info.cli_name,
+                algorithm_info.best_case,
+                algorithm_info.average_case,
+                algorithm_info.worst_case,
+                algorithm_info.extra_space,
+                yes_no(algorithm_info.is_stable),
+                yes_no(algorithm_info.is_in_place)
+            );
+        }
+        markdown.push('\n');
+    }
+    markdown
+}
+
+fn yes_no(flag: bool) -> &'static str {
+    if flag { "yes" } else { "no" }
+}
@@ -1,10 +1,12 @@
-pub fn selection_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Vec<u32>>) {
+use crate::sorting_algorithms::recorder::SortRecorder;
+
+pub fn selection_sort_with_recording(initial_values: &[u32], recorder: &mut SortRecorder) {
     let mut values = initial_values.to_vec();
     if values.is_empty() {
         return;
     }
 
-    frames.push(values.clone());
+    recorder.record_frame(&values);
 
     let length = values.len();
 
@@ -12,18 +14,15 @@ pub fn selection_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Ve
         let mut index_of_minimum = sorted_boundary_index;
 
         for candidate_index in (sorted_boundary_index + 1)..length {
-            if values[candidate_index] < values[index_of_minimum] {
+            if recorder.less(values[candidate_index], values[index_of_minimum]) {
                 index_of_minimum = candidate_index;
             }
         }
 
         if index_of_minimum != sorted_boundary_index {
-            values.swap(sorted_boundary_index, index_of_minimum);
-            frames.push(values.clone());
+            recorder.swap(&mut values, sorted_boundary_index, index_of_minimum);
         }
     }
 
-    if frames.last().map(|last_frame| last_frame.as_slice()) != Some(values.as_slice()) {
-        frames.push(values);
-    }
+    recorder.finish(&values);
 }
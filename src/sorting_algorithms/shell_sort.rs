@@ -1,10 +1,12 @@
-pub fn shell_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Vec<u32>>) {
+use crate::sorting_algorithms::recorder::SortRecorder;
+
+pub fn shell_sort_with_recording(initial_values: &[u32], recorder: &mut SortRecorder) {
     let mut values = initial_values.to_vec();
     if values.is_empty() {
         return;
     }
 
-    frames.push(values.clone());
+    recorder.record_frame(&values);
 
     let length = values.len();
     let mut gap_size = length / 2;
@@ -16,10 +18,9 @@ pub fn shell_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Vec<u3
             let mut insert_index = current_index;
 
             while insert_index >= gap_size
-                && values[insert_index - gap_size] > values[insert_index]
+                && recorder.greater(values[insert_index - gap_size], values[insert_index])
             {
-                values.swap(insert_index, insert_index - gap_size);
-                frames.push(values.clone());
+                recorder.swap(&mut values, insert_index, insert_index - gap_size);
                 insert_index -= gap_size;
             }
 
@@ -29,7 +30,5 @@ pub fn shell_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Vec<u3
         gap_size /= 2;
     }
 
-    if frames.last().map(|last_frame| last_frame.as_slice()) != Some(values.as_slice()) {
-        frames.push(values);
-    }
+    recorder.finish(&values);
 }
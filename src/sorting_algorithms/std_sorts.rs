@@ -0,0 +1,66 @@
+use std::collections::BinaryHeap;
+use std::mem;
+
+use crate::sorting_algorithms::recorder::SortRecorder;
+use crate::sorting_algorithms::tracked::{ Tracked, TrackingSession };
+
+// the standard library's sorts, run unchanged over tracked elements
+
+pub fn std_stable_sort_with_recording(initial_values: &[u32], recorder: &mut SortRecorder) {
+    run_tracked(initial_values, recorder, |tracked_values| tracked_values.sort());
+}
+
+pub fn std_unstable_sort_with_recording(initial_values: &[u32], recorder: &mut SortRecorder) {
+    run_tracked(initial_values, recorder, |tracked_values| tracked_values.sort_unstable());
+}
+
+// places the median and partitions around it; the halves stay unsorted
+pub fn std_select_nth_with_recording(initial_values: &[u32], recorder: &mut SortRecorder) {
+    run_tracked(initial_values, recorder, |tracked_values| {
+        if !tracked_values.is_empty() {
+            tracked_values.select_nth_unstable(select_nth_index(tracked_values.len()));
+        }
+    });
+}
+
+// the position select_nth_unstable is asked for: the median, the upper one for even lengths
+pub fn select_nth_index(value_count: usize) -> usize {
+    value_count / 2
+}
+
+// heapify and pop; both happen in place in the vector's own buffer
+pub fn std_binary_heap_with_recording(initial_values: &[u32], recorder: &mut SortRecorder) {
+    run_tracked_vec(initial_values, recorder, |tracked_values| {
+        BinaryHeap::from(tracked_values).into_sorted_vec()
+    });
+}
+
+fn run_tracked(
+    initial_values: &[u32],
+    recorder: &mut SortRecorder,
+    sort: impl FnOnce(&mut [Tracked<u32>])
+) {
+    run_tracked_vec(initial_values, recorder, |mut tracked_values| {
+        sort(&mut tracked_values);
+        tracked_values
+    });
+}
+
+// the sort gets the registered vector and must return it, or one sharing its buffer
+fn run_tracked_vec(
+    initial_values: &[u32],
+    recorder: &mut SortRecorder,
+    sort: impl FnOnce(Vec<Tracked<u32>>) -> Vec<Tracked<u32>>
+) {
+    if initial_values.is_empty() {
+        return;
+    }
+
+    let session = TrackingSession::new(mem::take(recorder));
+    let tracked_values = session.track(initial_values.to_vec(), |value| *value);
+    session.register(&tracked_values);
+
+    let sorted_values = sort(tracked_values);
+
+    *recorder = session.finish(&sorted_values);
+}
@@ -1,18 +1,19 @@
+use crate::sorting_algorithms::merge_sort::merge_ranges;
+use crate::sorting_algorithms::recorder::SortRecorder;
+
 const MINIMUM_RUN_LENGTH: usize = 32;
 
-pub fn tim_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Vec<u32>>) {
+pub fn tim_sort_with_recording(initial_values: &[u32], recorder: &mut SortRecorder) {
     let mut values = initial_values.to_vec();
     if values.is_empty() {
         return;
     }
 
-    frames.push(values.clone());
+    recorder.record_frame(&values);
 
     let length = values.len();
     if length <= 1 {
-        if frames.last().map(|last_frame| last_frame.as_slice()) != Some(values.as_slice()) {
-            frames.push(values);
-        }
+        recorder.finish(&values);
         return;
     }
 
@@ -26,7 +27,7 @@ pub fn tim_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Vec<u32>
     let mut start_index: usize = 0;
     while start_index < length {
         let end_index = (start_index + run_length).min(length);
-        insertion_sort_range(&mut values, start_index, end_index, frames);
+        insertion_sort_range(&mut values, start_index, end_index, recorder);
         start_index = end_index;
     }
 
@@ -49,7 +50,7 @@ pub fn tim_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Vec<u32>
                 merge_start_index,
                 middle_index,
                 merge_end_index,
-                frames,
+                recorder,
             );
 
             merge_start_index += 2 * current_run_size;
@@ -58,16 +59,14 @@ pub fn tim_sort_with_recording(initial_values: &[u32], frames: &mut Vec<Vec<u32>
         current_run_size *= 2;
     }
 
-    if frames.last().map(|last_frame| last_frame.as_slice()) != Some(values.as_slice()) {
-        frames.push(values);
-    }
+    recorder.finish(&values);
 }
 
 fn insertion_sort_range(
-    values: &mut Vec<u32>,
+    values: &mut [u32],
     start_index: usize,
     end_index: usize,
-    frames: &mut Vec<Vec<u32>>,
+    recorder: &mut SortRecorder,
 ) {
     if end_index <= start_index + 1 {
         return;
@@ -77,51 +76,15 @@ fn insertion_sort_range(
         let current_value = values[unsorted_index];
         let mut insert_index = unsorted_index;
 
-        while insert_index > start_index && values[insert_index - 1] > current_value {
-            values[insert_index] = values[insert_index - 1];
+        while
+            insert_index > start_index &&
+            recorder.greater(values[insert_index - 1], current_value)
+        {
+            let shifted_value = values[insert_index - 1];
+            recorder.write(values, insert_index, shifted_value);
             insert_index -= 1;
-            frames.push(values.clone());
-        }
-
-        values[insert_index] = current_value;
-        frames.push(values.clone());
-    }
-}
-
-fn merge_ranges(
-    values: &mut Vec<u32>,
-    start_index: usize,
-    middle_index: usize,
-    end_index: usize,
-    frames: &mut Vec<Vec<u32>>,
-) {
-    let mut left_index = start_index;
-    let mut right_index = middle_index;
-
-    let mut temporary_values: Vec<u32> = Vec::with_capacity(end_index - start_index);
-
-    while left_index < middle_index && right_index < end_index {
-        if values[left_index] <= values[right_index] {
-            temporary_values.push(values[left_index]);
-            left_index += 1;
-        } else {
-            temporary_values.push(values[right_index]);
-            right_index += 1;
         }
-    }
-
-    while left_index < middle_index {
-        temporary_values.push(values[left_index]);
-        left_index += 1;
-    }
-
-    while right_index < end_index {
-        temporary_values.push(values[right_index]);
-        right_index += 1;
-    }
 
-    for (offset, temporary_value) in temporary_values.into_iter().enumerate() {
-        values[start_index + offset] = temporary_value;
-        frames.push(values.clone());
+        recorder.write(values, insert_index, current_value);
     }
 }
@@ -0,0 +1,163 @@
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::mem;
+use std::rc::Rc;
+
+use crate::sorting_algorithms::recorder::SortRecorder;
+
+/// An element that reports every comparison to a shared recorder, so sorts that know nothing
+/// about recording (std, other crates) can be visualized.
+///
+/// Rust moves values bitwise without running any code, so moves cannot be seen directly.
+/// Instead, every comparison tells us the address and value of the elements involved: when
+/// that address lies inside the registered slice, the element's index follows from it and a
+/// value different from the last one seen there means the sort has written to that position.
+/// Whatever moved without being compared again is caught up by [`TrackingSession::finish`].
+pub struct Tracked<T> {
+    value: T,
+    display_value: u32,
+    log: Rc<RefCell<TrackingLog>>,
+}
+
+impl<T> Tracked<T> {
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    pub fn into_value(self) -> T {
+        self.value
+    }
+
+    fn observe(&self) {
+        self.log.borrow_mut().observe(self as *const Tracked<T> as usize, self.display_value);
+    }
+
+    fn observe_comparison(&self, other: &Tracked<T>) {
+        self.observe();
+        other.observe();
+        self.log.borrow_mut().recorder.record_comparison();
+    }
+}
+
+impl<T: Clone> Clone for Tracked<T> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            display_value: self.display_value,
+            log: Rc::clone(&self.log),
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for Tracked<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.observe_comparison(other);
+        self.value == other.value
+    }
+}
+
+impl<T: Eq> Eq for Tracked<T> {}
+
+impl<T: Ord> PartialOrd for Tracked<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> Ord for Tracked<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.observe_comparison(other);
+        self.value.cmp(&other.value)
+    }
+}
+
+struct TrackingLog {
+    recorder: SortRecorder,
+    // what the recorder believes the slice holds, one display value per position
+    shadow_values: Vec<u32>,
+    slice_start: usize,
+    element_size: usize,
+}
+
+impl TrackingLog {
+    fn index_of(&self, address: usize) -> Option<usize> {
+        let offset = address.checked_sub(self.slice_start)?;
+        let index = offset / self.element_size;
+        if offset % self.element_size != 0 || index >= self.shadow_values.len() {
+            return None;
+        }
+        Some(index)
+    }
+
+    fn observe(&mut self, address: usize, display_value: u32) {
+        // copies held outside the slice (pivots, insertion holes, merge buffers) are not shown
+        let Some(index) = self.index_of(address) else {
+            return;
+        };
+
+        if self.shadow_values[index] != display_value {
+            self.recorder.write(&mut self.shadow_values, index, display_value);
+        }
+    }
+}
+
+/// Owns the recorder while a sort runs over tracked elements.
+pub struct TrackingSession {
+    log: Rc<RefCell<TrackingLog>>,
+}
+
+impl TrackingSession {
+    pub fn new(recorder: SortRecorder) -> Self {
+        Self {
+            log: Rc::new(
+                RefCell::new(TrackingLog {
+                    recorder,
+                    shadow_values: Vec::new(),
+                    slice_start: 0,
+                    element_size: 1,
+                })
+            ),
+        }
+    }
+
+    /// Wraps values for this session; `display_value` gives the number shown for each element.
+    pub fn track<T>(&self, values: Vec<T>, display_value: impl Fn(&T) -> u32) -> Vec<Tracked<T>> {
+        values
+            .into_iter()
+            .map(|value| Tracked {
+                display_value: display_value(&value),
+                value,
+                log: Rc::clone(&self.log),
+            })
+            .collect()
+    }
+
+    /// Positions are inferred relative to this slice; the sort must work on it in place.
+    pub fn register<T>(&self, tracked_values: &[Tracked<T>]) {
+        let mut log = self.log.borrow_mut();
+        log.slice_start = tracked_values.as_ptr() as usize;
+        log.element_size = mem::size_of::<Tracked<T>>();
+        log.shadow_values = tracked_values
+            .iter()
+            .map(|tracked_value| tracked_value.display_value)
+            .collect();
+
+        let TrackingLog { recorder, shadow_values, .. } = &mut *log;
+        recorder.record_frame(shadow_values);
+    }
+
+    /// Records the moves no comparison revealed, then hands the recorder back.
+    pub fn finish<T>(self, tracked_values: &[Tracked<T>]) -> SortRecorder {
+        let mut log = self.log.borrow_mut();
+        for (index, tracked_value) in tracked_values.iter().enumerate() {
+            if index < log.shadow_values.len() && log.shadow_values[index] != tracked_value.display_value {
+                let TrackingLog { recorder, shadow_values, .. } = &mut *log;
+                recorder.write(shadow_values, index, tracked_value.display_value);
+            }
+        }
+
+        let TrackingLog { recorder, shadow_values, .. } = &mut *log;
+        recorder.finish(shadow_values);
+        mem::take(recorder)
+    }
+}
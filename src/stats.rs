@@ -1,21 +1,45 @@
 use std::time::Duration;
 
+use crate::sorting_algorithms::recorder::OperationCounts;
+
+/// Totals and wall time of one recorded run.
 #[derive(Clone, Debug)]
 pub struct SortStats {
     total_steps: u64,
+    operation_counts: OperationCounts,
     duration: Duration,
 }
 
+/// [`SortStats`] flattened for display.
 #[derive(Clone, Debug)]
 pub struct StatsSnapshot {
     pub total_steps: u64,
+    pub comparisons: u64,
+    pub swaps: u64,
+    pub writes: u64,
     pub duration_seconds: f64,
     pub duration_milliseconds: f64,
 }
 
 impl SortStats {
-    pub fn from_measurements(total_steps: u64, duration: Duration) -> Self {
-        Self { total_steps, duration }
+    pub fn from_measurements(
+        total_steps: u64,
+        operation_counts: OperationCounts,
+        duration: Duration
+    ) -> Self {
+        Self { total_steps, operation_counts, duration }
+    }
+
+    pub fn total_steps(&self) -> u64 {
+        self.total_steps
+    }
+
+    pub fn operation_counts(&self) -> OperationCounts {
+        self.operation_counts
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.duration
     }
 
     pub fn to_snapshot(&self) -> StatsSnapshot {
@@ -24,8 +48,26 @@ impl SortStats {
 
         StatsSnapshot { 
             total_steps: self.total_steps,
+            comparisons: self.operation_counts.comparisons,
+            swaps: self.operation_counts.swaps,
+            writes: self.operation_counts.writes,
             duration_seconds,
             duration_milliseconds,
         }
     }
-}
\ No newline at end of file
+}
+
+impl StatsSnapshot {
+    /// The stats line shown under each algorithm's name.
+    pub fn summary_line(&self) -> String {
+        format!(
+            "Steps: {} | Cmp: {} | Swaps: {} | Writes: {} | Time: {:.2} ms ({:.4} s)",
+            self.total_steps,
+            self.comparisons,
+            self.swaps,
+            self.writes,
+            self.duration_milliseconds,
+            self.duration_seconds
+        )
+    }
+}
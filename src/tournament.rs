@@ -0,0 +1,333 @@
+use std::fmt::Write as _;
+use std::time::{ SystemTime, UNIX_EPOCH };
+
+use crate::bench::{ measure_sort, BenchMeasurement, BenchOutputFormat };
+use crate::complexity::CounterKind;
+use crate::input::InputDistribution;
+use crate::sorting_algorithms::SortingAlgorithmKind;
+
+#[derive(Clone, Debug)]
+pub struct TournamentOptions {
+    pub algorithms: Vec<SortingAlgorithmKind>,
+    pub number_of_values: usize,
+    pub input_distribution: InputDistribution,
+    // rounds, seeded first_seed, first_seed + 1, ...
+    pub seed_count: usize,
+    pub first_seed: u64,
+}
+
+impl Default for TournamentOptions {
+    fn default() -> Self {
+        Self {
+            algorithms: SortingAlgorithmKind::ALL.to_vec(),
+            number_of_values: 256,
+            input_distribution: InputDistribution::Random,
+            seed_count: 20,
+            first_seed: 0,
+        }
+    }
+}
+
+// what the tournament ranks and plots: one of the counters, or wall time
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum TournamentMetric {
+    #[default]
+    Comparisons,
+    Swaps,
+    Writes,
+    Moves,
+    Milliseconds,
+}
+
+impl TournamentMetric {
+    pub const ALL: [TournamentMetric; 5] = [
+        TournamentMetric::Comparisons,
+        TournamentMetric::Swaps,
+        TournamentMetric::Writes,
+        TournamentMetric::Moves,
+        TournamentMetric::Milliseconds,
+    ];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            TournamentMetric::Milliseconds => "time (ms)",
+            _ => self.counter_kind().map(|counter_kind| counter_kind.display_name()).unwrap_or(""),
+        }
+    }
+
+    pub fn cli_name(&self) -> &'static str {
+        match self {
+            TournamentMetric::Milliseconds => "ms",
+            _ => self.counter_kind().map(|counter_kind| counter_kind.cli_name()).unwrap_or(""),
+        }
+    }
+
+    pub fn from_cli_name(name: &str) -> Option<TournamentMetric> {
+        TournamentMetric::ALL
+            .into_iter()
+            .find(|metric| metric.cli_name().eq_ignore_ascii_case(name))
+    }
+
+    fn counter_kind(&self) -> Option<CounterKind> {
+        match self {
+            TournamentMetric::Comparisons => Some(CounterKind::Comparisons),
+            TournamentMetric::Swaps => Some(CounterKind::Swaps),
+            TournamentMetric::Writes => Some(CounterKind::Writes),
+            TournamentMetric::Moves => Some(CounterKind::Moves),
+            TournamentMetric::Milliseconds => None,
+        }
+    }
+
+    fn value(&self, measurement: &BenchMeasurement) -> f64 {
+        match self.counter_kind() {
+            Some(counter_kind) => counter_kind.count(&measurement.operation_counts) as f64,
+            None => measurement.duration.as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+// five-number summary plus mean and spread of one metric over every round
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MetricSummary {
+    pub mean: f64,
+    pub median: f64,
+    pub standard_deviation: f64,
+    pub minimum: f64,
+    pub lower_quartile: f64,
+    pub upper_quartile: f64,
+    pub maximum: f64,
+}
+
+impl MetricSummary {
+    pub fn from_samples(samples: &[f64]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+
+        let mut sorted_samples = samples.to_vec();
+        sorted_samples.sort_by(f64::total_cmp);
+
+        let sample_count = sorted_samples.len() as f64;
+        let mean = sorted_samples.iter().sum::<f64>() / sample_count;
+        // population deviation: the rounds are the whole tournament, not a sample of one
+        let variance = sorted_samples
+            .iter()
+            .map(|sample| (sample - mean).powi(2))
+            .sum::<f64>() / sample_count;
+
+        Self {
+            mean,
+            median: quantile(&sorted_samples, 0.5),
+            standard_deviation: variance.sqrt(),
+            minimum: sorted_samples[0],
+            lower_quartile: quantile(&sorted_samples, 0.25),
+            upper_quartile: quantile(&sorted_samples, 0.75),
+            maximum: sorted_samples[sorted_samples.len() - 1],
+        }
+    }
+}
+
+// linear interpolation between closest ranks; expects sorted input
+fn quantile(sorted_samples: &[f64], fraction: f64) -> f64 {
+    let position = fraction * ((sorted_samples.len() - 1) as f64);
+    let lower_index = position.floor() as usize;
+    let upper_index = position.ceil() as usize;
+    let weight = position - (lower_index as f64);
+    sorted_samples[lower_index] * (1.0 - weight) + sorted_samples[upper_index] * weight
+}
+
+#[derive(Clone, Debug)]
+pub struct TournamentEntry {
+    pub algorithm_kind: SortingAlgorithmKind,
+    // one per round, in seed order
+    pub measurements: Vec<BenchMeasurement>,
+}
+
+impl TournamentEntry {
+    pub fn samples(&self, metric: TournamentMetric) -> Vec<f64> {
+        self.measurements.iter().map(|measurement| metric.value(measurement)).collect()
+    }
+
+    pub fn summary(&self, metric: TournamentMetric) -> MetricSummary {
+        MetricSummary::from_samples(&self.samples(metric))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TournamentResult {
+    pub options: TournamentOptions,
+    // seconds since the unix epoch, so exported files can be lined up over time
+    pub recorded_at: u64,
+    pub entries: Vec<TournamentEntry>,
+}
+
+impl TournamentResult {
+    // entry indices, lowest mean first
+    pub fn ranking(&self, metric: TournamentMetric) -> Vec<usize> {
+        let means: Vec<f64> = self.entries
+            .iter()
+            .map(|entry| entry.summary(metric).mean)
+            .collect();
+
+        let mut entry_indices: Vec<usize> = (0..self.entries.len()).collect();
+        entry_indices.sort_by(|left_index, right_index| {
+            means[*left_index].total_cmp(&means[*right_index])
+        });
+        entry_indices
+    }
+}
+
+// every algorithm sorts the same input in each round
+pub fn run_tournament(options: &TournamentOptions) -> TournamentResult {
+    let seed_count = options.seed_count.max(1);
+    let inputs: Vec<Vec<u32>> = (0..seed_count)
+        .map(|seed_offset| {
+            options.input_distribution.generate(
+                options.number_of_values,
+                Some(options.first_seed.wrapping_add(seed_offset as u64))
+            )
+        })
+        .collect();
+
+    let entries = options.algorithms
+        .iter()
+        .map(|algorithm_kind| TournamentEntry {
+            algorithm_kind: *algorithm_kind,
+            measurements: inputs
+                .iter()
+                .map(|base_values| measure_sort(*algorithm_kind, base_values))
+                .collect(),
+        })
+        .collect();
+
+    TournamentResult {
+        options: options.clone(),
+        recorded_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0),
+        entries,
+    }
+}
+
+// ranking table for the terminal, or one row per algorithm and metric for csv and json
+pub fn format_tournament(
+    result: &TournamentResult,
+    ranking_metric: TournamentMetric,
+    output_format: BenchOutputFormat
+) -> String {
+    match output_format {
+        BenchOutputFormat::Table => format_ranking_table(result, ranking_metric),
+        BenchOutputFormat::Csv => format_csv(result),
+        BenchOutputFormat::Json => format_json(result),
+    }
+}
+
+fn format_ranking_table(result: &TournamentResult, ranking_metric: TournamentMetric) -> String {
+    let mut table = String::new();
+    let _ = writeln!(
+        table,
+        "{} rounds, n = {}, {} input, ranked by mean {}",
+        result.options.seed_count.max(1),
+        result.options.number_of_values,
+        result.options.input_distribution.cli_name(),
+        ranking_metric.display_name()
+    );
+    let _ = writeln!(
+        table,
+        "{:>4}  {:<22}{:>14}{:>14}{:>12}{:>14}{:>14}",
+        "rank",
+        "algorithm",
+        "mean",
+        "median",
+        "std dev",
+        "min",
+        "max"
+    );
+
+    for (rank, entry_index) in result.ranking(ranking_metric).into_iter().enumerate() {
+        let entry = &result.entries[entry_index];
+        let summary = entry.summary(ranking_metric);
+        let _ = writeln!(
+            table,
+            "{:>4}  {:<22}{:>14.1}{:>14.1}{:>12.1}{:>14.1}{:>14.1}",
+            rank + 1,
+            entry.algorithm_kind.display_name(),
+            summary.mean,
+            summary.median,
+            summary.standard_deviation,
+            summary.minimum,
+            summary.maximum
+        );
+    }
+    table
+}
+
+fn format_csv(result: &TournamentResult) -> String {
+    let mut csv = String::from(
+        "recorded_at,algorithm,n,input,seeds,first_seed,metric,mean,median,std_dev,min,q1,q3,max\n"
+    );
+
+    for entry in &result.entries {
+        for metric in TournamentMetric::ALL {
+            let summary = entry.summary(metric);
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                result.recorded_at,
+                entry.algorithm_kind.cli_name(),
+                result.options.number_of_values,
+                result.options.input_distribution.cli_name(),
+                result.options.seed_count.max(1),
+                result.options.first_seed,
+                metric.cli_name(),
+                summary.mean,
+                summary.median,
+                summary.standard_deviation,
+                summary.minimum,
+                summary.lower_quartile,
+                summary.upper_quartile,
+                summary.maximum
+            );
+        }
+    }
+    csv
+}
+
+fn format_json(result: &TournamentResult) -> String {
+    let mut json = String::new();
+    let _ = writeln!(json, "{{");
+    let _ = writeln!(json, "  \"recorded_at\": {},", result.recorded_at);
+    let _ = writeln!(json, "  \"n\": {},", result.options.number_of_values);
+    let _ = writeln!(json, "  \"input\": \"{}\",", result.options.input_distribution.cli_name());
+    let _ = writeln!(json, "  \"seeds\": {},", result.options.seed_count.max(1));
+    let _ = writeln!(json, "  \"first_seed\": {},", result.options.first_seed);
+    let _ = writeln!(json, "  \"algorithms\": [");
+
+    for (entry_index, entry) in result.entries.iter().enumerate() {
+        let _ = writeln!(json, "    {{\"algorithm\": \"{}\", \"metrics\": {{", entry.algorithm_kind.cli_name());
+
+        for (metric_index, metric) in TournamentMetric::ALL.iter().enumerate() {
+            let summary = entry.summary(*metric);
+            let _ = write!(
+                json,
+                "      \"{}\": {{\"mean\": {}, \"median\": {}, \"std_dev\": {}, \"min\": {}, \
+                 \"q1\": {}, \"q3\": {}, \"max\": {}}}",
+                metric.cli_name(),
+                summary.mean,
+                summary.median,
+                summary.standard_deviation,
+                summary.minimum,
+                summary.lower_quartile,
+                summary.upper_quartile,
+                summary.maximum
+            );
+            json.push_str(if metric_index + 1 < TournamentMetric::ALL.len() { ",\n" } else { "\n" });
+        }
+
+        json.push_str(if entry_index + 1 < result.entries.len() { "    }},\n" } else { "    }}\n" });
+    }
+
+    json.push_str("  ]\n}\n");
+    json
+}
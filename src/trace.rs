@@ -0,0 +1,99 @@
+use std::sync::atomic::{ AtomicU64, Ordering };
+
+use crate::sorting_algorithms::SortingAlgorithmReplay;
+
+// default number of sampled rows kept per trace
+pub const TRACE_MAX_ROWS: usize = 1024;
+
+static NEXT_TRACE_ID: AtomicU64 = AtomicU64::new(0);
+
+// position-by-time image of a whole run: one row per sampled step
+#[derive(Clone, Debug)]
+pub struct SortTrace {
+    // unique per built trace, so caches of its image never mistake one run for another
+    id: u64,
+    rows: Vec<Vec<u32>>,
+    row_steps: Vec<usize>,
+    maximum_value: u32,
+}
+
+impl SortTrace {
+    pub fn from_replay(replay: &SortingAlgorithmReplay, max_rows: usize) -> Self {
+        let total_steps = replay.step_count();
+        let row_count = total_steps.min(max_rows.max(2));
+
+        let mut rows: Vec<Vec<u32>> = Vec::with_capacity(row_count);
+        let mut row_steps: Vec<usize> = Vec::with_capacity(row_count);
+
+        for row_index in 0..row_count {
+            // spread rows evenly, always keep first and last step
+            let step_index = if row_count > 1 {
+                (row_index * (total_steps - 1)) / (row_count - 1)
+            } else {
+                0
+            };
+
+            rows.push(replay.frame(step_index).to_vec());
+            row_steps.push(step_index);
+        }
+
+        Self::from_sampled_rows(rows, row_steps)
+    }
+
+    // rows already sampled by the caller, with the step each one shows in increasing order
+    pub fn from_sampled_rows(rows: Vec<Vec<u32>>, row_steps: Vec<usize>) -> Self {
+        let maximum_value = rows
+            .iter()
+            .flat_map(|row| row.iter().copied())
+            .max()
+            .unwrap_or(0);
+
+        Self {
+            id: NEXT_TRACE_ID.fetch_add(1, Ordering::Relaxed),
+            rows,
+            row_steps,
+            maximum_value,
+        }
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn rows(&self) -> &[Vec<u32>] {
+        &self.rows
+    }
+
+    pub fn width(&self) -> usize {
+        self.rows.first().map(|row| row.len()).unwrap_or(0)
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn maximum_value(&self) -> u32 {
+        self.maximum_value
+    }
+
+    // fractional row position of a step, for drawing the cursor line
+    pub fn row_position_for_step(&self, step_index: usize) -> f32 {
+        if self.rows.len() <= 1 {
+            return 0.0;
+        }
+
+        let row_index = self.row_steps.partition_point(|&row_step| row_step <= step_index);
+        let lower_row = row_index.saturating_sub(1).min(self.rows.len() - 1);
+        let upper_row = (lower_row + 1).min(self.rows.len() - 1);
+
+        let lower_step = self.row_steps[lower_row];
+        let upper_step = self.row_steps[upper_row];
+        if upper_step <= lower_step {
+            return lower_row as f32;
+        }
+
+        let fraction = ((step_index.min(upper_step) - lower_step) as f32) /
+            ((upper_step - lower_step) as f32);
+        (lower_row as f32) + fraction
+    }
+}
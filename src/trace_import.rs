@@ -0,0 +1,377 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{ self, BufRead, BufReader };
+use std::path::Path;
+use std::time::Duration;
+
+use crate::sorting_algorithms::recorder::{
+    frame_bytes,
+    OperationCounts,
+    SortOperation,
+    MAX_FRAME_BYTES,
+    MAX_OPERATIONS,
+};
+use crate::sorting_algorithms::SortingAlgorithmReplay;
+use crate::stats::SortStats;
+
+// one line of an external trace; the format is described under "Trace files" in the README
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TraceEvent {
+    Init(Vec<u32>),
+    Compare(usize, usize),
+    Swap(usize, usize),
+    Write(usize, u32),
+    Marker(String),
+}
+
+#[derive(Debug)]
+pub enum TraceImportError {
+    Io(io::Error),
+    InvalidLine {
+        line_number: usize,
+        message: String,
+    },
+    MissingInit,
+}
+
+impl fmt::Display for TraceImportError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceImportError::Io(error) => write!(formatter, "i/o error: {}", error),
+            TraceImportError::InvalidLine { line_number, message } =>
+                write!(formatter, "line {}: {}", line_number, message),
+            TraceImportError::MissingInit => write!(formatter, "trace has no init line"),
+        }
+    }
+}
+
+impl std::error::Error for TraceImportError {}
+
+impl From<io::Error> for TraceImportError {
+    fn from(error: io::Error) -> Self {
+        TraceImportError::Io(error)
+    }
+}
+
+// blank lines and '#' comments give None
+pub fn parse_trace_line(line: &str) -> Result<Option<TraceEvent>, String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+
+    let (keyword, arguments) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let arguments = arguments.trim();
+
+    let event = match keyword {
+        "init" => {
+            let values = arguments
+                .split_whitespace()
+                .map(|word| parse_trace_number::<u32>(word, "value"))
+                .collect::<Result<Vec<u32>, String>>()?;
+            TraceEvent::Init(values)
+        }
+        "compare" => {
+            let [first_index, second_index] = two_arguments(keyword, arguments)?;
+            TraceEvent::Compare(
+                parse_trace_number(first_index, "index")?,
+                parse_trace_number(second_index, "index")?
+            )
+        }
+        "swap" => {
+            let [first_index, second_index] = two_arguments(keyword, arguments)?;
+            TraceEvent::Swap(
+                parse_trace_number(first_index, "index")?,
+                parse_trace_number(second_index, "index")?
+            )
+        }
+        "write" => {
+            let [index, value] = two_arguments(keyword, arguments)?;
+            TraceEvent::Write(parse_trace_number(index, "index")?, parse_trace_number(value, "value")?)
+        }
+        "marker" => {
+            if arguments.is_empty() {
+                return Err("marker needs a name".to_owned());
+            }
+            TraceEvent::Marker(arguments.to_owned())
+        }
+        _ => {
+            return Err(format!("unknown operation '{}'", keyword));
+        }
+    };
+
+    Ok(Some(event))
+}
+
+fn two_arguments<'a>(keyword: &str, arguments: &'a str) -> Result<[&'a str; 2], String> {
+    let words: Vec<&str> = arguments.split_whitespace().collect();
+    match words.as_slice() {
+        [first, second] => Ok([first, second]),
+        _ => Err(format!("{} takes 2 arguments, found {}", keyword, words.len())),
+    }
+}
+
+fn parse_trace_number<T: std::str::FromStr>(word: &str, what: &str) -> Result<T, String> {
+    word.parse().map_err(|_| format!("'{}' is not a valid {}", word, what))
+}
+
+// checks events against the array they act on and collects them into a replay
+pub struct TraceBuilder {
+    initial_values: Option<Vec<u32>>,
+    array_length: usize,
+    operations: Vec<SortOperation>,
+    markers: Vec<(usize, String)>,
+    operation_counts: OperationCounts,
+    // frames so far, counting the initial one
+    frame_count: usize,
+    // operations and markers the trace may hold; comparisons keep no frame, so the frame
+    // limit alone does not bound the log
+    maximum_entries: usize,
+}
+
+impl TraceBuilder {
+    pub fn new() -> Self {
+        Self {
+            initial_values: None,
+            array_length: 0,
+            operations: Vec::new(),
+            markers: Vec::new(),
+            operation_counts: OperationCounts::default(),
+            frame_count: 0,
+            maximum_entries: MAX_OPERATIONS,
+        }
+    }
+
+    pub fn apply(&mut self, event: TraceEvent) -> Result<(), String> {
+        if let TraceEvent::Init(values) = event {
+            if self.initial_values.is_some() {
+                return Err("init appears more than once".to_owned());
+            }
+            self.array_length = values.len();
+            self.initial_values = Some(values);
+            return self.add_frame();
+        }
+
+        if self.initial_values.is_none() {
+            return Err("operation before init".to_owned());
+        }
+        if self.operations.len() + self.markers.len() >= self.maximum_entries {
+            return Err(format!("trace is too long: more than {} operations", self.maximum_entries));
+        }
+
+        match event {
+            TraceEvent::Init(_) => unreachable!("init handled above"),
+            TraceEvent::Compare(first_index, second_index) => {
+                self.check_index(first_index)?;
+                self.check_index(second_index)?;
+                self.operations.push(SortOperation::Compare);
+                self.operation_counts.comparisons += 1;
+            }
+            TraceEvent::Swap(first_index, second_index) => {
+                self.check_index(first_index)?;
+                self.check_index(second_index)?;
+                self.add_frame()?;
+                self.operations.push(SortOperation::Swap(first_index, second_index));
+                self.operation_counts.swaps += 1;
+            }
+            TraceEvent::Write(index, value) => {
+                self.check_index(index)?;
+                self.add_frame()?;
+                self.operations.push(SortOperation::Write(index, value));
+                self.operation_counts.writes += 1;
+            }
+            TraceEvent::Marker(name) => {
+                self.markers.push((self.frame_count - 1, name));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn into_replay(self, algorithm_name: String) -> Result<SortingAlgorithmReplay, TraceImportError> {
+        let Some(initial_values) = self.initial_values else {
+            return Err(TraceImportError::MissingInit);
+        };
+
+        // external programs run at their own speed, so there is no meaningful duration
+        let stats = SortStats::from_measurements(
+            self.frame_count as u64,
+            self.operation_counts,
+            Duration::ZERO
+        );
+
+        let replay = SortingAlgorithmReplay::from_operations(
+            algorithm_name,
+            initial_values,
+            self.operations,
+            stats
+        ).expect("trace indices are checked while applying");
+
+        Ok(replay.with_markers(self.markers))
+    }
+
+    fn add_frame(&mut self) -> Result<(), String> {
+        if frame_bytes(self.frame_count + 1, self.array_length) > MAX_FRAME_BYTES {
+            return Err(
+                format!(
+                    "trace is too long: {} frames of {} values need more than {} MB",
+                    self.frame_count + 1,
+                    self.array_length,
+                    MAX_FRAME_BYTES >> 20
+                )
+            );
+        }
+        self.frame_count += 1;
+        Ok(())
+    }
+
+    fn check_index(&self, index: usize) -> Result<(), String> {
+        if index >= self.array_length {
+            return Err(
+                format!("index {} is out of range for an array of {} values", index, self.array_length)
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Default for TraceBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// reads a whole trace file; the replay is named after the file
+pub fn import_trace_file(path: &Path) -> Result<SortingAlgorithmReplay, TraceImportError> {
+    let algorithm_name = path
+        .file_stem()
+        .map(|file_stem| file_stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "Imported trace".to_owned());
+    import_trace(BufReader::new(File::open(path)?), algorithm_name)
+}
+
+fn import_trace(
+    reader: impl BufRead,
+    algorithm_name: String
+) -> Result<SortingAlgorithmReplay, TraceImportError> {
+    let mut trace_builder = TraceBuilder::new();
+
+    for (line_index, line) in reader.lines().enumerate() {
+        let line = line?;
+        parse_trace_line(&line)
+            .and_then(|event| event.map_or(Ok(()), |event| trace_builder.apply(event)))
+            .map_err(|message| TraceImportError::InvalidLine { line_number: line_index + 1, message })?;
+    }
+
+    trace_builder.into_replay(algorithm_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import_text(contents: &str) -> Result<SortingAlgorithmReplay, TraceImportError> {
+        import_trace(contents.as_bytes(), "Trace".to_owned())
+    }
+
+    fn invalid_line(contents: &str) -> (usize, String) {
+        match import_text(contents) {
+            Err(TraceImportError::InvalidLine { line_number, message }) => (line_number, message),
+            other => panic!("expected an invalid line, got {:?}", other.map(|replay| replay.step_count())),
+        }
+    }
+
+    #[test]
+    fn out_of_range_index_names_its_line() {
+        let (line_number, message) = invalid_line("init 3 1 2\nswap 0 1\ncompare 1 2\nswap 2 3\n");
+        assert_eq!(line_number, 4);
+        assert!(message.contains("index 3 is out of range"), "{}", message);
+
+        let Err(error) = import_text("init 3 1 2\nwrite 5 7\n") else {
+            panic!("a write past the end was imported");
+        };
+        assert!(error.to_string().starts_with("line 2:"), "{}", error);
+    }
+
+    #[test]
+    fn operation_before_init_is_invalid() {
+        let (line_number, message) = invalid_line("compare 0 1\ninit 3 1 2\n");
+        assert_eq!(line_number, 1);
+        assert_eq!(message, "operation before init");
+    }
+
+    #[test]
+    fn second_init_is_invalid() {
+        let (line_number, message) = invalid_line("init 3 1 2\nswap 0 1\ninit 4 5 6\n");
+        assert_eq!(line_number, 3);
+        assert_eq!(message, "init appears more than once");
+    }
+
+    #[test]
+    fn unknown_keyword_is_invalid() {
+        let (line_number, message) = invalid_line("init 3 1 2\nshuffle 0 1\n");
+        assert_eq!(line_number, 2);
+        assert_eq!(message, "unknown operation 'shuffle'");
+    }
+
+    #[test]
+    fn comments_and_blank_lines_are_skipped() {
+        assert_eq!(parse_trace_line(""), Ok(None));
+        assert_eq!(parse_trace_line("   \t"), Ok(None));
+        assert_eq!(parse_trace_line("# swap 0 1"), Ok(None));
+        assert_eq!(parse_trace_line("  # indented comment"), Ok(None));
+
+        // skipped lines still count, so later errors keep their line numbers
+        let (line_number, _) = invalid_line("# header\n\ninit 3 1 2\n\n# next\nswap 0 9\n");
+        assert_eq!(line_number, 6);
+    }
+
+    #[test]
+    fn frames_are_capped() {
+        // 4 MB per frame, so the initial frame and 255 swaps just fit in 1 GB
+        let array_length = (MAX_FRAME_BYTES / std::mem::size_of::<u32>()) >> 8;
+        let mut trace_builder = TraceBuilder::new();
+        trace_builder.apply(TraceEvent::Init(vec![0; array_length])).unwrap();
+        for _ in 0..255 {
+            trace_builder.apply(TraceEvent::Swap(0, 1)).unwrap();
+        }
+
+        let message = trace_builder.apply(TraceEvent::Swap(0, 1)).unwrap_err();
+        assert!(message.starts_with("trace is too long: 257 frames"), "{}", message);
+        // comparisons keep no frame, so they are still accepted
+        assert!(trace_builder.apply(TraceEvent::Compare(0, 1)).is_ok());
+    }
+
+    #[test]
+    fn operations_are_capped() {
+        let mut trace_builder = TraceBuilder {
+            maximum_entries: 3,
+            ..TraceBuilder::new()
+        };
+        trace_builder.apply(TraceEvent::Init(vec![2, 1])).unwrap();
+        trace_builder.apply(TraceEvent::Compare(0, 1)).unwrap();
+        trace_builder.apply(TraceEvent::Marker("scan".to_owned())).unwrap();
+        trace_builder.apply(TraceEvent::Compare(0, 1)).unwrap();
+
+        let message = trace_builder.apply(TraceEvent::Compare(0, 1)).unwrap_err();
+        assert_eq!(message, "trace is too long: more than 3 operations");
+    }
+
+    #[test]
+    fn valid_trace_becomes_replay() {
+        let replay = import_text(
+            "# insertion sort of 3 1 2\ninit 3 1 2\ncompare 0 1\nswap 0 1\nmarker first pass\n\
+             compare 1 2\nswap 1 2\ncompare 0 1\nwrite 2 4\n"
+        ).unwrap();
+
+        assert_eq!(replay.name(), "Trace");
+        assert_eq!(replay.step_count(), 4);
+        assert_eq!(replay.frame(0), [3, 1, 2]);
+        assert_eq!(replay.frame(replay.step_count() - 1), [1, 2, 4]);
+        assert_eq!(replay.markers(), [(1, "first pass".to_owned())]);
+
+        let operation_counts = replay.stats().operation_counts();
+        assert_eq!(operation_counts.comparisons, 3);
+        assert_eq!(operation_counts.swaps, 2);
+        assert_eq!(operation_counts.writes, 1);
+    }
+}
@@ -0,0 +1,281 @@
+use std::collections::HashMap;
+use std::sync::atomic::{ AtomicBool, Ordering };
+use std::sync::mpsc::{ self, Receiver, Sender };
+use std::sync::{ Arc, Mutex };
+use std::time::{ Duration, Instant };
+
+use rodio::{ OutputStream, OutputStreamBuilder, Sink, Source };
+
+use crate::sonification::{ NoteEvent, PanelVoice, SonificationSettings, Voice, VoicePool };
+
+const SAMPLE_RATE: u32 = 44_100;
+// notes sound this long after their engine time, so every note of a tick reaches the mixer
+// before it is due and the spacing the engine gave them survives
+const SCHEDULING_DELAY: Duration = Duration::from_millis(60);
+// later notes are dropped instead of piling up, e.g. after the window was hidden
+const MAXIMUM_LATENESS: Duration = Duration::from_millis(100);
+// the mixer's sample clock follows the wall clock; it is pulled back when they drift apart
+const MAXIMUM_CLOCK_DRIFT: Duration = Duration::from_millis(200);
+// frames between checks for new notes
+const POLL_INTERVAL_FRAMES: u64 = 64;
+
+// set by the window, read by the mixer on the audio thread
+struct MixerControls {
+    is_enabled: AtomicBool,
+    silence_requested: AtomicBool,
+    // copied by the mixer on every poll, so a change applies to the next note
+    sonification_settings: Mutex<SonificationSettings>,
+    // by panel id; panels missing here play with the default voice
+    panel_voices: Mutex<HashMap<usize, PanelVoice>>,
+}
+
+struct ScheduledNote {
+    start_frame: u64,
+    voice: Voice,
+}
+
+// endless stereo source playing the notes it receives at the time the engine gave them
+struct VoiceMixer {
+    note_receiver: Receiver<Vec<NoteEvent>>,
+    controls: Arc<MixerControls>,
+    // wall clock time of frame 0
+    clock_origin: Instant,
+    frame_position: u64,
+    // latest start last, so the next due note is popped from the end
+    scheduled_notes: Vec<ScheduledNote>,
+    voice_pool: VoicePool,
+    // right channel of the current frame, returned after the left one
+    pending_right_sample: Option<f32>,
+}
+
+impl VoiceMixer {
+    fn new(note_receiver: Receiver<Vec<NoteEvent>>, controls: Arc<MixerControls>) -> Self {
+        Self {
+            note_receiver,
+            controls,
+            clock_origin: Instant::now(),
+            frame_position: 0,
+            scheduled_notes: Vec::new(),
+            voice_pool: VoicePool::new(),
+            pending_right_sample: None,
+        }
+    }
+
+    fn receive_notes(&mut self) {
+        if self.controls.silence_requested.swap(false, Ordering::SeqCst) {
+            self.scheduled_notes.clear();
+            self.voice_pool.clear();
+        }
+
+        // the output pulls samples at its own pace; keep frame_position close to the wall clock
+        let wall_clock_frame = duration_frames(self.clock_origin.elapsed());
+        if wall_clock_frame.abs_diff(self.frame_position) > duration_frames(MAXIMUM_CLOCK_DRIFT) {
+            self.clock_origin = Instant::now() - frame_duration(self.frame_position);
+        }
+
+        let is_enabled = self.controls.is_enabled.load(Ordering::Relaxed);
+        let sonification_settings = match self.controls.sonification_settings.lock() {
+            Ok(sonification_settings) => sonification_settings.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        };
+        let panel_voices = match self.controls.panel_voices.lock() {
+            Ok(panel_voices) => panel_voices.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        };
+        let oldest_start_frame = self.frame_position.saturating_sub(duration_frames(MAXIMUM_LATENESS));
+        let mut has_new_notes = false;
+
+        while let Ok(note_events) = self.note_receiver.try_recv() {
+            if !is_enabled {
+                continue;
+            }
+
+            for note_event in note_events {
+                let panel_voice = panel_voices.get(&note_event.panel_id).copied().unwrap_or_default();
+                if !panel_voice.is_audible || !sonification_settings.plays(note_event.kind) {
+                    continue;
+                }
+
+                let start_time = (note_event.time + SCHEDULING_DELAY).saturating_duration_since(
+                    self.clock_origin
+                );
+                let start_frame = duration_frames(start_time);
+                if start_frame < oldest_start_frame {
+                    continue;
+                }
+
+                self.scheduled_notes.push(ScheduledNote {
+                    start_frame,
+                    voice: Voice::new(&note_event, &panel_voice, &sonification_settings, SAMPLE_RATE),
+                });
+                has_new_notes = true;
+            }
+        }
+
+        if has_new_notes {
+            self.scheduled_notes.sort_unstable_by_key(|scheduled_note| std::cmp::Reverse(scheduled_note.start_frame));
+        }
+    }
+
+    fn start_due_notes(&mut self) {
+        while
+            let Some(scheduled_note) = self.scheduled_notes
+                .pop_if(|scheduled_note| scheduled_note.start_frame <= self.frame_position)
+        {
+            self.voice_pool.start(scheduled_note.voice);
+        }
+    }
+
+    fn next_frame(&mut self) -> [f32; 2] {
+        if self.frame_position.is_multiple_of(POLL_INTERVAL_FRAMES) {
+            self.receive_notes();
+        }
+        self.start_due_notes();
+
+        self.frame_position += 1;
+        self.voice_pool.next_frame()
+    }
+}
+
+impl Iterator for VoiceMixer {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if let Some(right_sample) = self.pending_right_sample.take() {
+            return Some(right_sample);
+        }
+
+        let [left_sample, right_sample] = self.next_frame();
+        self.pending_right_sample = Some(right_sample);
+        Some(left_sample)
+    }
+}
+
+impl Source for VoiceMixer {
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        2
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+// the engine's notes, mixed into one stereo stream
+pub struct AudioOutput {
+    // must keep _audio_stream alive for audio to work
+    _audio_stream: Option<OutputStream>,
+    audio_sink: Option<Sink>,
+    note_sender: Option<Sender<Vec<NoteEvent>>>,
+    controls: Arc<MixerControls>,
+}
+
+impl AudioOutput {
+    pub fn new() -> Self {
+        let controls = Arc::new(MixerControls {
+            is_enabled: AtomicBool::new(true),
+            silence_requested: AtomicBool::new(false),
+            sonification_settings: Mutex::new(SonificationSettings::default()),
+            panel_voices: Mutex::new(HashMap::new()),
+        });
+
+        // initialize audio, handle failure gracefully
+        let Ok(audio_stream) = OutputStreamBuilder::open_default_stream() else {
+            return Self {
+                _audio_stream: None,
+                audio_sink: None,
+                note_sender: None,
+                controls,
+            };
+        };
+
+        let (note_sender, note_receiver) = mpsc::channel();
+        let sink = Sink::connect_new(audio_stream.mixer());
+        sink.set_volume(0.2);
+        sink.append(VoiceMixer::new(note_receiver, Arc::clone(&controls)));
+
+        Self {
+            _audio_stream: Some(audio_stream),
+            audio_sink: Some(sink),
+            note_sender: Some(note_sender),
+            controls,
+        }
+    }
+
+    // for the engine; None when there is no audio device
+    pub fn note_sender(&self) -> Option<Sender<Vec<NoteEvent>>> {
+        self.note_sender.clone()
+    }
+
+    // notes that do not come from the engine, e.g. the live panel's
+    pub fn play_notes(&self, note_events: Vec<NoteEvent>) {
+        if let Some(note_sender) = &self.note_sender {
+            if !note_events.is_empty() {
+                let _ = note_sender.send(note_events);
+            }
+        }
+    }
+
+    pub fn set_volume(&self, volume: f32) {
+        if let Some(sink) = &self.audio_sink {
+            sink.set_volume(volume);
+        }
+    }
+
+    // while disabled, incoming notes are dropped rather than kept for later
+    pub fn set_enabled(&self, is_enabled: bool) {
+        if !is_enabled && self.controls.is_enabled.load(Ordering::Relaxed) {
+            self.clear();
+        }
+        self.controls.is_enabled.store(is_enabled, Ordering::Relaxed);
+    }
+
+    // takes effect from the next note on
+    pub fn set_sonification_settings(&self, sonification_settings: &SonificationSettings) {
+        let mut current_settings = match self.controls.sonification_settings.lock() {
+            Ok(current_settings) => current_settings,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if *current_settings != *sonification_settings {
+            *current_settings = sonification_settings.clone();
+        }
+    }
+
+    // pan and mute state of each panel, replacing the previous ones
+    pub fn set_panel_voices(&self, panel_voices: HashMap<usize, PanelVoice>) {
+        let mut current_voices = match self.controls.panel_voices.lock() {
+            Ok(current_voices) => current_voices,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if *current_voices != panel_voices {
+            *current_voices = panel_voices;
+        }
+    }
+
+    // stops every playing and scheduled note
+    pub fn clear(&self) {
+        self.controls.silence_requested.store(true, Ordering::SeqCst);
+    }
+}
+
+impl Default for AudioOutput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn duration_frames(duration: Duration) -> u64 {
+    (duration.as_secs_f64() * (SAMPLE_RATE as f64)) as u64
+}
+
+fn frame_duration(frame_count: u64) -> Duration {
+    Duration::from_secs_f64((frame_count as f64) / (SAMPLE_RATE as f64))
+}
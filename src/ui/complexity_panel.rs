@@ -0,0 +1,308 @@
+use std::thread::JoinHandle;
+
+use eframe::egui::{ self, Color32 };
+
+use crate::bench::spawn_bench_thread;
+use crate::complexity::{
+    fit_complexity,
+    run_sweep,
+    ComplexityModel,
+    CounterKind,
+    SweepOptions,
+    SweepSeries,
+    SWEEP_SIZE_RANGE,
+};
+use crate::input::InputDistribution;
+use crate::sorting_algorithms::SortingAlgorithmKind;
+
+const PLOT_HEIGHT: f32 = 260.0;
+const PLOT_MARGIN_LEFT: f32 = 44.0;
+const PLOT_MARGIN_BOTTOM: f32 = 20.0;
+const PLOT_MARGIN_TOP: f32 = 8.0;
+const PLOT_MARGIN_RIGHT: f32 = 56.0;
+
+// size sweep controls, the last results and the sweep still running, if any
+pub struct ComplexityPanelState {
+    pub is_open: bool,
+    minimum_size: usize,
+    maximum_size: usize,
+    counter_kind: CounterKind,
+    show_reference_curves: bool,
+    sweep_series: Vec<SweepSeries>,
+    pending_sweep: Option<JoinHandle<Vec<SweepSeries>>>,
+}
+
+impl Default for ComplexityPanelState {
+    fn default() -> Self {
+        let sweep_options = SweepOptions::default();
+        Self {
+            is_open: false,
+            minimum_size: sweep_options.minimum_size,
+            maximum_size: sweep_options.maximum_size,
+            counter_kind: CounterKind::Comparisons,
+            show_reference_curves: true,
+            sweep_series: Vec::new(),
+            pending_sweep: None,
+        }
+    }
+}
+
+impl ComplexityPanelState {
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        selected_algorithms: Vec<SortingAlgorithmKind>,
+        input_distribution: InputDistribution
+    ) {
+        self.collect_finished_sweep();
+
+        ui.heading("Complexity");
+
+        ui.horizontal(|ui| {
+            ui.label("n from");
+            ui.add(egui::DragValue::new(&mut self.minimum_size).range(SWEEP_SIZE_RANGE));
+            ui.label("to");
+            ui.add(egui::DragValue::new(&mut self.maximum_size).range(SWEEP_SIZE_RANGE));
+        });
+        self.maximum_size = self.maximum_size.max(self.minimum_size);
+
+        ui.horizontal(|ui| {
+            egui::ComboBox
+                ::from_id_salt("complexity_counter")
+                .selected_text(self.counter_kind.display_name())
+                .show_ui(ui, |combo_ui| {
+                    for counter_kind in CounterKind::ALL {
+                        combo_ui.selectable_value(
+                            &mut self.counter_kind,
+                            counter_kind,
+                            counter_kind.display_name()
+                        );
+                    }
+                });
+
+            ui.checkbox(&mut self.show_reference_curves, "Reference curves");
+        });
+
+        ui.horizontal(|ui| {
+            let is_running = self.pending_sweep.is_some();
+            let can_start = !is_running && !selected_algorithms.is_empty();
+
+            if ui.add_enabled(can_start, egui::Button::new("Run sweep")).clicked() {
+                let sweep_options = SweepOptions {
+                    algorithms: selected_algorithms,
+                    minimum_size: self.minimum_size,
+                    maximum_size: self.maximum_size,
+                    input_distribution,
+                    ..SweepOptions::default()
+                };
+                self.pending_sweep = Some(spawn_bench_thread(move || run_sweep(&sweep_options)));
+            }
+
+            if is_running {
+                ui.spinner();
+                ui.label("Counting...");
+                ui.ctx().request_repaint();
+            }
+        });
+
+        ui.label(
+            egui::RichText
+                ::new("Uses the algorithms and input selected on the left, sizes doubling.")
+                .small()
+                .italics()
+        );
+
+        ui.separator();
+
+        if self.sweep_series.is_empty() {
+            ui.label("Run a sweep to plot operation counts against n.");
+            return;
+        }
+
+        self.draw_log_log_plot(ui);
+
+        ui.add_space(6.0);
+
+        // fitted growth per algorithm
+        egui::Grid
+            ::new("complexity_fits")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |grid_ui| {
+                for (series_index, series) in self.sweep_series.iter().enumerate() {
+                    grid_ui.label(
+                        egui::RichText
+                            ::new(series.algorithm_kind.display_name())
+                            .color(series_color(series_index))
+                    );
+
+                    let samples = series.samples(self.counter_kind);
+                    match fit_complexity(&samples) {
+                        Some(complexity_fit) => {
+                            grid_ui
+                                .label(complexity_fit.describe(self.counter_kind))
+                                .on_hover_text(complexity_fit.describe_power_law());
+                        }
+                        None => {
+                            grid_ui.label(series.missing_fit_reason(self.counter_kind));
+                        }
+                    }
+                    grid_ui.end_row();
+                }
+            });
+    }
+
+    fn collect_finished_sweep(&mut self) {
+        let is_finished = self.pending_sweep
+            .as_ref()
+            .is_some_and(|pending_sweep| pending_sweep.is_finished());
+
+        if is_finished {
+            if let Some(pending_sweep) = self.pending_sweep.take() {
+                // a panicking sweep just leaves the previous results in place
+                if let Ok(sweep_series) = pending_sweep.join() {
+                    self.sweep_series = sweep_series;
+                }
+            }
+        }
+    }
+
+    fn draw_log_log_plot(&self, ui: &mut egui::Ui) {
+        let plot_size = egui::vec2(ui.available_width(), PLOT_HEIGHT);
+        let (response, painter) = ui.allocate_painter(plot_size, egui::Sense::hover());
+        let visuals = ui.visuals();
+
+        painter.rect_filled(response.rect, 4.0, visuals.extreme_bg_color);
+
+        let plot_rect = egui::Rect::from_min_max(
+            egui::pos2(response.rect.left() + PLOT_MARGIN_LEFT, response.rect.top() + PLOT_MARGIN_TOP),
+            egui::pos2(
+                response.rect.right() - PLOT_MARGIN_RIGHT,
+                response.rect.bottom() - PLOT_MARGIN_BOTTOM
+            )
+        );
+        if plot_rect.width() <= 0.0 || plot_rect.height() <= 0.0 {
+            return;
+        }
+
+        let all_samples: Vec<Vec<(f64, f64)>> = self.sweep_series
+            .iter()
+            .map(|series| series.samples(self.counter_kind))
+            .collect();
+
+        // axis ranges in log10, widened to whole decades
+        let sizes = all_samples.iter().flatten().map(|(number_of_values, _)| *number_of_values);
+        let (Some(minimum_n), Some(maximum_n)) = (
+            sizes.clone().reduce(f64::min),
+            sizes.reduce(f64::max),
+        ) else {
+            return;
+        };
+
+        let mut counts: Vec<f64> = all_samples
+            .iter()
+            .flatten()
+            .map(|(_, count)| *count)
+            .collect();
+        if self.show_reference_curves {
+            for model in ComplexityModel::REFERENCE_CURVES {
+                counts.push(model.evaluate(minimum_n));
+                counts.push(model.evaluate(maximum_n));
+            }
+        }
+        let minimum_count = counts.iter().copied().filter(|count| *count > 0.0).fold(f64::INFINITY, f64::min);
+        let maximum_count = counts.iter().copied().fold(1.0, f64::max);
+
+        let x_range = (minimum_n.log10().floor(), maximum_n.log10().ceil().max(minimum_n.log10().floor() + 1.0));
+        let y_range = (
+            minimum_count.max(1.0).log10().floor(),
+            maximum_count.log10().ceil().max(minimum_count.max(1.0).log10().floor() + 1.0),
+        );
+
+        let to_screen = |number_of_values: f64, count: f64| -> egui::Pos2 {
+            let x_fraction = (number_of_values.log10() - x_range.0) / (x_range.1 - x_range.0);
+            let y_fraction = (count.log10() - y_range.0) / (y_range.1 - y_range.0);
+            egui::pos2(
+                plot_rect.left() + plot_rect.width() * (x_fraction as f32),
+                plot_rect.bottom() - plot_rect.height() * (y_fraction as f32)
+            )
+        };
+
+        let grid_stroke = egui::Stroke::new(1.0, visuals.weak_text_color().gamma_multiply(0.3));
+        let label_color = visuals.weak_text_color();
+        let label_font = egui::FontId::proportional(10.0);
+
+        // decade grid lines
+        for decade in (x_range.0 as i32)..=(x_range.1 as i32) {
+            let x_position = to_screen((10f64).powi(decade), (10f64).powf(y_range.0)).x;
+            painter.vline(x_position, plot_rect.y_range(), grid_stroke);
+            painter.text(
+                egui::pos2(x_position, plot_rect.bottom() + 2.0),
+                egui::Align2::CENTER_TOP,
+                format!("10^{}", decade),
+                label_font.clone(),
+                label_color
+            );
+        }
+        for decade in (y_range.0 as i32)..=(y_range.1 as i32) {
+            let y_position = to_screen((10f64).powf(x_range.0), (10f64).powi(decade)).y;
+            painter.hline(plot_rect.x_range(), y_position, grid_stroke);
+            painter.text(
+                egui::pos2(plot_rect.left() - 4.0, y_position),
+                egui::Align2::RIGHT_CENTER,
+                format!("10^{}", decade),
+                label_font.clone(),
+                label_color
+            );
+        }
+
+        let plot_painter = painter.with_clip_rect(plot_rect);
+
+        // theoretical curves, unscaled, dashed behind the measurements
+        if self.show_reference_curves {
+            let reference_stroke = egui::Stroke::new(1.0, visuals.text_color().gamma_multiply(0.5));
+            for model in ComplexityModel::REFERENCE_CURVES {
+                let curve_points: Vec<egui::Pos2> = (0..=32)
+                    .map(|sample_index| {
+                        let log_n = x_range.0 + (x_range.1 - x_range.0) * (sample_index as f64) / 32.0;
+                        let number_of_values = (10f64).powf(log_n).max(2.0);
+                        to_screen(number_of_values, model.evaluate(number_of_values).max(1e-9))
+                    })
+                    .collect();
+
+                plot_painter.extend(egui::Shape::dashed_line(&curve_points, reference_stroke, 6.0, 4.0));
+
+                let end_position = to_screen(maximum_n, model.evaluate(maximum_n));
+                if plot_rect.y_range().contains(end_position.y) {
+                    painter.text(
+                        egui::pos2(plot_rect.right() + 4.0, end_position.y),
+                        egui::Align2::LEFT_CENTER,
+                        model.formula(),
+                        label_font.clone(),
+                        label_color
+                    );
+                }
+            }
+        }
+
+        // measured counts, one polyline per algorithm
+        for (series_index, samples) in all_samples.iter().enumerate() {
+            let color = series_color(series_index);
+            let points: Vec<egui::Pos2> = samples
+                .iter()
+                .map(|(number_of_values, count)| to_screen(*number_of_values, *count))
+                .collect();
+
+            plot_painter.add(egui::Shape::line(points.clone(), egui::Stroke::new(1.5, color)));
+            for point in points {
+                plot_painter.circle_filled(point, 2.5, color);
+            }
+        }
+    }
+}
+
+// well separated hues, stable per series position
+fn series_color(series_index: usize) -> Color32 {
+    let hue = ((series_index as f32) * 0.618_034).fract();
+    egui::ecolor::Hsva::new(hue, 0.75, 0.95, 1.0).into()
+}
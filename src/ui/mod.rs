@@ -1,2 +1,14 @@
+#[cfg(feature = "audio")]
+pub mod audio;
+pub mod complexity_panel;
+pub mod panel_view;
+pub mod playlist_player;
+pub mod settings_file;
 pub mod settings_panel;
-pub mod ui;
\ No newline at end of file
+#[cfg(not(feature = "audio"))]
+#[path = "silent_audio.rs"]
+pub mod audio;
+pub mod tournament_panel;
+pub mod trace_view;
+#[allow(clippy::module_inception)]
+pub mod ui;
@@ -0,0 +1,21 @@
+use std::time::Instant;
+
+use eframe::egui;
+
+use crate::chart::ChartStyle;
+use crate::ui::trace_view::TraceViewState;
+
+// per-panel view settings, keyed by algorithm name
+#[derive(Clone, Default)]
+pub struct PanelViewState {
+    pub chart_style: ChartStyle,
+    pub show_trace: bool,
+    // audio: a muted panel is silent, and while any panel is soloed only soloed ones play
+    pub is_muted: bool,
+    pub is_soloed: bool,
+    // when the verification sweep of the finished run began; cleared while it runs
+    pub sweep_started_at: Option<Instant>,
+    pub trace_view: TraceViewState,
+    // permuted picture for image runs, tagged with the step it shows; dropped when a run starts
+    pub image_texture: Option<(egui::TextureHandle, usize)>,
+}
@@ -0,0 +1,99 @@
+use std::time::{ Duration, Instant };
+
+use crate::engine::{ EngineSharedState, EngineState };
+use crate::scenario::{ Playlist, Scenario };
+
+// the open playlist, which scenario is on screen and when its run finished
+pub struct PlaylistPlayer {
+    pub playlist: Playlist,
+    pub current_index: usize,
+    // starts as the file's auto_advance; the settings panel can toggle it
+    pub auto_advance: bool,
+    // false while the grid shows something else, e.g. a run started from the settings panel
+    is_showing: bool,
+    // set once the current run was seen preparing or running, so the idle state before the
+    // worker picks it up is not taken for a finished run
+    has_started: bool,
+    finished_since: Option<Instant>,
+}
+
+impl PlaylistPlayer {
+    pub fn new(playlist: Playlist) -> Self {
+        let auto_advance = playlist.auto_advance;
+        Self {
+            playlist,
+            current_index: 0,
+            auto_advance,
+            is_showing: false,
+            has_started: false,
+            finished_since: None,
+        }
+    }
+
+    pub fn current(&self) -> &Scenario {
+        &self.playlist.scenarios[self.current_index]
+    }
+
+    pub fn scenario_count(&self) -> usize {
+        self.playlist.scenarios.len()
+    }
+
+    // makes the scenario current; the caller starts its run
+    pub fn select(&mut self, scenario_index: usize) {
+        self.current_index = scenario_index.min(self.scenario_count() - 1);
+        self.is_showing = true;
+        self.has_started = false;
+        self.finished_since = None;
+    }
+
+    // the current scenario, while its run is the one on screen
+    pub fn showing(&self) -> Option<&Scenario> {
+        self.is_showing.then(|| self.current())
+    }
+
+    // another run took over the grid; the playlist stays open at the same position
+    pub fn leave(&mut self) {
+        self.is_showing = false;
+    }
+
+    pub fn next_index(&self) -> Option<usize> {
+        Some(self.current_index + 1).filter(|next_index| *next_index < self.scenario_count())
+    }
+
+    pub fn previous_index(&self) -> Option<usize> {
+        self.current_index.checked_sub(1)
+    }
+
+    // the scenario to play next once the current run has finished and stayed on screen for
+    // its hold time; runs that were stopped never advance
+    pub fn poll_auto_advance(&mut self, engine_state: &EngineSharedState) -> Option<usize> {
+        if !self.is_showing {
+            return None;
+        }
+
+        match engine_state.engine_state {
+            EngineState::Preparing { .. } | EngineState::Running => {
+                self.has_started = true;
+                self.finished_since = None;
+                return None;
+            }
+            EngineState::Idle => {}
+        }
+
+        let has_finished =
+            self.has_started &&
+            !engine_state.algorithm_states.is_empty() &&
+            engine_state.algorithm_states.iter().all(|algorithm_state| algorithm_state.is_finished);
+        if !has_finished {
+            return None;
+        }
+
+        let finished_since = *self.finished_since.get_or_insert_with(Instant::now);
+        let hold_duration = Duration::from_secs_f32(self.current().hold_seconds);
+        if self.auto_advance && finished_since.elapsed() >= hold_duration {
+            self.next_index()
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,578 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::ops::RangeInclusive;
+use std::path::{ Path, PathBuf };
+use std::time::{ Duration, Instant };
+
+use toml::{ Table, Value };
+
+use crate::engine::{ FRAMES_PER_SECOND_RANGE, NUMBER_OF_VALUES_RANGE };
+use crate::export::midi::{ STEPS_PER_BEAT_RANGE, TEMPO_RANGE_BPM };
+use crate::image_source::ImageSliceMode;
+use crate::input::InputDistribution;
+use crate::live_trace::LiveTraceSourceKind;
+use crate::palette::Palette;
+use crate::sonification::{ PitchSource, Scale, SonificationSettings, Waveform };
+use crate::sorting_algorithms::instance::AlgorithmInstance;
+use crate::sorting_algorithms::quick_sort::PartitionScheme;
+use crate::sorting_algorithms::SortingAlgorithmKind;
+use crate::ui::settings_panel::SettingsPanelState;
+
+// settings.toml schema version; bump it together with a new entry in MIGRATIONS
+pub const SETTINGS_VERSION: i64 = 1;
+
+// MIGRATIONS[n] turns a version n + 1 table into version n + 2. a step is only needed when a
+// setting is renamed or changes meaning; added settings are simply missing from older files
+// and keep their defaults, and removed ones are ignored.
+const MIGRATIONS: [fn(&mut Table); (SETTINGS_VERSION - 1) as usize] = [];
+
+// how often the settings are compared with what was last written
+const SAVE_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+// sizes of the window and its side panels; egui does not keep them between launches
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindowLayout {
+    pub window_size: Option<[f32; 2]>,
+    pub settings_panel_width: f32,
+    pub complexity_panel_width: f32,
+    pub tournament_panel_width: f32,
+    pub complexity_panel_open: bool,
+    pub tournament_panel_open: bool,
+}
+
+impl Default for WindowLayout {
+    fn default() -> Self {
+        Self {
+            window_size: None,
+            settings_panel_width: 220.0,
+            complexity_panel_width: 380.0,
+            tournament_panel_width: 380.0,
+            complexity_panel_open: false,
+            tournament_panel_open: false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SettingsFileError {
+    Io(io::Error),
+    Parse(String),
+}
+
+impl fmt::Display for SettingsFileError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsFileError::Io(error) => write!(formatter, "i/o error: {}", error),
+            SettingsFileError::Parse(message) => write!(formatter, "invalid settings file: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for SettingsFileError {}
+
+impl From<io::Error> for SettingsFileError {
+    fn from(error: io::Error) -> Self {
+        SettingsFileError::Io(error)
+    }
+}
+
+// the settings file of this session; saves whenever the settings or layout change
+pub struct SettingsFile {
+    path: Option<PathBuf>,
+    last_saved_text: String,
+    // set when saving would destroy something: a file from a newer version, or one that
+    // could not be read
+    read_only_reason: Option<String>,
+    last_check_time: Instant,
+}
+
+impl SettingsFile {
+    // $XDG_CONFIG_HOME/sortvis/settings.toml on linux, the platform's config dir elsewhere
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|config_dir| config_dir.join("sortvis").join("settings.toml"))
+    }
+
+    // reads the file if there is one; anything missing or invalid keeps its default
+    pub fn load(path: Option<PathBuf>) -> (Self, SettingsPanelState, WindowLayout) {
+        let mut settings = SettingsPanelState::default();
+        let mut layout = WindowLayout::default();
+        let mut read_only_reason = None;
+        let mut last_saved_text = String::new();
+
+        if let Some(path) = &path {
+            match read_settings_table(path) {
+                Ok(Some((table, file_version))) => {
+                    apply_settings(&table, &mut settings, &mut layout);
+                    if file_version > SETTINGS_VERSION {
+                        read_only_reason = Some(
+                            format!(
+                                "{} is from a newer version of sortvis (schema {}); changes are not saved",
+                                path.display(),
+                                file_version
+                            )
+                        );
+                    } else {
+                        last_saved_text = settings_to_toml(&settings, &layout);
+                    }
+                }
+                Ok(None) => {}
+                Err(error) => {
+                    read_only_reason = Some(
+                        format!("Could not read {}: {}; changes are not saved", path.display(), error)
+                    );
+                }
+            }
+        }
+
+        let settings_file = Self {
+            path,
+            last_saved_text,
+            read_only_reason,
+            last_check_time: Instant::now(),
+        };
+        (settings_file, settings, layout)
+    }
+
+    // for a session started with settings from the command line, e.g. a kiosk shortcut: the
+    // file keeps the user's own settings
+    pub fn save_nothing_this_session(&mut self, reason: &str) {
+        if self.read_only_reason.is_none() && self.path.is_some() {
+            self.read_only_reason = Some(format!("{}; changes are not saved", reason));
+        }
+    }
+
+    pub fn status(&self) -> Option<&str> {
+        self.read_only_reason.as_deref()
+    }
+
+    // writes the file when something changed since the last write, at most every few seconds
+    pub fn save_if_changed(&mut self, settings: &SettingsPanelState, layout: &WindowLayout) {
+        if self.read_only_reason.is_some() || self.last_check_time.elapsed() < SAVE_CHECK_INTERVAL {
+            return;
+        }
+        self.last_check_time = Instant::now();
+
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let settings_text = settings_to_toml(settings, layout);
+        if settings_text == self.last_saved_text {
+            return;
+        }
+
+        match write_settings_text(path, &settings_text) {
+            Ok(()) => {
+                self.last_saved_text = settings_text;
+            }
+            Err(error) => {
+                // keep the session running; the next change tries again
+                eprintln!("warning: could not save settings to {}: {}", path.display(), error);
+            }
+        }
+    }
+}
+
+// Ok(None) when there is no file yet
+fn read_settings_table(path: &Path) -> Result<Option<(Table, i64)>, SettingsFileError> {
+    let settings_text = match fs::read_to_string(path) {
+        Ok(settings_text) => settings_text,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            return Ok(None);
+        }
+        Err(error) => {
+            return Err(error.into());
+        }
+    };
+
+    parse_settings_text(&settings_text).map(Some)
+}
+
+// the table of a settings file, migrated to the current schema, and the version it was written as
+fn parse_settings_text(settings_text: &str) -> Result<(Table, i64), SettingsFileError> {
+    let mut table: Table = settings_text
+        .parse()
+        .map_err(|error: toml::de::Error| SettingsFileError::Parse(error.message().to_owned()))?;
+
+    // files without a version predate nothing, so they count as the first schema
+    let file_version = table
+        .get("version")
+        .and_then(Value::as_integer)
+        .unwrap_or(1)
+        .max(1);
+
+    if file_version < SETTINGS_VERSION {
+        for migration in &MIGRATIONS[(file_version - 1) as usize..] {
+            migration(&mut table);
+        }
+    }
+
+    Ok((table, file_version))
+}
+
+// writes next to the file and renames, so a crash never leaves half a settings file
+fn write_settings_text(path: &Path, settings_text: &str) -> Result<(), SettingsFileError> {
+    if let Some(parent_dir) = path.parent() {
+        fs::create_dir_all(parent_dir)?;
+    }
+    let temporary_path = path.with_extension("toml.tmp");
+    fs::write(&temporary_path, settings_text)?;
+    fs::rename(&temporary_path, path)?;
+    Ok(())
+}
+
+pub fn settings_to_toml(settings: &SettingsPanelState, layout: &WindowLayout) -> String {
+    let mut run = Table::new();
+    run.insert("values".to_owned(), Value::Integer(settings.number_of_values as i64));
+    run.insert("fps".to_owned(), Value::Integer(settings.frames_per_second as i64));
+    run.insert("input".to_owned(), string_value(settings.input_distribution.cli_name()));
+    if let Some(input_seed) = settings.input_seed() {
+        // toml integers are signed; the bits round-trip through the cast
+        run.insert("seed".to_owned(), Value::Integer(input_seed as i64));
+    }
+
+    let panels = settings.algorithm_instances
+        .iter()
+        .map(|algorithm_instance| {
+            let mut panel = Table::new();
+            panel.insert("algorithm".to_owned(), string_value(algorithm_instance.kind.cli_name()));
+            if algorithm_instance.has_partition_scheme() {
+                panel.insert(
+                    "partition".to_owned(),
+                    string_value(algorithm_instance.partition_scheme.cli_name())
+                );
+            }
+            if let Some(input_distribution) = algorithm_instance.input_distribution {
+                panel.insert("input".to_owned(), string_value(input_distribution.cli_name()));
+            }
+            if !algorithm_instance.custom_label.trim().is_empty() {
+                panel.insert("label".to_owned(), string_value(algorithm_instance.custom_label.trim()));
+            }
+            Value::Table(panel)
+        })
+        .collect();
+
+    let mut palette = Table::new();
+    palette.insert("custom".to_owned(), Value::Boolean(settings.use_custom_palette));
+    palette.insert("hue".to_owned(), float_value(settings.palette_base_hue_degrees));
+    palette.insert("saturation".to_owned(), float_value(settings.palette_saturation));
+    palette.insert("brightness".to_owned(), float_value(settings.palette_brightness));
+    palette.insert("gradient".to_owned(), float_value(settings.palette_gradient_strength));
+
+    let mut audio = Table::new();
+    audio.insert("enabled".to_owned(), Value::Boolean(settings.enable_audio));
+    audio.insert("volume".to_owned(), float_value(settings.audio_volume));
+    audio.insert("pitch".to_owned(), string_value(settings.sonification.pitch_source.cli_name()));
+    audio.insert("scale".to_owned(), string_value(settings.sonification.scale.cli_name()));
+    audio.insert("sound".to_owned(), string_value(settings.sonification.change_waveform.cli_name()));
+    audio.insert("compare_sound".to_owned(), string_value(settings.sonification.compare_waveform.cli_name()));
+    audio.insert("comparisons".to_owned(), Value::Boolean(settings.sonification.play_comparisons));
+    audio.insert("note_ms".to_owned(), float_value(settings.sonification.note_length_ms));
+    audio.insert("midi_tempo".to_owned(), Value::Integer(settings.midi_tempo_bpm as i64));
+    audio.insert("midi_steps_per_beat".to_owned(), Value::Integer(settings.midi_steps_per_beat as i64));
+
+    let mut files = Table::new();
+    files.insert("image".to_owned(), string_value(&settings.image_path));
+    files.insert("image_slices".to_owned(), string_value(settings.image_slice_mode.cli_name()));
+    files.insert("snapshot".to_owned(), string_value(&settings.snapshot_path));
+    files.insert("replay".to_owned(), string_value(&settings.replay_path));
+    files.insert("trace".to_owned(), string_value(&settings.trace_path));
+    files.insert("scenario".to_owned(), string_value(&settings.scenario_path));
+    files.insert("midi".to_owned(), string_value(&settings.midi_path));
+
+    let mut live = Table::new();
+    live.insert("source".to_owned(), string_value(settings.live_source_kind.cli_name()));
+    live.insert("target".to_owned(), string_value(&settings.live_target));
+    live.insert("steps_per_frame".to_owned(), Value::Integer(settings.live_steps_per_frame as i64));
+
+    let mut window = Table::new();
+    if let Some([window_width, window_height]) = layout.window_size {
+        window.insert("width".to_owned(), float_value(window_width));
+        window.insert("height".to_owned(), float_value(window_height));
+    }
+    window.insert("settings_width".to_owned(), float_value(layout.settings_panel_width));
+    window.insert("complexity_width".to_owned(), float_value(layout.complexity_panel_width));
+    window.insert("tournament_width".to_owned(), float_value(layout.tournament_panel_width));
+    window.insert("complexity_open".to_owned(), Value::Boolean(layout.complexity_panel_open));
+    window.insert("tournament_open".to_owned(), Value::Boolean(layout.tournament_panel_open));
+
+    let mut table = Table::new();
+    table.insert("version".to_owned(), Value::Integer(SETTINGS_VERSION));
+    table.insert("run".to_owned(), Value::Table(run));
+    table.insert("panels".to_owned(), Value::Array(panels));
+    table.insert("palette".to_owned(), Value::Table(palette));
+    table.insert("audio".to_owned(), Value::Table(audio));
+    table.insert("files".to_owned(), Value::Table(files));
+    table.insert("live".to_owned(), Value::Table(live));
+    table.insert("window".to_owned(), Value::Table(window));
+
+    toml::to_string(&table).unwrap_or_default()
+}
+
+// values of the wrong type are skipped and numbers outside the settings panel's ranges are
+// clamped to them, so a hand-edited file only loses the settings it got wrong
+fn apply_settings(table: &Table, settings: &mut SettingsPanelState, layout: &mut WindowLayout) {
+    if let Some(run) = section(table, "run") {
+        if let Some(number_of_values) = integer_in_range(run, "values", NUMBER_OF_VALUES_RANGE) {
+            settings.number_of_values = number_of_values;
+        }
+        if let Some(frames_per_second) = integer_in_range(run, "fps", FRAMES_PER_SECOND_RANGE) {
+            settings.frames_per_second = frames_per_second;
+        }
+        if let Some(input_distribution) = string(run, "input").and_then(InputDistribution::from_cli_name) {
+            settings.input_distribution = input_distribution;
+        }
+        if let Some(input_seed) = run.get("seed").and_then(Value::as_integer) {
+            settings.use_fixed_seed = true;
+            settings.fixed_seed = input_seed as u64;
+        }
+    }
+
+    if let Some(panels) = table.get("panels").and_then(Value::as_array) {
+        settings.algorithm_instances = panels
+            .iter()
+            .filter_map(Value::as_table)
+            .filter_map(algorithm_instance_from_table)
+            .collect();
+    }
+
+    if let Some(palette) = section(table, "palette") {
+        if let Some(use_custom_palette) = boolean(palette, "custom") {
+            settings.use_custom_palette = use_custom_palette;
+        }
+        if let Some(base_hue_degrees) = float_in_range(palette, "hue", Palette::HUE_RANGE_DEGREES) {
+            settings.palette_base_hue_degrees = base_hue_degrees;
+        }
+        if let Some(saturation) = float_in_range(palette, "saturation", Palette::SATURATION_RANGE) {
+            settings.palette_saturation = saturation;
+        }
+        if let Some(brightness) = float_in_range(palette, "brightness", Palette::BRIGHTNESS_RANGE) {
+            settings.palette_brightness = brightness;
+        }
+        let gradient_strength = float_in_range(palette, "gradient", Palette::GRADIENT_STRENGTH_RANGE);
+        if let Some(gradient_strength) = gradient_strength {
+            settings.palette_gradient_strength = gradient_strength;
+        }
+    }
+
+    if let Some(audio) = section(table, "audio") {
+        if let Some(enable_audio) = boolean(audio, "enabled") {
+            settings.enable_audio = enable_audio;
+        }
+        if let Some(audio_volume) = float_in(audio, "volume", 0.0, 1.0) {
+            settings.audio_volume = audio_volume;
+        }
+        if let Some(pitch_source) = string(audio, "pitch").and_then(PitchSource::from_cli_name) {
+            settings.sonification.pitch_source = pitch_source;
+        }
+        if let Some(scale) = string(audio, "scale").and_then(Scale::from_cli_name) {
+            settings.sonification.scale = scale;
+        }
+        if let Some(change_waveform) = string(audio, "sound").and_then(Waveform::from_cli_name) {
+            settings.sonification.change_waveform = change_waveform;
+        }
+        if let Some(compare_waveform) = string(audio, "compare_sound").and_then(Waveform::from_cli_name) {
+            settings.sonification.compare_waveform = compare_waveform;
+        }
+        if let Some(play_comparisons) = boolean(audio, "comparisons") {
+            settings.sonification.play_comparisons = play_comparisons;
+        }
+        let note_length_ms = float_in_range(audio, "note_ms", SonificationSettings::NOTE_LENGTH_RANGE_MS);
+        if let Some(note_length_ms) = note_length_ms {
+            settings.sonification.note_length_ms = note_length_ms;
+        }
+        if let Some(midi_tempo_bpm) = integer_in_range(audio, "midi_tempo", TEMPO_RANGE_BPM) {
+            settings.midi_tempo_bpm = midi_tempo_bpm;
+        }
+        let midi_steps_per_beat = integer_in_range(audio, "midi_steps_per_beat", STEPS_PER_BEAT_RANGE);
+        if let Some(midi_steps_per_beat) = midi_steps_per_beat {
+            settings.midi_steps_per_beat = midi_steps_per_beat;
+        }
+    }
+
+    if let Some(files) = section(table, "files") {
+        if let Some(image_path) = string(files, "image") {
+            settings.image_path = image_path.to_owned();
+        }
+        if let Some(slice_mode) = string(files, "image_slices").and_then(ImageSliceMode::from_cli_name) {
+            settings.image_slice_mode = slice_mode;
+        }
+        if let Some(snapshot_path) = string(files, "snapshot") {
+            settings.snapshot_path = snapshot_path.to_owned();
+        }
+        if let Some(replay_path) = string(files, "replay") {
+            settings.replay_path = replay_path.to_owned();
+        }
+        if let Some(trace_path) = string(files, "trace") {
+            settings.trace_path = trace_path.to_owned();
+        }
+        if let Some(scenario_path) = string(files, "scenario") {
+            settings.scenario_path = scenario_path.to_owned();
+        }
+        if let Some(midi_path) = string(files, "midi") {
+            settings.midi_path = midi_path.to_owned();
+        }
+    }
+
+    if let Some(live) = section(table, "live") {
+        if let Some(source_kind) = string(live, "source").and_then(LiveTraceSourceKind::from_cli_name) {
+            settings.live_source_kind = source_kind;
+        }
+        if let Some(live_target) = string(live, "target") {
+            settings.live_target = live_target.to_owned();
+        }
+        if let Some(steps_per_frame) = integer_in(live, "steps_per_frame", 1, 256) {
+            settings.live_steps_per_frame = steps_per_frame as usize;
+        }
+    }
+
+    if let Some(window) = section(table, "window") {
+        if
+            let (Some(window_width), Some(window_height)) = (
+                float_in(window, "width", 200.0, 16_384.0),
+                float_in(window, "height", 150.0, 16_384.0),
+            )
+        {
+            layout.window_size = Some([window_width, window_height]);
+        }
+        if let Some(settings_panel_width) = float_in(window, "settings_width", 100.0, 4096.0) {
+            layout.settings_panel_width = settings_panel_width;
+        }
+        if let Some(complexity_panel_width) = float_in(window, "complexity_width", 100.0, 4096.0) {
+            layout.complexity_panel_width = complexity_panel_width;
+        }
+        if let Some(tournament_panel_width) = float_in(window, "tournament_width", 100.0, 4096.0) {
+            layout.tournament_panel_width = tournament_panel_width;
+        }
+        if let Some(complexity_panel_open) = boolean(window, "complexity_open") {
+            layout.complexity_panel_open = complexity_panel_open;
+        }
+        if let Some(tournament_panel_open) = boolean(window, "tournament_open") {
+            layout.tournament_panel_open = tournament_panel_open;
+        }
+    }
+}
+
+// panels naming an algorithm this build does not have are dropped
+fn algorithm_instance_from_table(panel: &Table) -> Option<AlgorithmInstance> {
+    let algorithm_kind = string(panel, "algorithm").and_then(SortingAlgorithmKind::from_cli_name)?;
+    let mut algorithm_instance = AlgorithmInstance::new(algorithm_kind);
+
+    if let Some(partition_scheme) = string(panel, "partition").and_then(PartitionScheme::from_cli_name) {
+        algorithm_instance.partition_scheme = partition_scheme;
+    }
+    algorithm_instance.input_distribution = string(panel, "input").and_then(
+        InputDistribution::from_cli_name
+    );
+    if let Some(custom_label) = string(panel, "label") {
+        algorithm_instance.custom_label = custom_label.to_owned();
+    }
+    Some(algorithm_instance)
+}
+
+fn section<'a>(table: &'a Table, key: &str) -> Option<&'a Table> {
+    table.get(key).and_then(Value::as_table)
+}
+
+fn string<'a>(table: &'a Table, key: &str) -> Option<&'a str> {
+    table.get(key).and_then(Value::as_str)
+}
+
+fn boolean(table: &Table, key: &str) -> Option<bool> {
+    table.get(key).and_then(Value::as_bool)
+}
+
+fn integer_in(table: &Table, key: &str, minimum: i64, maximum: i64) -> Option<i64> {
+    table
+        .get(key)
+        .and_then(Value::as_integer)
+        .map(|value| value.clamp(minimum, maximum))
+}
+
+// the ranges are all unsigned, so a value that does not fit is below zero or above the range
+fn integer_in_range<T: TryFrom<i64> + PartialOrd + Copy>(
+    table: &Table,
+    key: &str,
+    range: RangeInclusive<T>
+) -> Option<T> {
+    let value = table.get(key).and_then(Value::as_integer)?;
+    let clamped_value = match T::try_from(value) {
+        Ok(converted_value) if converted_value < *range.start() => *range.start(),
+        Ok(converted_value) if converted_value > *range.end() => *range.end(),
+        Ok(converted_value) => converted_value,
+        Err(_) if value < 0 => *range.start(),
+        Err(_) => *range.end(),
+    };
+    Some(clamped_value)
+}
+
+fn float_in_range(table: &Table, key: &str, range: RangeInclusive<f32>) -> Option<f32> {
+    float_in(table, key, *range.start(), *range.end())
+}
+
+// integers are accepted too, since hand-written files often leave out the ".0"
+fn float_in(table: &Table, key: &str, minimum: f32, maximum: f32) -> Option<f32> {
+    let value = match table.get(key)? {
+        Value::Float(value) => *value as f32,
+        Value::Integer(value) => *value as f32,
+        _ => {
+            return None;
+        }
+    };
+    value.is_finite().then(|| value.clamp(minimum, maximum))
+}
+
+fn string_value(value: &str) -> Value {
+    Value::String(value.to_owned())
+}
+
+// goes through the shortest f32 text so 0.3 is written as 0.3, not 0.30000001192092896
+fn float_value(value: f32) -> Value {
+    Value::Float(value.to_string().parse().unwrap_or(value as f64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the settings a file with this text loads, and the schema version it was written as
+    fn load_text(settings_text: &str) -> (SettingsPanelState, i64) {
+        let (table, file_version) = parse_settings_text(settings_text).unwrap();
+        let mut settings = SettingsPanelState::default();
+        apply_settings(&table, &mut settings, &mut WindowLayout::default());
+        (settings, file_version)
+    }
+
+    #[test]
+    fn older_file_loads_with_out_of_range_values_clamped() {
+        // version 0 predates the schema and is read as the first version
+        let (settings, file_version) = load_text(
+            "version = 0\n\n[run]\nvalues = 100000\nfps = 1\n\n\
+             [audio]\nmidi_tempo = 9000\nnote_ms = 2.5\nmidi_steps_per_beat = -3\nvolume = 0.4\n"
+        );
+
+        assert_eq!(file_version, 1);
+        assert_eq!(settings.midi_tempo_bpm, *TEMPO_RANGE_BPM.end());
+        assert_eq!(
+            settings.sonification.note_length_ms,
+            *SonificationSettings::NOTE_LENGTH_RANGE_MS.start()
+        );
+        assert_eq!(settings.midi_steps_per_beat, *STEPS_PER_BEAT_RANGE.start());
+        assert_eq!(settings.number_of_values, *NUMBER_OF_VALUES_RANGE.end());
+        assert_eq!(settings.frames_per_second, *FRAMES_PER_SECOND_RANGE.start());
+        assert_eq!(settings.audio_volume, 0.4);
+    }
+
+    #[test]
+    fn values_of_the_wrong_type_keep_their_defaults() {
+        let (settings, _) = load_text(
+            "version = 1\n\n[audio]\nmidi_tempo = \"fast\"\nnote_ms = nan\nmidi_steps_per_beat = 8\n"
+        );
+        let default_settings = SettingsPanelState::default();
+
+        assert_eq!(settings.midi_tempo_bpm, default_settings.midi_tempo_bpm);
+        assert_eq!(settings.sonification.note_length_ms, default_settings.sonification.note_length_ms);
+        assert_eq!(settings.midi_steps_per_beat, 8);
+    }
+}
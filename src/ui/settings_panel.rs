@@ -1,25 +1,29 @@
 use eframe::egui;
 
-use crate::engine::EngineState;
+use crate::engine::{ EngineState, FRAMES_PER_SECOND_RANGE, NUMBER_OF_VALUES_RANGE };
+use crate::export::midi::{ STEPS_PER_BEAT_RANGE, TEMPO_RANGE_BPM };
+use crate::image_source::{ ImageSliceMode, SourceImage };
+use crate::input::InputDistribution;
+use crate::live_trace::LiveTraceSourceKind;
+use crate::palette::Palette;
+use crate::sonification::{ PitchSource, Scale, SonificationSettings, Waveform };
+use crate::ui::playlist_player::PlaylistPlayer;
+use crate::sorting_algorithms::instance::AlgorithmInstance;
+use crate::sorting_algorithms::quick_sort::PartitionScheme;
+use crate::sorting_algorithms::registry::{ AlgorithmCategory, ALGORITHMS };
 use crate::sorting_algorithms::SortingAlgorithmKind;
 
 #[derive(Clone)]
 pub struct SettingsPanelState {
     pub number_of_values: usize,
     pub frames_per_second: u32,
+    pub input_distribution: InputDistribution,
+    // same input on every start when set
+    pub use_fixed_seed: bool,
+    pub fixed_seed: u64,
 
-    pub use_bubble_sort: bool,
-    pub use_quick_sort: bool,
-    use_insertion_sort: bool,
-    use_selection_sort: bool,
-    use_merge_sort: bool,
-    use_heap_sort: bool,
-    use_shell_sort: bool,
-    use_intro_sort: bool,
-    use_tim_sort: bool,
-    use_cocktail_sort: bool,
-    use_comb_sort: bool,
-    use_gnome_sort: bool,
+    // one panel each, in grid order; an algorithm may appear more than once
+    pub algorithm_instances: Vec<AlgorithmInstance>,
 
     pub use_custom_palette: bool,
     pub palette_base_hue_degrees: f32,
@@ -29,12 +33,55 @@ pub struct SettingsPanelState {
 
     pub enable_audio: bool,
     pub audio_volume: f32,
+    pub sonification: SonificationSettings,
+    pub midi_path: String,
+    pub midi_tempo_bpm: u32,
+    pub midi_steps_per_beat: u32,
+    pub midi_status: Option<String>,
+
+    pub use_image_input: bool,
+    pub image_path: String,
+    pub image_slice_mode: ImageSliceMode,
+    pub image_status: Option<String>,
+
+    pub snapshot_path: String,
+    pub snapshot_status: Option<String>,
+
+    pub replay_path: String,
+    pub trace_path: String,
+    pub replay_status: Option<String>,
+    // violations found by the last self-check, one line each
+    pub self_check_status: Option<String>,
+    pub self_check_violations: Vec<String>,
+
+    pub live_source_kind: LiveTraceSourceKind,
+    pub live_target: String,
+    pub live_steps_per_frame: usize,
+    pub live_status: Option<String>,
+
+    pub scenario_path: String,
+    pub scenario_status: Option<String>,
+
+    // why settings.toml is not being saved, if it is not
+    pub settings_file_status: Option<String>,
 }
 
 pub enum SettingsPanelAction {
     None,
-    StartRequested(Vec<SortingAlgorithmKind>),
+    StartRequested(Vec<AlgorithmInstance>),
     StopRequested,
+    LoadImageRequested,
+    SaveSnapshotRequested,
+    SaveRunRequested,
+    ExportMidiRequested,
+    LoadRunRequested,
+    ImportTraceRequested,
+    SelfCheckRequested,
+    ConnectLiveRequested,
+    DisconnectLiveRequested,
+    OpenScenariosRequested,
+    PlayScenarioRequested(usize),
+    CloseScenariosRequested,
 }
 
 impl Default for SettingsPanelState {
@@ -42,19 +89,14 @@ impl Default for SettingsPanelState {
         Self {
             number_of_values: 128,
             frames_per_second: 60,
+            input_distribution: InputDistribution::Random,
+            use_fixed_seed: false,
+            fixed_seed: 0,
 
-            use_bubble_sort: true,
-            use_quick_sort: true,
-            use_insertion_sort: false,
-            use_selection_sort: false,
-            use_merge_sort: false,
-            use_heap_sort: false,
-            use_shell_sort: false,
-            use_intro_sort: false,
-            use_tim_sort: false,
-            use_cocktail_sort: false,
-            use_comb_sort: false,
-            use_gnome_sort: false,
+            algorithm_instances: vec![
+                AlgorithmInstance::new(SortingAlgorithmKind::BubbleSort),
+                AlgorithmInstance::new(SortingAlgorithmKind::QuickSort)
+            ],
 
             use_custom_palette: true,
             palette_base_hue_degrees: 210.0, // ~teal
@@ -64,111 +106,308 @@ impl Default for SettingsPanelState {
 
             enable_audio: true,
             audio_volume: 0.3,
+            sonification: SonificationSettings::default(),
+            midi_path: "run.mid".to_owned(),
+            midi_tempo_bpm: 120,
+            midi_steps_per_beat: 4,
+            midi_status: None,
+
+            use_image_input: false,
+            image_path: String::new(),
+            image_slice_mode: ImageSliceMode::Columns,
+            image_status: None,
+
+            snapshot_path: "sortvis-snapshot.svg".to_owned(),
+            snapshot_status: None,
+
+            replay_path: "run.sortvis".to_owned(),
+            trace_path: String::new(),
+            replay_status: None,
+            self_check_status: None,
+            self_check_violations: Vec::new(),
+
+            live_source_kind: LiveTraceSourceKind::Command,
+            live_target: String::new(),
+            live_steps_per_frame: 4,
+            live_status: None,
+
+            scenario_path: "scenarios.toml".to_owned(),
+            scenario_status: None,
+
+            settings_file_status: None,
         }
     }
 }
 
 impl SettingsPanelState {
-    pub fn show(&mut self, ui: &mut egui::Ui, engine_state: &EngineState) -> SettingsPanelAction {
+    pub fn input_seed(&self) -> Option<u64> {
+        self.use_fixed_seed.then_some(self.fixed_seed)
+    }
+
+    pub fn palette(&self, dark_mode: bool) -> Palette {
+        Palette {
+            use_custom_palette: self.use_custom_palette,
+            base_hue_degrees: self.palette_base_hue_degrees,
+            saturation: self.palette_saturation,
+            brightness: self.palette_brightness,
+            gradient_strength: self.palette_gradient_strength,
+            dark_mode,
+        }
+    }
+
+    // instances to run, in the order the grid shows them
+    pub fn selected_algorithms(&self) -> Vec<AlgorithmInstance> {
+        self.algorithm_instances.clone()
+    }
+
+    // each selected algorithm once, in registry order, for panels that ignore parameters
+    pub fn selected_algorithm_kinds(&self) -> Vec<SortingAlgorithmKind> {
+        SortingAlgorithmKind::ALL
+            .into_iter()
+            .filter(|algorithm_kind| self.is_algorithm_selected(*algorithm_kind))
+            .collect()
+    }
+
+    fn is_algorithm_selected(&self, algorithm_kind: SortingAlgorithmKind) -> bool {
+        self.algorithm_instances
+            .iter()
+            .any(|algorithm_instance| algorithm_instance.kind == algorithm_kind)
+    }
+
+    // checking adds one default instance, unchecking removes every instance of the algorithm
+    fn set_algorithm_selected(&mut self, algorithm_kind: SortingAlgorithmKind, is_selected: bool) {
+        if is_selected {
+            if !self.is_algorithm_selected(algorithm_kind) {
+                self.algorithm_instances.push(AlgorithmInstance::new(algorithm_kind));
+            }
+        } else {
+            self.algorithm_instances.retain(|algorithm_instance| algorithm_instance.kind != algorithm_kind);
+        }
+    }
+
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        engine_state: &EngineState,
+        source_image: Option<&SourceImage>,
+        playlist_player: Option<&mut PlaylistPlayer>
+    ) -> SettingsPanelAction {
+        let mut action = SettingsPanelAction::None;
+
         ui.heading("Settings");
 
+        if let Some(settings_file_status) = &self.settings_file_status {
+            ui.label(egui::RichText::new(settings_file_status).small().italics());
+        }
+
+        let image_slice_count = source_image
+            .filter(|_| self.use_image_input)
+            .map(|image| image.slice_count(self.image_slice_mode));
+
+        if let Some(image_slice_count) = image_slice_count {
+            ui.label(format!("Values per algorithm: {} (image slices)", image_slice_count));
+        } else {
+            ui.add(
+                egui::Slider
+                    ::new(&mut self.number_of_values, NUMBER_OF_VALUES_RANGE)
+                    .text("Values per algorithm")
+            );
+        }
+
         ui.add(
-            egui::Slider::new(&mut self.number_of_values, 32..=2500).text("Values per algorithm")
+            egui::Slider
+                ::new(&mut self.frames_per_second, FRAMES_PER_SECOND_RANGE)
+                .text("Frames per second")
         );
 
-        ui.add(egui::Slider::new(&mut self.frames_per_second, 10..=120).text("Frames per second"));
+        ui.add_enabled_ui(image_slice_count.is_none(), |ui| {
+            egui::ComboBox
+                ::from_label("Input")
+                .selected_text(self.input_distribution.display_name())
+                .show_ui(ui, |combo_ui| {
+                    for distribution in InputDistribution::ALL {
+                        combo_ui.selectable_value(
+                            &mut self.input_distribution,
+                            distribution,
+                            distribution.display_name()
+                        );
+                    }
+                });
+        });
 
-        ui.separator();
-        ui.label("Algorithms to visualize:");
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.use_fixed_seed, "Fixed seed").on_hover_text(
+                "Start every run from the same input instead of a new one"
+            );
+            ui.add_enabled(self.use_fixed_seed, egui::DragValue::new(&mut self.fixed_seed));
+        });
 
         // --------------------------------------------------------------------
-        // Group 1: Basic O(n²) comparison sorts
+        // image input
         // --------------------------------------------------------------------
-        ui.collapsing("Basic O(n²) comparison sorts", |ui| {
+        ui.collapsing("Image sorting", |ui| {
+            ui.checkbox(&mut self.use_image_input, "Sort an image").on_hover_text(
+                "Each slice of the picture is one element, keyed by its original position."
+            );
+
             ui.horizontal(|ui| {
-                ui.label(
-                    egui::RichText
-                        ::new("Simple, educational sorts – good for seeing the fundamentals.")
-                        .small()
-                        .italics()
+                ui.label("File:");
+                ui.text_edit_singleline(&mut self.image_path).on_hover_text(
+                    "Path to a PNG or JPEG image"
                 );
-
-                if ui.button("Select all").clicked() {
-                    self.use_bubble_sort = true;
-                    self.use_insertion_sort = true;
-                    self.use_selection_sort = true;
-                    self.use_shell_sort = true;
-                    self.use_cocktail_sort = true;
-                    self.use_comb_sort = true;
-                    self.use_gnome_sort = true;
-                }
             });
 
-            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("Load image").clicked() {
+                    action = SettingsPanelAction::LoadImageRequested;
+                }
 
-            ui.horizontal_wrapped(|ui| {
-                ui.checkbox(&mut self.use_bubble_sort, "Bubble Sort");
-                ui.checkbox(&mut self.use_insertion_sort, "Insertion Sort");
-                ui.checkbox(&mut self.use_selection_sort, "Selection Sort");
-                ui.checkbox(&mut self.use_shell_sort, "Shell Sort");
-                ui.checkbox(&mut self.use_cocktail_sort, "Cocktail Sort");
-                ui.checkbox(&mut self.use_comb_sort, "Comb Sort");
-                ui.checkbox(&mut self.use_gnome_sort, "Gnome Sort");
+                egui::ComboBox
+                    ::from_id_salt("image_slice_mode")
+                    .selected_text(self.image_slice_mode.display_name())
+                    .show_ui(ui, |combo_ui| {
+                        for slice_mode in ImageSliceMode::ALL {
+                            combo_ui.selectable_value(
+                                &mut self.image_slice_mode,
+                                slice_mode,
+                                slice_mode.display_name()
+                            );
+                        }
+                    });
             });
+
+            if let Some(image_status) = &self.image_status {
+                ui.label(egui::RichText::new(image_status).small().italics());
+            }
         });
 
+        ui.separator();
+        ui.label("Algorithms to visualize:");
+
         // --------------------------------------------------------------------
-        // Group 2: Classic O(n log n) sorts
+        // one group per registry category
         // --------------------------------------------------------------------
-        ui.collapsing("Classic O(n log n) sorts", |ui| {
-            ui.horizontal(|ui| {
-                ui.label(
-                    egui::RichText
-                        ::new("Divide-and-conquer algorithms used in textbooks and interviews.")
-                        .small()
-                        .italics()
-                );
-
-                if ui.button("Select all").clicked() {
-                    self.use_quick_sort = true;
-                    self.use_merge_sort = true;
-                    self.use_heap_sort = true;
-                    self.use_intro_sort = true;
-                }
-            });
-
-            ui.separator();
-
-            ui.horizontal_wrapped(|ui| {
-                ui.checkbox(&mut self.use_quick_sort, "Quick Sort");
-                ui.checkbox(&mut self.use_merge_sort, "Merge Sort");
-                ui.checkbox(&mut self.use_heap_sort, "Heap Sort");
-                ui.checkbox(&mut self.use_intro_sort, "Intro Sort");
+        for category in AlgorithmCategory::ALL {
+            ui.collapsing(category.display_name(), |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(category.description()).small().italics());
+
+                    if ui.button("Select all").clicked() {
+                        for algorithm_info in &ALGORITHMS {
+                            if algorithm_info.category == category {
+                                self.set_algorithm_selected(algorithm_info.kind, true);
+                            }
+                        }
+                    }
+                });
+
+                ui.separator();
+
+                ui.horizontal_wrapped(|ui| {
+                    for algorithm_info in &ALGORITHMS {
+                        if algorithm_info.category != category {
+                            continue;
+                        }
+
+                        let mut is_selected = self.is_algorithm_selected(algorithm_info.kind);
+                        let checkbox_response = ui
+                            .checkbox(&mut is_selected, algorithm_info.display_name)
+                            .on_hover_text(algorithm_info.summary());
+                        if checkbox_response.changed() {
+                            self.set_algorithm_selected(algorithm_info.kind, is_selected);
+                        }
+                    }
+                });
             });
-        });
+        }
 
         // --------------------------------------------------------------------
-        // Group 3: Hybrid / practical sorts
+        // instances: one panel each, with its own label and parameters
         // --------------------------------------------------------------------
-        ui.collapsing("Hybrid / practical sorts", |ui| {
-            ui.horizontal(|ui| {
-                ui.label(
-                    egui::RichText
-                        ::new("More realistic, production-style algorithms.")
-                        .small()
-                        .italics()
-                );
+        ui.collapsing(format!("Panels ({})", self.algorithm_instances.len()), |ui| {
+            ui.label(
+                egui::RichText
+                    ::new("Duplicate a panel to run the same algorithm with other parameters or input.")
+                    .small()
+                    .italics()
+            );
 
-                if ui.button("Select all").clicked() {
-                    self.use_tim_sort = true;
-                }
-            });
+            let mut duplicated_index = None;
+            let mut removed_index = None;
+
+            for (instance_index, algorithm_instance) in self.algorithm_instances.iter_mut().enumerate() {
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(algorithm_instance.kind.display_name()).strong());
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.small_button("✕").on_hover_text("Remove this panel").clicked() {
+                            removed_index = Some(instance_index);
+                        }
+                        if ui.small_button("Duplicate").clicked() {
+                            duplicated_index = Some(instance_index);
+                        }
+                    });
+                });
+
+                let generated_label = algorithm_instance.generated_label();
+                ui.horizontal(|ui| {
+                    ui.label("Label:");
+                    ui.add(
+                        egui::TextEdit
+                            ::singleline(&mut algorithm_instance.custom_label)
+                            .hint_text(generated_label)
+                    );
+                });
+
+                ui.horizontal_wrapped(|ui| {
+                    if algorithm_instance.has_partition_scheme() {
+                        egui::ComboBox
+                            ::from_id_salt(("instance_partition", instance_index))
+                            .selected_text(algorithm_instance.partition_scheme.display_name())
+                            .show_ui(ui, |combo_ui| {
+                                for partition_scheme in PartitionScheme::ALL {
+                                    combo_ui.selectable_value(
+                                        &mut algorithm_instance.partition_scheme,
+                                        partition_scheme,
+                                        partition_scheme.display_name()
+                                    );
+                                }
+                            });
+                    }
 
-            ui.separator();
+                    egui::ComboBox
+                        ::from_id_salt(("instance_input", instance_index))
+                        .selected_text(
+                            algorithm_instance.input_distribution
+                                .map_or("Run input", |distribution| distribution.display_name())
+                        )
+                        .show_ui(ui, |combo_ui| {
+                            combo_ui.selectable_value(
+                                &mut algorithm_instance.input_distribution,
+                                None,
+                                "Run input"
+                            );
+                            for distribution in InputDistribution::ALL {
+                                combo_ui.selectable_value(
+                                    &mut algorithm_instance.input_distribution,
+                                    Some(distribution),
+                                    distribution.display_name()
+                                );
+                            }
+                        })
+                        .response.on_hover_text("Input shape for this panel only; image runs ignore it.");
+                });
+            }
 
-            ui.horizontal_wrapped(|ui| {
-                ui.checkbox(&mut self.use_tim_sort, "Tim Sort");
-            });
+            if let Some(instance_index) = duplicated_index {
+                let duplicate = self.algorithm_instances[instance_index].clone();
+                self.algorithm_instances.insert(instance_index + 1, duplicate);
+            }
+            if let Some(instance_index) = removed_index {
+                self.algorithm_instances.remove(instance_index);
+            }
         });
 
         ui.separator();
@@ -184,21 +423,25 @@ impl SettingsPanelState {
             ui.add_enabled_ui(self.use_custom_palette, |ui| {
                 ui.add(
                     egui::Slider
-                        ::new(&mut self.palette_base_hue_degrees, 0.0..=360.0)
+                        ::new(&mut self.palette_base_hue_degrees, Palette::HUE_RANGE_DEGREES)
                         .text("Base hue (degrees)")
                 );
 
                 ui.add(
-                    egui::Slider::new(&mut self.palette_saturation, 0.2..=1.0).text("Saturation")
+                    egui::Slider
+                        ::new(&mut self.palette_saturation, Palette::SATURATION_RANGE)
+                        .text("Saturation")
                 );
 
                 ui.add(
-                    egui::Slider::new(&mut self.palette_brightness, 0.3..=1.0).text("Brightness")
+                    egui::Slider
+                        ::new(&mut self.palette_brightness, Palette::BRIGHTNESS_RANGE)
+                        .text("Brightness")
                 );
 
                 ui.add(
                     egui::Slider
-                        ::new(&mut self.palette_gradient_strength, 0.0..=0.4)
+                        ::new(&mut self.palette_gradient_strength, Palette::GRADIENT_STRENGTH_RANGE)
                         .text("Gradient strength")
                 );
             });
@@ -210,63 +453,287 @@ impl SettingsPanelState {
         // audio settings
         // --------------------------------------------------------------------
         ui.collapsing("Audio", |ui| {
-            ui.checkbox(&mut self.enable_audio, "Enable swap/write sounds").on_hover_text(
-                "Play a short tone whenever the array changes between frames."
+            ui.checkbox(&mut self.enable_audio, "Enable sounds").on_hover_text(
+                "Play a short tone for the compares, swaps and writes of every step."
             );
 
             ui.add_enabled(
                 self.enable_audio,
                 egui::Slider::new(&mut self.audio_volume, 0.0..=1.0).text("Master Volume")
             );
-        });
 
-        ui.separator();
+            ui.add_enabled_ui(self.enable_audio, |ui| {
+                let sonification = &mut self.sonification;
 
-        let mut action = SettingsPanelAction::None;
+                ui.horizontal(|ui| {
+                    ui.label("Pitch from:");
+                    for pitch_source in PitchSource::ALL {
+                        ui.radio_value(&mut sonification.pitch_source, pitch_source, pitch_source.display_name());
+                    }
+                }).response.on_hover_text(
+                    "Position: each place in the array has its own note. Value: notes rise as the array gets sorted."
+                );
 
-        match engine_state {
-            EngineState::Idle => {
-                if ui.button("Start").clicked() {
-                    let mut selected_algorithms: Vec<SortingAlgorithmKind> = Vec::new();
+                egui::ComboBox
+                    ::from_label("Scale")
+                    .selected_text(sonification.scale.display_name())
+                    .show_ui(ui, |combo_ui| {
+                        for scale in Scale::ALL {
+                            combo_ui.selectable_value(&mut sonification.scale, scale, scale.display_name());
+                        }
+                    });
 
-                    if self.use_bubble_sort {
-                        selected_algorithms.push(SortingAlgorithmKind::BubbleSort);
-                    }
-                    if self.use_quick_sort {
-                        selected_algorithms.push(SortingAlgorithmKind::QuickSort);
-                    }
-                    if self.use_insertion_sort {
-                        selected_algorithms.push(SortingAlgorithmKind::InsertionSort);
-                    }
-                    if self.use_selection_sort {
-                        selected_algorithms.push(SortingAlgorithmKind::SelectionSort);
-                    }
-                    if self.use_merge_sort {
-                        selected_algorithms.push(SortingAlgorithmKind::MergeSort);
-                    }
-                    if self.use_heap_sort {
-                        selected_algorithms.push(SortingAlgorithmKind::HeapSort);
-                    }
-                    if self.use_shell_sort {
-                        selected_algorithms.push(SortingAlgorithmKind::ShellSort);
-                    }
-                    if self.use_intro_sort {
-                        selected_algorithms.push(SortingAlgorithmKind::IntroSort);
-                    }
-                    if self.use_comb_sort {
-                        selected_algorithms.push(SortingAlgorithmKind::CombSort);
+                waveform_combo_box(ui, "Swap/write sound", &mut sonification.change_waveform);
+
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut sonification.play_comparisons, "");
+                    ui.add_enabled_ui(sonification.play_comparisons, |ui| {
+                        waveform_combo_box(ui, "Compare sound", &mut sonification.compare_waveform);
+                    });
+                });
+
+                ui.add(
+                    egui::Slider
+                        ::new(&mut sonification.note_length_ms, SonificationSettings::NOTE_LENGTH_RANGE_MS)
+                        .text("Note length (ms)")
+                        .integer()
+                );
+            });
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.label("MIDI file:");
+                ui.text_edit_singleline(&mut self.midi_path).on_hover_text(
+                    "Standard MIDI file with the notes of the current run, one track per panel"
+                );
+            });
+
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::DragValue
+                        ::new(&mut self.midi_tempo_bpm)
+                        .range(TEMPO_RANGE_BPM)
+                        .suffix(" bpm")
+                );
+                ui.add(
+                    egui::DragValue
+                        ::new(&mut self.midi_steps_per_beat)
+                        .range(STEPS_PER_BEAT_RANGE)
+                        .suffix(" steps/beat")
+                );
+            });
+
+            if
+                ui
+                    .button("Export MIDI")
+                    .on_hover_text("Pitch, scale and sounds follow the settings above; velocity follows bar height.")
+                    .clicked()
+            {
+                action = SettingsPanelAction::ExportMidiRequested;
+            }
+
+            if let Some(midi_status) = &self.midi_status {
+                ui.label(egui::RichText::new(midi_status).small().italics());
+            }
+        });
+
+        // --------------------------------------------------------------------
+        // scenario playlist
+        // --------------------------------------------------------------------
+        ui.collapsing("Scenarios", |ui| {
+            ui.horizontal(|ui| {
+                ui.label("File:");
+                ui.text_edit_singleline(&mut self.scenario_path).on_hover_text(
+                    "A TOML file of [[scenario]] demos, played in order"
+                );
+            });
+
+            ui.horizontal(|ui| {
+                if ui.button("Open").clicked() {
+                    action = SettingsPanelAction::OpenScenariosRequested;
+                }
+                if playlist_player.is_some() && ui.button("Close").clicked() {
+                    action = SettingsPanelAction::CloseScenariosRequested;
+                }
+            });
+
+            if let Some(playlist_player) = playlist_player {
+                ui.separator();
+
+                for (scenario_index, scenario) in playlist_player.playlist.scenarios.iter().enumerate() {
+                    let scenario_title = if scenario.title.is_empty() {
+                        format!("{}. Scenario {}", scenario_index + 1, scenario_index + 1)
+                    } else {
+                        format!("{}. {}", scenario_index + 1, scenario.title)
+                    };
+                    let is_current = scenario_index == playlist_player.current_index;
+                    if ui.selectable_label(is_current, scenario_title).clicked() {
+                        action = SettingsPanelAction::PlayScenarioRequested(scenario_index);
                     }
-                    if self.use_tim_sort {
-                        selected_algorithms.push(SortingAlgorithmKind::TimSort);
+                }
+
+                ui.horizontal(|ui| {
+                    let previous_index = playlist_player.previous_index();
+                    if
+                        ui
+                            .add_enabled(previous_index.is_some(), egui::Button::new("◀ Previous"))
+                            .on_hover_text("Left arrow or Page Up")
+                            .clicked()
+                    {
+                        action = SettingsPanelAction::PlayScenarioRequested(previous_index.unwrap_or_default());
                     }
-                    if self.use_cocktail_sort {
-                        selected_algorithms.push(SortingAlgorithmKind::CocktailSort);
+
+                    let next_index = playlist_player.next_index();
+                    if
+                        ui
+                            .add_enabled(next_index.is_some(), egui::Button::new("Next ▶"))
+                            .on_hover_text("Right arrow or Page Down")
+                            .clicked()
+                    {
+                        action = SettingsPanelAction::PlayScenarioRequested(next_index.unwrap_or_default());
                     }
-                    if self.use_gnome_sort {
-                        selected_algorithms.push(SortingAlgorithmKind::GnomeSort);
+                });
+
+                ui.checkbox(&mut playlist_player.auto_advance, "Auto-advance").on_hover_text(
+                    "Move on once every panel has finished and the scenario's hold time has passed."
+                );
+            }
+
+            if let Some(scenario_status) = &self.scenario_status {
+                ui.label(egui::RichText::new(scenario_status).small().italics());
+            }
+        });
+
+        // --------------------------------------------------------------------
+        // vector snapshot
+        // --------------------------------------------------------------------
+        ui.collapsing("Snapshot", |ui| {
+            ui.horizontal(|ui| {
+                ui.label("File:");
+                ui.text_edit_singleline(&mut self.snapshot_path).on_hover_text(
+                    "SVG file for the grid; single panels add the algorithm name"
+                );
+            });
+
+            if ui.button("Save grid as SVG").clicked() {
+                action = SettingsPanelAction::SaveSnapshotRequested;
+            }
+
+            if let Some(snapshot_status) = &self.snapshot_status {
+                ui.label(egui::RichText::new(snapshot_status).small().italics());
+            }
+        });
+
+        // --------------------------------------------------------------------
+        // saved runs
+        // --------------------------------------------------------------------
+        ui.collapsing("Replay file", |ui| {
+            ui.horizontal(|ui| {
+                ui.label("File:");
+                ui.text_edit_singleline(&mut self.replay_path).on_hover_text(
+                    "A .sortvis file holding the input, every operation and the stats of a run"
+                );
+            });
+
+            ui.horizontal(|ui| {
+                if ui.button("Save run").clicked() {
+                    action = SettingsPanelAction::SaveRunRequested;
+                }
+                if ui.button("Load run").clicked() {
+                    action = SettingsPanelAction::LoadRunRequested;
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Trace:");
+                ui.text_edit_singleline(&mut self.trace_path).on_hover_text(
+                    "A text trace (init, compare, swap, write, marker) written by another program"
+                );
+            });
+
+            if ui.button("Import trace").clicked() {
+                action = SettingsPanelAction::ImportTraceRequested;
+            }
+
+            if let Some(replay_status) = &self.replay_status {
+                ui.label(egui::RichText::new(replay_status).small().italics());
+            }
+        });
+
+        // --------------------------------------------------------------------
+        // recorder self-check
+        // --------------------------------------------------------------------
+        ui.collapsing("Self-check", |ui| {
+            if
+                ui
+                    .button("Check recorded run")
+                    .on_hover_text(
+                        "Checks every step of every panel against its operation log and its input"
+                    )
+                    .clicked()
+            {
+                action = SettingsPanelAction::SelfCheckRequested;
+            }
+
+            if let Some(self_check_status) = &self.self_check_status {
+                ui.label(egui::RichText::new(self_check_status).small().italics());
+            }
+            for violation in &self.self_check_violations {
+                ui.label(egui::RichText::new(violation).small().monospace());
+            }
+        });
+
+        // --------------------------------------------------------------------
+        // live trace from another program
+        // --------------------------------------------------------------------
+        ui.collapsing("Live trace", |ui| {
+            egui::ComboBox
+                ::from_id_salt("live_source_kind")
+                .selected_text(self.live_source_kind.display_name())
+                .show_ui(ui, |combo_ui| {
+                    for source_kind in LiveTraceSourceKind::ALL {
+                        combo_ui.selectable_value(
+                            &mut self.live_source_kind,
+                            source_kind,
+                            source_kind.display_name()
+                        );
                     }
+                });
+
+            let target_hint = match self.live_source_kind {
+                LiveTraceSourceKind::Command => "Command whose output is a trace, e.g. python3 my_sort.py",
+                LiveTraceSourceKind::UnixSocket => "Socket path to listen on; connect and write a trace",
+            };
+            ui.text_edit_singleline(&mut self.live_target).on_hover_text(target_hint);
+
+            ui.add(
+                egui::Slider
+                    ::new(&mut self.live_steps_per_frame, 1..=256)
+                    .logarithmic(true)
+                    .text("Steps per frame")
+            ).on_hover_text("The producer is slowed down to this pace once the buffer is full.");
 
-                    action = SettingsPanelAction::StartRequested(selected_algorithms);
+            ui.horizontal(|ui| {
+                if ui.button("Connect").clicked() {
+                    action = SettingsPanelAction::ConnectLiveRequested;
+                }
+                if ui.button("Disconnect").clicked() {
+                    action = SettingsPanelAction::DisconnectLiveRequested;
+                }
+            });
+
+            if let Some(live_status) = &self.live_status {
+                ui.label(egui::RichText::new(live_status).small().italics());
+            }
+        });
+
+        ui.separator();
+
+        match engine_state {
+            EngineState::Idle => {
+                if ui.button("Start").clicked() {
+                    action = SettingsPanelAction::StartRequested(self.selected_algorithms());
                 }
             }
             EngineState::Preparing { algorithms_completed, algorithms_total } => {
@@ -290,3 +757,14 @@ impl SettingsPanelState {
         action
     }
 }
+
+fn waveform_combo_box(ui: &mut egui::Ui, label: &str, waveform: &mut Waveform) {
+    egui::ComboBox
+        ::from_label(label)
+        .selected_text(waveform.display_name())
+        .show_ui(ui, |combo_ui| {
+            for candidate in Waveform::ALL {
+                combo_ui.selectable_value(waveform, candidate, candidate.display_name());
+            }
+        });
+}
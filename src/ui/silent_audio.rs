@@ -0,0 +1,30 @@
+use std::collections::HashMap;
+use std::sync::mpsc::Sender;
+
+use crate::sonification::{ NoteEvent, PanelVoice, SonificationSettings };
+
+// stands in for the audio module when sortvis is built without the audio feature
+#[derive(Default)]
+pub struct AudioOutput;
+
+impl AudioOutput {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn note_sender(&self) -> Option<Sender<Vec<NoteEvent>>> {
+        None
+    }
+
+    pub fn play_notes(&self, _note_events: Vec<NoteEvent>) {}
+
+    pub fn set_volume(&self, _volume: f32) {}
+
+    pub fn set_enabled(&self, _is_enabled: bool) {}
+
+    pub fn set_sonification_settings(&self, _sonification_settings: &SonificationSettings) {}
+
+    pub fn set_panel_voices(&self, _panel_voices: HashMap<usize, PanelVoice>) {}
+
+    pub fn clear(&self) {}
+}
@@ -0,0 +1,277 @@
+use std::path::Path;
+use std::thread::JoinHandle;
+
+use eframe::egui;
+
+use crate::bench::{ spawn_bench_thread, BenchOutputFormat };
+use crate::input::InputDistribution;
+use crate::sorting_algorithms::SortingAlgorithmKind;
+use crate::tournament::{
+    format_tournament,
+    run_tournament,
+    MetricSummary,
+    TournamentMetric,
+    TournamentOptions,
+    TournamentResult,
+};
+
+const BOX_ROW_HEIGHT: f32 = 22.0;
+const BOX_PLOT_LABEL_WIDTH: f32 = 120.0;
+
+// tournament controls, the last result and the tournament still running, if any
+pub struct TournamentPanelState {
+    pub is_open: bool,
+    number_of_values: usize,
+    seed_count: usize,
+    metric: TournamentMetric,
+    export_path: String,
+    export_status: Option<String>,
+    result: Option<TournamentResult>,
+    pending_tournament: Option<JoinHandle<TournamentResult>>,
+}
+
+impl Default for TournamentPanelState {
+    fn default() -> Self {
+        let tournament_options = TournamentOptions::default();
+        Self {
+            is_open: false,
+            number_of_values: tournament_options.number_of_values,
+            seed_count: tournament_options.seed_count,
+            metric: TournamentMetric::Comparisons,
+            export_path: "sortvis-tournament.csv".to_owned(),
+            export_status: None,
+            result: None,
+            pending_tournament: None,
+        }
+    }
+}
+
+impl TournamentPanelState {
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        selected_algorithms: Vec<SortingAlgorithmKind>,
+        input_distribution: InputDistribution
+    ) {
+        self.collect_finished_tournament();
+
+        ui.heading("Tournament");
+
+        ui.add(egui::Slider::new(&mut self.number_of_values, 8..=4096).text("Values per round"));
+        ui.add(egui::Slider::new(&mut self.seed_count, 2..=200).text("Rounds (seeds)"));
+
+        ui.horizontal(|ui| {
+            let is_running = self.pending_tournament.is_some();
+            let can_start = !is_running && !selected_algorithms.is_empty();
+
+            if ui.add_enabled(can_start, egui::Button::new("Run tournament")).clicked() {
+                let tournament_options = TournamentOptions {
+                    algorithms: selected_algorithms,
+                    number_of_values: self.number_of_values,
+                    input_distribution,
+                    seed_count: self.seed_count,
+                    first_seed: 0,
+                };
+                self.pending_tournament = Some(
+                    spawn_bench_thread(move || run_tournament(&tournament_options))
+                );
+            }
+
+            if is_running {
+                ui.spinner();
+                ui.label("Running rounds...");
+                ui.ctx().request_repaint();
+            }
+        });
+
+        ui.separator();
+
+        let Some(result) = &self.result else {
+            ui.label("Run a tournament to compare the selected algorithms over many inputs.");
+            return;
+        };
+
+        ui.label(
+            format!(
+                "{} rounds of {} values, {} input",
+                result.options.seed_count,
+                result.options.number_of_values,
+                result.options.input_distribution.display_name()
+            )
+        );
+
+        egui::ComboBox
+            ::from_label("Metric")
+            .selected_text(self.metric.display_name())
+            .show_ui(ui, |combo_ui| {
+                for metric in TournamentMetric::ALL {
+                    combo_ui.selectable_value(&mut self.metric, metric, metric.display_name());
+                }
+            });
+
+        let ranking = result.ranking(self.metric);
+        let summaries: Vec<MetricSummary> = result.entries
+            .iter()
+            .map(|entry| entry.summary(self.metric))
+            .collect();
+
+        draw_box_plots(ui, result, &ranking, &summaries);
+
+        ui.add_space(6.0);
+
+        egui::Grid
+            ::new("tournament_ranking")
+            .num_columns(6)
+            .striped(true)
+            .show(ui, |grid_ui| {
+                for header in ["#", "Algorithm", "Mean", "Median", "Std dev", "Min–max"] {
+                    grid_ui.label(egui::RichText::new(header).strong());
+                }
+                grid_ui.end_row();
+
+                for (rank, entry_index) in ranking.iter().enumerate() {
+                    let summary = &summaries[*entry_index];
+                    grid_ui.label(format!("{}", rank + 1));
+                    grid_ui.label(result.entries[*entry_index].algorithm_kind.display_name());
+                    grid_ui.label(format_metric(summary.mean));
+                    grid_ui.label(format_metric(summary.median));
+                    grid_ui.label(format_metric(summary.standard_deviation));
+                    grid_ui.label(
+                        format!("{}–{}", format_metric(summary.minimum), format_metric(summary.maximum))
+                    );
+                    grid_ui.end_row();
+                }
+            });
+
+        ui.separator();
+
+        // export, format picked from the file extension
+        ui.horizontal(|ui| {
+            ui.label("File:");
+            ui.text_edit_singleline(&mut self.export_path).on_hover_text(
+                "A .json file gets JSON, anything else CSV"
+            );
+        });
+
+        if ui.button("Export results").clicked() {
+            let export_path = self.export_path.trim().to_owned();
+            let output_format = if export_path.to_ascii_lowercase().ends_with(".json") {
+                BenchOutputFormat::Json
+            } else {
+                BenchOutputFormat::Csv
+            };
+
+            let formatted_result = format_tournament(result, self.metric, output_format);
+            self.export_status = Some(match std::fs::write(Path::new(&export_path), formatted_result) {
+                Ok(()) => format!("Saved {}", export_path),
+                Err(error) => format!("Could not save results: {}", error),
+            });
+        }
+
+        if let Some(export_status) = &self.export_status {
+            ui.label(egui::RichText::new(export_status).small().italics());
+        }
+    }
+
+    fn collect_finished_tournament(&mut self) {
+        let is_finished = self.pending_tournament
+            .as_ref()
+            .is_some_and(|pending_tournament| pending_tournament.is_finished());
+
+        if is_finished {
+            if let Some(pending_tournament) = self.pending_tournament.take() {
+                if let Ok(result) = pending_tournament.join() {
+                    self.result = Some(result);
+                    self.export_status = None;
+                }
+            }
+        }
+    }
+}
+
+// one horizontal box per algorithm in ranking order, sharing a linear axis
+fn draw_box_plots(
+    ui: &mut egui::Ui,
+    result: &TournamentResult,
+    ranking: &[usize],
+    summaries: &[MetricSummary]
+) {
+    let plot_size = egui::vec2(ui.available_width(), BOX_ROW_HEIGHT * (ranking.len() as f32) + 16.0);
+    let (response, painter) = ui.allocate_painter(plot_size, egui::Sense::hover());
+    let visuals = ui.visuals();
+
+    painter.rect_filled(response.rect, 4.0, visuals.extreme_bg_color);
+
+    let axis_left = response.rect.left() + BOX_PLOT_LABEL_WIDTH;
+    let axis_right = response.rect.right() - 8.0;
+    if axis_right <= axis_left {
+        return;
+    }
+
+    let axis_minimum = summaries.iter().map(|summary| summary.minimum).fold(f64::INFINITY, f64::min);
+    let axis_maximum = summaries.iter().map(|summary| summary.maximum).fold(0.0, f64::max);
+    let axis_span = (axis_maximum - axis_minimum).max(f64::EPSILON);
+    let to_x = |value: f64| -> f32 {
+        axis_left + (axis_right - axis_left) * (((value - axis_minimum) / axis_span) as f32)
+    };
+
+    let label_font = egui::FontId::proportional(11.0);
+    let whisker_stroke = egui::Stroke::new(1.0, visuals.text_color());
+    let box_fill = visuals.selection.bg_fill.gamma_multiply(0.8);
+    let median_stroke = egui::Stroke::new(2.0, visuals.strong_text_color());
+
+    for (row_index, entry_index) in ranking.iter().enumerate() {
+        let summary = &summaries[*entry_index];
+        let row_top = response.rect.top() + 4.0 + BOX_ROW_HEIGHT * (row_index as f32);
+        let row_center = row_top + BOX_ROW_HEIGHT * 0.5;
+
+        painter.text(
+            egui::pos2(response.rect.left() + 6.0, row_center),
+            egui::Align2::LEFT_CENTER,
+            result.entries[*entry_index].algorithm_kind.display_name(),
+            label_font.clone(),
+            visuals.text_color()
+        );
+
+        // whiskers span min..max, the box q1..q3
+        painter.hline(to_x(summary.minimum)..=to_x(summary.maximum), row_center, whisker_stroke);
+        for whisker_value in [summary.minimum, summary.maximum] {
+            painter.vline(
+                to_x(whisker_value),
+                (row_center - 4.0)..=(row_center + 4.0),
+                whisker_stroke
+            );
+        }
+
+        let box_rect = egui::Rect::from_min_max(
+            egui::pos2(to_x(summary.lower_quartile), row_center - 7.0),
+            egui::pos2(to_x(summary.upper_quartile).max(to_x(summary.lower_quartile) + 1.0), row_center + 7.0)
+        );
+        painter.rect_filled(box_rect, 2.0, box_fill);
+        painter.vline(to_x(summary.median), box_rect.y_range(), median_stroke);
+        painter.circle_filled(egui::pos2(to_x(summary.mean), row_center), 2.5, visuals.strong_text_color());
+    }
+
+    painter.text(
+        egui::pos2(axis_left, response.rect.bottom() - 2.0),
+        egui::Align2::LEFT_BOTTOM,
+        format_metric(axis_minimum),
+        label_font.clone(),
+        visuals.weak_text_color()
+    );
+    painter.text(
+        egui::pos2(axis_right, response.rect.bottom() - 2.0),
+        egui::Align2::RIGHT_BOTTOM,
+        format_metric(axis_maximum),
+        label_font,
+        visuals.weak_text_color()
+    );
+}
+
+fn format_metric(value: f64) -> String {
+    if value.abs() >= 100.0 {
+        format!("{:.0}", value)
+    } else {
+        format!("{:.2}", value)
+    }
+}
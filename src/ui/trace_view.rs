@@ -0,0 +1,167 @@
+use eframe::egui::{ self, Color32 };
+
+use crate::trace::SortTrace;
+
+const MAXIMUM_TRACE_ZOOM: f32 = 64.0;
+
+// zoom, pan and cached texture of one panel's trace image
+#[derive(Clone)]
+pub struct TraceViewState {
+    zoom: f32,
+    // center of the visible region in uv space (0..1)
+    center: egui::Vec2,
+    texture: Option<(egui::TextureHandle, u64)>,
+}
+
+impl Default for TraceViewState {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            center: egui::vec2(0.5, 0.5),
+            texture: None,
+        }
+    }
+}
+
+impl TraceViewState {
+    // texture_key must change whenever the trace or the palette changes
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        size: egui::Vec2,
+        trace: &SortTrace,
+        current_step: usize,
+        texture_key: u64,
+        color_for_value: impl Fn(f32) -> Color32
+    ) {
+        let (response, painter) = ui.allocate_painter(size, egui::Sense::click_and_drag());
+        let view_rect = response.rect;
+
+        painter.rect_filled(view_rect, 4.0, ui.visuals().extreme_bg_color);
+
+        if trace.width() == 0 || trace.height() == 0 {
+            return;
+        }
+
+        let texture_id = self.texture_id(ui.ctx(), trace, texture_key, color_for_value);
+
+        self.handle_zoom_and_pan(ui, &response);
+
+        let uv_rect = self.visible_uv_rect();
+        painter.image(texture_id, view_rect, uv_rect, Color32::WHITE);
+
+        // cursor line at the current step
+        let row_count = trace.height() as f32;
+        let cursor_uv = (trace.row_position_for_step(current_step) + 0.5) / row_count;
+        if cursor_uv >= uv_rect.top() && cursor_uv <= uv_rect.bottom() {
+            let cursor_fraction = (cursor_uv - uv_rect.top()) / uv_rect.height();
+            let cursor_y = view_rect.top() + view_rect.height() * cursor_fraction;
+            painter.hline(
+                view_rect.x_range(),
+                cursor_y,
+                egui::Stroke::new(1.5, ui.visuals().strong_text_color())
+            );
+        }
+
+        response.on_hover_text("Scroll to zoom, drag to pan, double-click to reset");
+    }
+
+    fn texture_id(
+        &mut self,
+        context: &egui::Context,
+        trace: &SortTrace,
+        texture_key: u64,
+        color_for_value: impl Fn(f32) -> Color32
+    ) -> egui::TextureId {
+        if let Some((texture, cached_key)) = &self.texture {
+            if *cached_key == texture_key {
+                return texture.id();
+            }
+        }
+
+        let image = trace_color_image(context, trace, color_for_value);
+        let texture = context.load_texture("sort_trace", image, egui::TextureOptions::NEAREST);
+        let texture_id = texture.id();
+        self.texture = Some((texture, texture_key));
+        texture_id
+    }
+
+    fn handle_zoom_and_pan(&mut self, ui: &egui::Ui, response: &egui::Response) {
+        if response.double_clicked() {
+            *self = Self { texture: self.texture.take(), ..Self::default() };
+            return;
+        }
+
+        let view_rect = response.rect;
+
+        if response.hovered() {
+            let (scroll_delta, pinch_zoom) = ui.input(|input| {
+                (input.smooth_scroll_delta.y, input.zoom_delta())
+            });
+            let zoom_factor = (scroll_delta * 0.005).exp() * pinch_zoom;
+
+            if zoom_factor != 1.0 {
+                // keep the uv point under the mouse pointer fixed while zooming
+                let pointer_uv = response.hover_pos().map(|pointer_position| {
+                    let uv_rect = self.visible_uv_rect();
+                    let fraction = (pointer_position - view_rect.min) / view_rect.size();
+                    uv_rect.min.to_vec2() + fraction * uv_rect.size()
+                });
+
+                let new_zoom = (self.zoom * zoom_factor).clamp(1.0, MAXIMUM_TRACE_ZOOM);
+                if let Some(pointer_uv) = pointer_uv {
+                    let scale = self.zoom / new_zoom;
+                    self.center = pointer_uv + (self.center - pointer_uv) * scale;
+                }
+                self.zoom = new_zoom;
+            }
+        }
+
+        if response.dragged() {
+            let uv_per_point = egui::vec2(1.0, 1.0) / (view_rect.size() * self.zoom);
+            self.center -= response.drag_delta() * uv_per_point;
+        }
+
+        // keep the visible region inside the image
+        let half_extent = 0.5 / self.zoom;
+        self.center.x = self.center.x.clamp(half_extent, 1.0 - half_extent);
+        self.center.y = self.center.y.clamp(half_extent, 1.0 - half_extent);
+    }
+
+    fn visible_uv_rect(&self) -> egui::Rect {
+        let half_extent = 0.5 / self.zoom;
+        egui::Rect::from_center_size(
+            self.center.to_pos2(),
+            egui::vec2(half_extent * 2.0, half_extent * 2.0)
+        )
+    }
+}
+
+fn trace_color_image(
+    context: &egui::Context,
+    trace: &SortTrace,
+    color_for_value: impl Fn(f32) -> Color32
+) -> egui::ColorImage {
+    let maximum_value = trace.maximum_value().max(1);
+
+    // one color per distinct value instead of one hsv conversion per pixel
+    let color_lookup: Vec<Color32> = (0..=maximum_value)
+        .map(|value| color_for_value((value as f32) / (maximum_value as f32)))
+        .collect();
+
+    // sample columns down if the array is wider than the gpu allows
+    let max_texture_side = context.input(|input| input.max_texture_side).max(1);
+    let image_width = trace.width().min(max_texture_side);
+    let image_height = trace.height().min(max_texture_side);
+
+    let mut pixels: Vec<Color32> = Vec::with_capacity(image_width * image_height);
+    for pixel_row in 0..image_height {
+        let row = &trace.rows()[(pixel_row * trace.height()) / image_height];
+        for pixel_column in 0..image_width {
+            let value = row[(pixel_column * row.len()) / image_width];
+            pixels.push(color_lookup[value as usize]);
+        }
+    }
+
+    egui::ColorImage::new([image_width, image_height], pixels)
+}
@@ -1,238 +1,558 @@
 use std::collections::HashMap;
+use std::hash::{ DefaultHasher, Hash, Hasher };
+use std::path::{ Path, PathBuf };
 use std::sync::{ Arc, Mutex };
-use std::time::Duration;
+use std::thread::JoinHandle;
+use std::time::{ Duration, Instant };
 use rand::random;
 
 use eframe::egui::{ self, Color32 };
-use eframe::egui::epaint::Hsva;
-use rodio::{ OutputStream, OutputStreamBuilder, Sink, Source, source::SineWave, source::Spatial };
 
+use crate::bench::spawn_bench_thread;
+use crate::chart::{ chart_shapes, ChartRect, ChartShape, ChartStyle };
 use crate::engine::{
     AlgorithmStateSnapshot,
     EngineConfig,
     EngineController,
     EngineSharedState,
     EngineState,
+    RecordedRun,
 };
+use crate::export::midi::write_midi_file;
+use crate::export::{ exported_step_indices, panel_title, write_svg_snapshot, PanelSnapshot };
+use crate::image_source::{ ImageSliceMode, SourceImage };
+use crate::input::InputDistribution;
+use crate::layout::GridLayout;
+use crate::live_trace::{ LiveTrace, LiveTraceStatus };
+use crate::palette::Rgba8;
+use crate::replay_file::{ read_replay_file, write_replay_file };
+use crate::scenario::{ read_playlist, Playlist };
+use crate::self_check::{ check_replay, expected_result };
+use crate::sonification::{ notes_for_changes, notes_for_sweep, PanelVoice };
+use crate::sorting_algorithms::instance::AlgorithmInstance;
+use crate::trace::SortTrace;
+use crate::trace_import::import_trace_file;
+use crate::ui::audio::AudioOutput;
+use crate::ui::complexity_panel::ComplexityPanelState;
+use crate::ui::panel_view::PanelViewState;
+use crate::ui::playlist_player::PlaylistPlayer;
+use crate::ui::settings_file::{ SettingsFile, WindowLayout };
 use crate::ui::settings_panel::{ SettingsPanelAction, SettingsPanelState };
+use crate::ui::tournament_panel::TournamentPanelState;
+use crate::verification::Verification;
+
+// verification sweep at the end of a run: positions turn green or red from left to right,
+// then the green fades back to the finished colors while the red stays
+const SWEEP_DURATION: Duration = Duration::from_millis(1200);
+const SWEEP_HOLD: Duration = Duration::from_millis(800);
+const SWEEP_FADE: Duration = Duration::from_millis(600);
+
+// how far the verification sweep of one panel has come
+struct SweepView<'a> {
+    correct_positions: &'a [bool],
+    swept_count: usize,
+    // how strongly positions found correct are tinted green, 1 down to 0 as it fades
+    verified_strength: f32,
+    is_complete: bool,
+}
 
-// max grid columns
-const MAX_GRID_COLUMNS: usize = 4;
+impl<'a> SweepView<'a> {
+    fn new(verification: &'a Verification, elapsed: Duration) -> Self {
+        let position_count = verification.correct_positions.len();
+        let progress = (elapsed.as_secs_f32() / SWEEP_DURATION.as_secs_f32()).min(1.0);
+        let fade_elapsed = elapsed.saturating_sub(SWEEP_DURATION + SWEEP_HOLD);
+        Self {
+            correct_positions: &verification.correct_positions,
+            swept_count: ((position_count as f32) * progress).ceil() as usize,
+            verified_strength: 1.0 - (fade_elapsed.as_secs_f32() / SWEEP_FADE.as_secs_f32()).min(1.0),
+            is_complete: progress >= 1.0,
+        }
+    }
+}
 
-// c major scale
-const C_MAJOR_DEGREES: [i32; 7] = [0, 2, 4, 5, 7, 9, 11];
+// what plays as soon as the window opens, e.g. from the command line
+pub enum LaunchAction {
+    None,
+    // a run with the settings, as if Start was pressed
+    Start,
+    // first scenario of a playlist, as if it was opened in the Scenarios section
+    Playlist(Playlist),
+    Replay(RecordedRun),
+}
 
-// spatial audio constants
-const LEFT_EAR_POS: [f32; 3] = [-1.0, 0.0, 0.0];
-const RIGHT_EAR_POS: [f32; 3] = [1.0, 0.0, 0.0];
-// pushes the sound emitter to the front of the listener
-const EMITTER_Z: f32 = 1.0;
+// what a self-check of the recorded run found, shown in the settings panel
+struct SelfCheckOutcome {
+    status: String,
+    violations: Vec<String>,
+}
 
 pub struct SortVisApp {
     shared_state: Arc<Mutex<EngineSharedState>>,
     engine_controller: EngineController,
     settings_state: SettingsPanelState,
-
-    // must keep _audio_stream alive for audio to work
-    _audio_stream: Option<OutputStream>,
-    audio_sink: Option<Sink>,
-    previous_values_for_audio: HashMap<String, Vec<u32>>,
+    // where settings_state and window_layout are kept between launches
+    settings_file: SettingsFile,
+    window_layout: WindowLayout,
+
+    audio_output: AudioOutput,
+
+    panel_views: HashMap<usize, PanelViewState>,
+    complexity_panel: ComplexityPanelState,
+    tournament_panel: TournamentPanelState,
+    // stream from another program, shown as one more panel in the grid
+    live_trace: Option<LiveTrace>,
+    // self-check of the recorded run still running, if any
+    pending_self_check: Option<JoinHandle<SelfCheckOutcome>>,
+    // step of the live panel that was last sounded
+    live_sounded_step: Option<usize>,
+    // scenario file being presented; its current caption is drawn over the grid
+    playlist_player: Option<PlaylistPlayer>,
+
+    source_image: Option<Arc<SourceImage>>,
+    // size of the grid area last frame, so snapshots match the window
+    last_grid_size: egui::Vec2,
+    // image and slicing used by the current run, if it sorts an image
+    active_image: Option<(Arc<SourceImage>, ImageSliceMode)>,
 }
 
 impl SortVisApp {
+    // settings_state and window_layout are usually what settings_file loaded
     pub fn new(
         _creation_context: &eframe::CreationContext<'_>,
-        shared_state: Arc<Mutex<EngineSharedState>>
+        shared_state: Arc<Mutex<EngineSharedState>>,
+        settings_file: SettingsFile,
+        mut settings_state: SettingsPanelState,
+        window_layout: WindowLayout,
+        launch_action: LaunchAction
     ) -> Self {
-        let engine_controller = EngineController::new(Arc::clone(&shared_state));
-
-        // initialize audio, handle failure gracefully
-        let (_audio_stream, audio_sink) = match OutputStreamBuilder::open_default_stream() {
-            Ok(stream) => {
-                let sink = Sink::connect_new(&stream.mixer());
-                sink.set_volume(0.2);
-                (Some(stream), Some(sink))
-            }
-            Err(_) => (None, None),
-        };
+        let mut engine_controller = EngineController::new(Arc::clone(&shared_state));
+        let audio_output = AudioOutput::new();
+        // the engine times the notes, so they go to the mixer without waiting for a repaint
+        engine_controller.set_note_sender(audio_output.note_sender());
 
-        Self {
+        settings_state.settings_file_status = settings_file.status().map(str::to_owned);
+
+        let mut complexity_panel = ComplexityPanelState::default();
+        complexity_panel.is_open = window_layout.complexity_panel_open;
+        let mut tournament_panel = TournamentPanelState::default();
+        tournament_panel.is_open = window_layout.tournament_panel_open;
+
+        let mut app = Self {
             shared_state,
             engine_controller,
-            settings_state: SettingsPanelState::default(),
-            _audio_stream,
-            audio_sink,
-            previous_values_for_audio: HashMap::new(),
+            settings_state,
+            settings_file,
+            window_layout,
+            audio_output,
+            panel_views: HashMap::new(),
+            complexity_panel,
+            tournament_panel,
+            live_trace: None,
+            pending_self_check: None,
+            live_sounded_step: None,
+            playlist_player: None,
+            source_image: None,
+            last_grid_size: egui::vec2(960.0, 540.0),
+            active_image: None,
+        };
+
+        match launch_action {
+            LaunchAction::None => {}
+            LaunchAction::Start => {
+                let selected_algorithms = app.settings_state.selected_algorithms();
+                app.start_from_settings(selected_algorithms);
+            }
+            LaunchAction::Playlist(playlist) => {
+                app.open_playlist(playlist);
+            }
+            LaunchAction::Replay(recorded_run) => {
+                app.settings_state.input_distribution = recorded_run.input_distribution;
+                app.start_recorded_run(recorded_run);
+            }
         }
+
+        app
     }
 
-    fn handle_settings_action(&mut self, action: SettingsPanelAction) {
+    fn collect_finished_self_check(&mut self, context: &egui::Context) {
+        let is_finished = self.pending_self_check
+            .as_ref()
+            .is_some_and(|pending_self_check| pending_self_check.is_finished());
+
+        if !is_finished {
+            if self.pending_self_check.is_some() {
+                context.request_repaint();
+            }
+            return;
+        }
+
+        if let Some(pending_self_check) = self.pending_self_check.take() {
+            match pending_self_check.join() {
+                Ok(outcome) => {
+                    self.settings_state.self_check_status = Some(outcome.status);
+                    self.settings_state.self_check_violations = outcome.violations;
+                }
+                Err(_) => {
+                    self.settings_state.self_check_status = Some("Self-check failed".to_owned());
+                }
+            }
+        }
+    }
+
+    fn handle_settings_action(
+        &mut self,
+        action: SettingsPanelAction,
+        engine_state_snapshot: &EngineSharedState,
+        dark_mode: bool
+    ) {
         match action {
             SettingsPanelAction::None => {}
             SettingsPanelAction::StopRequested => {
                 self.engine_controller.stop();
                 // stop queued sounds and reset audio tracking
-                self.clear_audio_state();
+                self.audio_output.clear();
             }
             SettingsPanelAction::StartRequested(selected_algorithms) => {
-                if selected_algorithms.is_empty() {
-                    return;
-                }
-
-                // randomize base hue (0..360)
-                self.settings_state.palette_base_hue_degrees = random::<f32>() * 360.0;
+                self.start_from_settings(selected_algorithms);
+            }
+            SettingsPanelAction::LoadImageRequested => {
+                let image_path = self.settings_state.image_path.trim().to_owned();
 
-                // reset audio and create fresh sink
-                self.clear_audio_state();
-                self.ensure_audio_sink();
+                match SourceImage::load(Path::new(&image_path)) {
+                    Ok(image) => {
+                        self.settings_state.image_status = Some(
+                            format!("Loaded {}×{} pixels", image.width(), image.height())
+                        );
+                        self.settings_state.use_image_input = true;
+                        self.source_image = Some(Arc::new(image));
+                    }
+                    Err(error) => {
+                        self.settings_state.image_status = Some(
+                            format!("Could not load image: {}", error)
+                        );
+                    }
+                }
+            }
+            SettingsPanelAction::SaveSnapshotRequested => {
+                let grid_size = self.last_grid_size;
+                let output_path = PathBuf::from(self.settings_state.snapshot_path.trim());
+                self.save_svg_snapshot(
+                    &output_path,
+                    &engine_state_snapshot.algorithm_states,
+                    grid_size,
+                    dark_mode
+                );
+            }
+            SettingsPanelAction::SaveRunRequested => {
+                let replay_path = self.settings_state.replay_path.trim().to_owned();
 
-                let engine_config = EngineConfig {
-                    number_of_values: self.settings_state.number_of_values,
-                    selected_algorithms,
-                    frames_per_second: self.settings_state.frames_per_second,
+                let Some(recorded_run) = &engine_state_snapshot.recorded_run else {
+                    self.settings_state.replay_status = Some("Nothing to save yet".to_owned());
+                    return;
                 };
 
-                self.engine_controller.start_run(engine_config);
+                self.settings_state.replay_status = Some(
+                    match write_replay_file(Path::new(&replay_path), recorded_run) {
+                        Ok(()) => format!("Saved {}", replay_path),
+                        Err(error) => format!("Could not save run: {}", error),
+                    }
+                );
             }
-        }
-    }
+            SettingsPanelAction::ExportMidiRequested => {
+                let midi_path = self.settings_state.midi_path.trim().to_owned();
 
-    // audio state management
-    fn clear_audio_state(&mut self) {
-        // dropping sink stops queued sounds immediately
-        if let Some(sink) = self.audio_sink.take() {
-            sink.stop();
-        }
+                let Some(recorded_run) = &engine_state_snapshot.recorded_run else {
+                    self.settings_state.midi_status = Some("Nothing to export yet".to_owned());
+                    return;
+                };
 
-        // clear to avoid detecting bogus changes
-        self.previous_values_for_audio.clear();
-    }
+                // every step, one after another, as the window plays them
+                let step_indices = exported_step_indices(&recorded_run.replays, 1);
+                self.settings_state.midi_status = Some(
+                    match
+                        write_midi_file(
+                            Path::new(&midi_path),
+                            &recorded_run.replays,
+                            &step_indices,
+                            self.settings_state.midi_tempo_bpm,
+                            self.settings_state.midi_steps_per_beat,
+                            &self.settings_state.sonification
+                        )
+                    {
+                        Ok(note_count) => format!("Saved {} notes to {}", note_count, midi_path),
+                        Err(error) => format!("Could not save MIDI file: {}", error),
+                    }
+                );
+            }
+            SettingsPanelAction::SelfCheckRequested => {
+                if self.pending_self_check.is_some() {
+                    return;
+                }
+                self.settings_state.self_check_violations.clear();
 
-    fn ensure_audio_sink(&mut self) {
-        // sink exists, nothing to do
-        if self.audio_sink.is_some() {
-            return;
-        }
+                let Some(recorded_run) = &engine_state_snapshot.recorded_run else {
+                    self.settings_state.self_check_status = Some("Nothing to check yet".to_owned());
+                    return;
+                };
 
-        // lazily recreate sink if stream exists
-        if let Some(ref stream) = self._audio_stream {
-            let sink = Sink::connect_new(&stream.mixer());
-            sink.set_volume(0.2);
-            self.audio_sink = Some(sink);
-        }
-    }
+                // large runs take a while to check, so the window keeps drawing meanwhile
+                let recorded_run = Arc::clone(recorded_run);
+                self.pending_self_check = Some(
+                    spawn_bench_thread(move || check_recorded_run(&recorded_run))
+                );
+                self.settings_state.self_check_status = Some("Checking...".to_owned());
+            }
+            SettingsPanelAction::LoadRunRequested => {
+                let replay_path = self.settings_state.replay_path.trim().to_owned();
 
-    // audio detection helpers
-    fn detect_first_changed_index(&self, previous: &[u32], current: &[u32]) -> Option<usize> {
-        let min_len = previous.len().min(current.len());
+                match read_replay_file(Path::new(&replay_path)) {
+                    Ok(recorded_run) => {
+                        self.settings_state.replay_status = Some(
+                            format!(
+                                "Loaded {} algorithms on {} values",
+                                recorded_run.replays.len(),
+                                recorded_run.base_values.len()
+                            )
+                        );
+                        self.settings_state.input_distribution = recorded_run.input_distribution;
+                        self.start_recorded_run(recorded_run);
+                    }
+                    Err(error) => {
+                        self.settings_state.replay_status = Some(
+                            format!("Could not load run: {}", error)
+                        );
+                    }
+                }
+            }
+            SettingsPanelAction::ConnectLiveRequested => {
+                // the previous stream is closed when it is dropped
+                self.live_trace = None;
+                self.live_sounded_step = None;
+
+                match
+                    LiveTrace::start(
+                        self.settings_state.live_source_kind,
+                        &self.settings_state.live_target
+                    )
+                {
+                    Ok(live_trace) => {
+                        self.live_trace = Some(live_trace);
+                    }
+                    Err(error) => {
+                        self.settings_state.live_status = Some(format!("Could not connect: {}", error));
+                    }
+                }
+            }
+            SettingsPanelAction::DisconnectLiveRequested => {
+                if let Some(live_trace) = &mut self.live_trace {
+                    live_trace.stop();
+                }
+            }
+            SettingsPanelAction::OpenScenariosRequested => {
+                let scenario_path = self.settings_state.scenario_path.trim().to_owned();
 
-        // check common range for changes
-        for i in 0..min_len {
-            if previous[i] != current[i] {
-                return Some(i);
+                match read_playlist(Path::new(&scenario_path)) {
+                    Ok(playlist) => {
+                        self.open_playlist(playlist);
+                    }
+                    Err(error) => {
+                        self.settings_state.scenario_status = Some(
+                            format!("Could not open scenarios: {}", error)
+                        );
+                    }
+                }
             }
-        }
+            SettingsPanelAction::PlayScenarioRequested(scenario_index) => {
+                self.play_scenario(scenario_index);
+            }
+            SettingsPanelAction::CloseScenariosRequested => {
+                self.playlist_player = None;
+                self.settings_state.scenario_status = None;
+            }
+            SettingsPanelAction::ImportTraceRequested => {
+                let trace_path = self.settings_state.trace_path.trim().to_owned();
 
-        // length difference is a change at min_len
-        if previous.len() != current.len() {
-            return Some(min_len);
+                match import_trace_file(Path::new(&trace_path)) {
+                    Ok(replay) => {
+                        self.settings_state.replay_status = Some(
+                            format!(
+                                "Imported {} ({} steps)",
+                                replay.name(),
+                                replay.step_count()
+                            )
+                        );
+                        self.start_recorded_run(RecordedRun {
+                            input_distribution: InputDistribution::default(),
+                            frames_per_second: self.settings_state.frames_per_second,
+                            base_values: replay.frame(0).to_vec(),
+                            replay_inputs: vec![replay.frame(0).to_vec()],
+                            replays: vec![replay],
+                            algorithm_kinds: vec![None],
+                        });
+                    }
+                    Err(error) => {
+                        self.settings_state.replay_status = Some(
+                            format!("Could not import trace: {}", error)
+                        );
+                    }
+                }
+            }
         }
-
-        None
     }
 
-    fn play_audio_for_change(&self, current_values: &[u32], changed_index: usize) {
-        let Some(sink) = &self.audio_sink else {
-            return;
-        };
-
-        if !sink.empty() {
+    // a fresh run of the given panels with the settings panel's size, input and speed
+    fn start_from_settings(&mut self, mut selected_algorithms: Vec<AlgorithmInstance>) {
+        if selected_algorithms.is_empty() {
             return;
         }
 
-        let len = current_values.len();
-        if len == 0 || changed_index >= len {
-            return;
+        if let Some(playlist_player) = &mut self.playlist_player {
+            playlist_player.leave();
         }
 
-        let value = current_values[changed_index];
-        let maximum_value = current_values.iter().copied().max().unwrap_or(1) as f32;
-        if maximum_value <= 0.0 {
-            return;
-        }
+        // randomize base hue (0..360)
+        self.settings_state.palette_base_hue_degrees = random::<f32>() * 360.0;
 
-        // horizontal: 0.0 left, 1.0 right
-        let normalized_index = if len > 1 {
-            (changed_index as f32) / ((len - 1) as f32)
-        } else {
-            0.5
-        };
-
-        // bar height for loudness
-        let normalized_value = (value as f32) / maximum_value;
+        self.active_image = self.source_image
+            .as_ref()
+            .filter(|_| self.settings_state.use_image_input)
+            .map(|image| (Arc::clone(image), self.settings_state.image_slice_mode));
 
-        // c major scale mapping
-        let frequency = c_major_scale_frequency(normalized_index);
+        let number_of_values = match &self.active_image {
+            Some((image, slice_mode)) => image.slice_count(*slice_mode),
+            None => self.settings_state.number_of_values,
+        };
 
-        // map bar position to 3d emitter for panning
-        let emitter_pos = emitter_position_from_normalized_index(normalized_index);
+        // image runs need every slice exactly once, so they always start shuffled
+        let input_distribution = if self.active_image.is_some() {
+            InputDistribution::Random
+        } else {
+            self.settings_state.input_distribution
+        };
+        if self.active_image.is_some() {
+            for algorithm_instance in &mut selected_algorithms {
+                algorithm_instance.input_distribution = None;
+            }
+        }
 
-        // envelope loudness
-        let amplitude = 0.05 + 0.15 * normalized_value; // 0.05–0.20
-        let duration = Duration::from_millis(40);
-        let attack = Duration::from_millis(5);
-        let release = Duration::from_millis(40);
+        self.start_run(EngineConfig {
+            number_of_values,
+            input_distribution,
+            selected_algorithms,
+            frames_per_second: self.settings_state.frames_per_second,
+            input_seed: self.settings_state.input_seed(),
+        });
+    }
 
-        let base_source = SineWave::new(frequency)
-            .take_duration(duration)
-            .fade_in(attack)
-            .fade_out(release)
-            .amplify(amplitude);
+    fn start_run(&mut self, engine_config: EngineConfig) {
+        // notes of the previous run stop here
+        self.audio_output.clear();
+        // a new picture or slice mode can start at a step a cached picture already shows
+        for panel_view in self.panel_views.values_mut() {
+            panel_view.image_texture = None;
+        }
 
-        let spatial_source = Spatial::new(base_source, emitter_pos, LEFT_EAR_POS, RIGHT_EAR_POS);
+        self.engine_controller.start_run(engine_config);
+    }
 
-        sink.append(spatial_source);
+    fn open_playlist(&mut self, playlist: Playlist) {
+        self.settings_state.scenario_status = Some(
+            format!("Loaded {} scenarios", playlist.scenarios.len())
+        );
+        self.playlist_player = Some(PlaylistPlayer::new(playlist));
+        self.play_scenario(0);
     }
 
-    fn handle_audio_for_frame(&mut self, engine_state_snapshot: &EngineSharedState) {
-        // if audio disabled, clear and return
-        if !self.settings_state.enable_audio {
-            self.clear_audio_state();
+    // runs a scenario of the open playlist with its own settings; the settings panel keeps
+    // the user's own, so closing the playlist goes back to them
+    fn play_scenario(&mut self, scenario_index: usize) {
+        let Some(playlist_player) = &mut self.playlist_player else {
             return;
+        };
+        playlist_player.select(scenario_index);
+        let scenario = playlist_player.current().clone();
+
+        self.active_image = None;
+        self.panel_views.clear();
+        for panel_id in 0..scenario.algorithms.len() {
+            self.panel_views.insert(panel_id, PanelViewState {
+                chart_style: scenario.chart_style,
+                ..PanelViewState::default()
+            });
         }
 
-        // check if sink exists
-        self.ensure_audio_sink();
-        if self.audio_sink.is_none() {
-            // audio backend init failed
-            return;
-        }
+        self.start_run(EngineConfig {
+            number_of_values: scenario.number_of_values,
+            input_distribution: scenario.input_distribution,
+            selected_algorithms: scenario.algorithms,
+            frames_per_second: scenario.frames_per_second,
+            input_seed: scenario.seed,
+        });
+    }
 
-        let mut tone_played_this_frame = false;
+    fn start_recorded_run(&mut self, recorded_run: RecordedRun) {
+        if let Some(playlist_player) = &mut self.playlist_player {
+            playlist_player.leave();
+        }
 
-        for algorithm_state in &engine_state_snapshot.algorithm_states {
-            let algorithm_name = &algorithm_state.algorithm_name;
-            let current_values = &algorithm_state.current_values;
+        // saved runs and traces hold values only, so they always draw as bars
+        self.active_image = None;
+        self.audio_output.clear();
 
-            if let Some(previous_values) = self.previous_values_for_audio.get(algorithm_name) {
-                if !tone_played_this_frame {
-                    if
-                        let Some(changed_index) = self.detect_first_changed_index(
-                            previous_values,
-                            current_values
-                        )
-                    {
-                        self.play_audio_for_change(current_values, changed_index);
-                        tone_played_this_frame = true;
-                    }
-                }
-            }
+        self.engine_controller.start_replay(
+            Arc::new(recorded_run),
+            self.settings_state.frames_per_second
+        );
+    }
 
-            self.previous_values_for_audio.insert(algorithm_name.clone(), current_values.clone());
+    // writes the given panels at their current step, using the on-screen chart styles and palette
+    fn save_svg_snapshot(
+        &mut self,
+        output_path: &Path,
+        algorithm_states: &[AlgorithmStateSnapshot],
+        size: egui::Vec2,
+        dark_mode: bool
+    ) {
+        if algorithm_states.is_empty() {
+            self.settings_state.snapshot_status = Some("Nothing to save yet".to_owned());
+            return;
         }
+
+        let panels: Vec<PanelSnapshot> = algorithm_states
+            .iter()
+            .map(|algorithm_state| {
+                let chart_style = self.panel_views
+                    .get(&algorithm_state.panel_id)
+                    .map(|panel_view| panel_view.chart_style)
+                    .unwrap_or_default();
+
+                PanelSnapshot {
+                    title: panel_title(&algorithm_state.algorithm_name, algorithm_state.is_finished),
+                    stats_text: algorithm_state.stats.summary_line(),
+                    values: algorithm_state.current_values.clone(),
+                    highlighted_indices: algorithm_state.highlighted_indices.clone(),
+                    chart_style,
+                    is_finished: algorithm_state.is_finished,
+                }
+            })
+            .collect();
+
+        let palette = self.settings_state.palette(dark_mode);
+        let result = write_svg_snapshot(
+            output_path,
+            &panels,
+            &palette,
+            size.x.round().max(1.0) as u32,
+            size.y.round().max(1.0) as u32
+        );
+
+        self.settings_state.snapshot_status = Some(match result {
+            Ok(()) => format!("Saved {}", output_path.display()),
+            Err(error) => format!("Could not save snapshot: {}", error),
+        });
     }
 
-    fn draw_algorithm_grid(&self, ui: &mut egui::Ui, engine_state_snapshot: &EngineSharedState) {
+    fn draw_algorithm_grid(&mut self, ui: &mut egui::Ui, engine_state_snapshot: &EngineSharedState) {
         // check if preparing
         if
             let EngineState::Preparing { algorithms_completed, algorithms_total } =
@@ -275,34 +595,31 @@ impl SortVisApp {
         }
 
         let available_size = ui.available_size();
-
-        let minimum_cell_width = 220.0;
-        let minimum_cell_height = 160.0;
-
-        let columns_from_width = (available_size.x / minimum_cell_width).floor().max(1.0) as usize;
-
-        let rows_from_height = (available_size.y / minimum_cell_height).floor().max(1.0) as usize;
-
-        let columns_capped = columns_from_width.min(MAX_GRID_COLUMNS);
-
-        let column_count = if rows_from_height >= 3 && algorithm_count >= 3 {
-            // enforce min 3 rows if space allows
-            let max_columns_for_three_rows = (algorithm_count + 2) / 3;
-            columns_capped.min(max_columns_for_three_rows).min(algorithm_count)
-        } else if rows_from_height >= 2 && algorithm_count > 1 {
-            // enforce min 2 rows if space allows
-            let max_columns_for_two_rows = (algorithm_count + 1) / 2;
-            columns_capped.min(max_columns_for_two_rows).min(algorithm_count)
-        } else {
-            // insufficient vertical space or single algo
-            columns_capped.min(algorithm_count)
-        };
-
-        let row_count = ((algorithm_count + column_count - 1) / column_count).max(1);
-
-        let cell_width = (available_size.x / (column_count as f32)).max(minimum_cell_width);
-        let cell_height = (available_size.y / (row_count as f32)).max(minimum_cell_height);
-        let cell_size = egui::vec2(cell_width, cell_height);
+        self.last_grid_size = available_size;
+        let grid_layout = GridLayout::compute(available_size.x, available_size.y, algorithm_count);
+        let row_count = grid_layout.row_count;
+        let column_count = grid_layout.column_count;
+        let cell_size = egui::vec2(grid_layout.cell_width, grid_layout.cell_height);
+
+        // each panel sounds from its own column's part of the stereo field
+        let any_soloed = engine_state_snapshot.algorithm_states
+            .iter()
+            .any(|algorithm_state| {
+                self.panel_views.get(&algorithm_state.panel_id).is_some_and(|panel_view| panel_view.is_soloed)
+            });
+        let panel_voices = engine_state_snapshot.algorithm_states
+            .iter()
+            .enumerate()
+            .map(|(algorithm_index, algorithm_state)| {
+                let (column_index, _) = grid_layout.cell_position(algorithm_index);
+                let is_audible = match self.panel_views.get(&algorithm_state.panel_id) {
+                    Some(panel_view) => !panel_view.is_muted && (panel_view.is_soloed || !any_soloed),
+                    None => !any_soloed,
+                };
+                (algorithm_state.panel_id, PanelVoice::for_grid_column(column_index, column_count, is_audible))
+            })
+            .collect();
+        self.audio_output.set_panel_voices(panel_voices);
 
         let mut algorithm_index = 0;
         for _row_index in 0..row_count {
@@ -326,110 +643,304 @@ impl SortVisApp {
         }
     }
 
-    fn draw_algorithm_panel(&self, ui: &mut egui::Ui, algorithm_state: &AlgorithmStateSnapshot) {
+    fn draw_algorithm_panel(
+        &mut self,
+        ui: &mut egui::Ui,
+        algorithm_state: &AlgorithmStateSnapshot
+    ) {
+        // take the view state out so the chart helpers can still borrow self
+        let mut panel_view = self.panel_views
+            .remove(&algorithm_state.panel_id)
+            .unwrap_or_default();
+
+        let panel_size = ui.available_size();
+        let mut snapshot_requested = false;
+
+        // the sweep starts, with its rising notes, the first time the panel is drawn finished
+        let now = Instant::now();
+        match &algorithm_state.verification {
+            Some(_) if panel_view.sweep_started_at.is_none() => {
+                panel_view.sweep_started_at = Some(now);
+                self.audio_output.play_notes(
+                    notes_for_sweep(
+                        algorithm_state.panel_id,
+                        &algorithm_state.current_values,
+                        now,
+                        SWEEP_DURATION
+                    )
+                );
+            }
+            None => {
+                panel_view.sweep_started_at = None;
+            }
+            _ => {}
+        }
+        let sweep_view = match (&algorithm_state.verification, panel_view.sweep_started_at) {
+            (Some(verification), Some(sweep_started_at)) => {
+                Some(SweepView::new(verification, now.duration_since(sweep_started_at)))
+            }
+            _ => None,
+        };
+        // reported once the sweep has reached the end
+        let failure_message = algorithm_state.verification
+            .as_ref()
+            .filter(|_| sweep_view.as_ref().is_some_and(|sweep_view| sweep_view.is_complete))
+            .and_then(|verification| verification.failure_message());
+
         ui.vertical(|panel_ui| {
             panel_ui.group(|group_ui| {
-                if algorithm_state.is_finished {
-                    group_ui.label(
-                        egui::RichText
-                            ::new(format!("{} (finished)", algorithm_state.algorithm_name))
-                            .strong()
-                            .italics()
-                    );
-                } else {
-                    group_ui.label(egui::RichText::new(&algorithm_state.algorithm_name).strong());
+                group_ui.horizontal(|header_ui| {
+                    if failure_message.is_some() {
+                        header_ui.label(
+                            egui::RichText
+                                ::new(format!("{} (verification failed)", algorithm_state.algorithm_name))
+                                .strong()
+                                .color(header_ui.visuals().error_fg_color)
+                        );
+                    } else if algorithm_state.is_finished {
+                        header_ui.label(
+                            egui::RichText
+                                ::new(format!("{} (finished)", algorithm_state.algorithm_name))
+                                .strong()
+                                .italics()
+                        );
+                    } else {
+                        header_ui.label(
+                            egui::RichText::new(&algorithm_state.algorithm_name).strong()
+                        );
+                    }
+
+                    header_ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        egui::ComboBox
+                            ::from_id_salt(("chart_style", algorithm_state.panel_id))
+                            .selected_text(panel_view.chart_style.display_name())
+                            .show_ui(ui, |combo_ui| {
+                                for chart_style in ChartStyle::ALL {
+                                    combo_ui.selectable_value(
+                                        &mut panel_view.chart_style,
+                                        chart_style,
+                                        chart_style.display_name()
+                                    );
+                                }
+                            });
+
+                        ui.toggle_value(&mut panel_view.show_trace, "Trace").on_hover_text(
+                            "Show the whole run as an image: position left to right, time top to bottom."
+                        );
+
+                        if
+                            ui
+                                .small_button("SVG")
+                                .on_hover_text("Save this panel at the current step as a vector image.")
+                                .clicked()
+                        {
+                            snapshot_requested = true;
+                        }
+
+                        ui.toggle_value(&mut panel_view.is_soloed, "S").on_hover_text(
+                            "Solo: while any panel is soloed, only soloed panels are heard."
+                        );
+                        ui.toggle_value(&mut panel_view.is_muted, "M").on_hover_text("Mute this panel.");
+                    });
+                });
+
+                match &algorithm_state.current_marker {
+                    Some(marker) => {
+                        group_ui.label(format!("{} | {}", algorithm_state.stats.summary_line(), marker));
+                    }
+                    None => {
+                        group_ui.label(algorithm_state.stats.summary_line());
+                    }
                 }
 
-                let stats_text = format!(
-                    "Steps: {} | Time: {:.2} ms ({:.4} s)",
-                    algorithm_state.stats.total_steps,
-                    algorithm_state.stats.duration_milliseconds,
-                    algorithm_state.stats.duration_seconds
-                );
-                group_ui.label(stats_text);
+                if let Some(failure_message) = &failure_message {
+                    let error_color = group_ui.visuals().error_fg_color;
+                    group_ui.label(egui::RichText::new(failure_message).small().color(error_color));
+                }
 
                 let available_size = group_ui.available_size();
                 let reserved_height_for_labels = 40.0;
                 let chart_height = (available_size.y - reserved_height_for_labels).max(40.0);
-                let chart_size = egui::vec2(available_size.x, chart_height);
 
-                let (response, painter) = group_ui.allocate_painter(
-                    chart_size,
-                    egui::Sense::hover()
-                );
+                // live chart and trace share the row side by side
+                let trace_spacing = 6.0;
+                let chart_width = if panel_view.show_trace {
+                    ((available_size.x - trace_spacing) * 0.5).max(20.0)
+                } else {
+                    available_size.x
+                };
+                let chart_size = egui::vec2(chart_width, chart_height);
 
-                let bounding_rect = response.rect;
+                group_ui.horizontal(|chart_row_ui| {
+                    chart_row_ui.spacing_mut().item_spacing.x = trace_spacing;
 
-                let chart_margin_top = 6.0;
-                let chart_rect = egui::Rect::from_min_max(
-                    egui::pos2(bounding_rect.left(), bounding_rect.top() + chart_margin_top),
-                    egui::pos2(bounding_rect.right(), bounding_rect.bottom())
-                );
+                    let (response, painter) = chart_row_ui.allocate_painter(
+                        chart_size,
+                        egui::Sense::hover()
+                    );
 
-                self.draw_bar_chart(
-                    &painter,
-                    chart_rect,
-                    &algorithm_state.current_values,
-                    algorithm_state.is_finished
-                );
+                    let bounding_rect = response.rect;
+
+                    let chart_margin_top = 6.0;
+                    let chart_rect = egui::Rect::from_min_max(
+                        egui::pos2(bounding_rect.left(), bounding_rect.top() + chart_margin_top),
+                        egui::pos2(bounding_rect.right(), bounding_rect.bottom())
+                    );
+
+                    if let Some((image, slice_mode)) = &self.active_image {
+                        self.draw_image_chart(
+                            &painter,
+                            chart_rect,
+                            &mut panel_view,
+                            image,
+                            *slice_mode,
+                            algorithm_state
+                        );
+                    } else {
+                        self.draw_chart(
+                            &painter,
+                            chart_rect,
+                            panel_view.chart_style,
+                            algorithm_state,
+                            sweep_view.as_ref()
+                        );
+                    }
+
+                    if panel_view.show_trace {
+                        let visuals = chart_row_ui.visuals().clone();
+                        let texture_key = self.trace_texture_key(
+                            &visuals,
+                            &algorithm_state.trace
+                        );
+
+                        panel_view.trace_view.show(
+                            chart_row_ui,
+                            chart_size,
+                            &algorithm_state.trace,
+                            algorithm_state.current_step,
+                            texture_key,
+                            |normalized_value| {
+                                self.bar_fill_color(&visuals, normalized_value, false)
+                            }
+                        );
+                    }
+                });
             });
         });
+
+        self.panel_views.insert(algorithm_state.panel_id, panel_view);
+
+        if snapshot_requested {
+            let output_path = panel_snapshot_path(
+                Path::new(self.settings_state.snapshot_path.trim()),
+                &algorithm_state.algorithm_name
+            );
+            let dark_mode = ui.visuals().dark_mode;
+            self.save_svg_snapshot(
+                &output_path,
+                std::slice::from_ref(algorithm_state),
+                panel_size,
+                dark_mode
+            );
+        }
     }
 
-    fn bar_fill_color(
+    fn draw_image_chart(
         &self,
-        visuals: &egui::Visuals,
-        normalized_value: f32,
-        is_finished: bool
-    ) -> Color32 {
-        let clamped_value = normalized_value.clamp(0.0, 1.0);
-
-        // snapshot palette settings to avoid borrow conflicts
-        let use_custom_palette = self.settings_state.use_custom_palette;
-        let palette_base_hue_degrees = self.settings_state.palette_base_hue_degrees;
-        let palette_saturation = self.settings_state.palette_saturation;
-        let palette_brightness = self.settings_state.palette_brightness;
-        let palette_gradient_strength = self.settings_state.palette_gradient_strength;
-
-        // base hue from theme or user setting
-        let base_hue = if use_custom_palette {
-            (palette_base_hue_degrees / 360.0).rem_euclid(1.0)
-        } else if visuals.dark_mode {
-            0.58 // teal-ish
-        } else {
-            0.13 // orange-ish
-        };
+        painter: &egui::Painter,
+        chart_rect: egui::Rect,
+        panel_view: &mut PanelViewState,
+        image: &SourceImage,
+        slice_mode: ImageSliceMode,
+        algorithm_state: &AlgorithmStateSnapshot
+    ) {
+        let visuals = &painter.ctx().style().visuals;
+        let chart_background_color = self.chart_background_color(
+            visuals,
+            algorithm_state.is_finished
+        );
+        painter.rect_filled(chart_rect, 4.0, chart_background_color);
 
-        // gradient strength controls hue variation
-        let gradient_strength = if use_custom_palette { palette_gradient_strength } else { 0.18 };
+        if image.width() == 0 || image.height() == 0 {
+            return;
+        }
 
-        let hue_variation = gradient_strength * (clamped_value - 0.5);
-        let hue = (base_hue + hue_variation).rem_euclid(1.0);
+        // only rebuild the picture when the replay moved on
+        let needs_upload = !matches!(
+            &panel_view.image_texture,
+            Some((_, texture_step)) if *texture_step == algorithm_state.current_step
+        );
+
+        if needs_upload {
+            let permuted_pixels = image.render_permuted(&algorithm_state.current_values, slice_mode);
+            let color_image = egui::ColorImage::new(
+                [image.width(), image.height()],
+                permuted_pixels
+                    .into_iter()
+                    .map(|[red, green, blue, alpha]| {
+                        Color32::from_rgba_unmultiplied(red, green, blue, alpha)
+                    })
+                    .collect()
+            );
 
-        // saturation from theme or user, desaturated if finished
-        let saturation = if use_custom_palette {
-            let base = palette_saturation.clamp(0.0, 1.0);
-            if is_finished {
-                base * 0.7
-            } else {
-                base
+            match &mut panel_view.image_texture {
+                Some((texture, texture_step)) => {
+                    texture.set(color_image, egui::TextureOptions::LINEAR);
+                    *texture_step = algorithm_state.current_step;
+                }
+                None => {
+                    let texture = painter
+                        .ctx()
+                        .load_texture("sorted_image", color_image, egui::TextureOptions::LINEAR);
+                    panel_view.image_texture = Some((texture, algorithm_state.current_step));
+                }
             }
-        } else if is_finished {
-            0.5
-        } else {
-            0.85
+        }
+
+        let Some((texture, _)) = &panel_view.image_texture else {
+            return;
         };
 
-        // brightness from theme or user setting
-        let value_brightness = if use_custom_palette {
-            palette_brightness.clamp(0.0, 1.0)
-        } else if visuals.dark_mode {
-            0.9
+        // fit the picture into the chart, keeping its aspect ratio
+        let image_aspect = (image.width() as f32) / (image.height() as f32);
+        let fitted_size = if chart_rect.width() / chart_rect.height() > image_aspect {
+            egui::vec2(chart_rect.height() * image_aspect, chart_rect.height())
         } else {
-            0.7
+            egui::vec2(chart_rect.width(), chart_rect.width() / image_aspect)
         };
+        let image_rect = egui::Rect::from_center_size(chart_rect.center(), fitted_size);
+
+        painter.image(
+            texture.id(),
+            image_rect,
+            egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+            Color32::WHITE
+        );
+    }
 
-        Hsva::new(hue, saturation, value_brightness, 1.0).into()
+    // identifies the trace image contents: the trace itself plus every palette input
+    fn trace_texture_key(&self, visuals: &egui::Visuals, trace: &SortTrace) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        trace.id().hash(&mut hasher);
+        visuals.dark_mode.hash(&mut hasher);
+        self.settings_state.use_custom_palette.hash(&mut hasher);
+        self.settings_state.palette_base_hue_degrees.to_bits().hash(&mut hasher);
+        self.settings_state.palette_saturation.to_bits().hash(&mut hasher);
+        self.settings_state.palette_brightness.to_bits().hash(&mut hasher);
+        self.settings_state.palette_gradient_strength.to_bits().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn bar_fill_color(
+        &self,
+        visuals: &egui::Visuals,
+        normalized_value: f32,
+        is_finished: bool
+    ) -> Color32 {
+        let [red, green, blue, alpha] = self.settings_state
+            .palette(visuals.dark_mode)
+            .bar_color(normalized_value, is_finished);
+        Color32::from_rgba_unmultiplied(red, green, blue, alpha)
     }
 
     fn chart_background_color(&self, visuals: &egui::Visuals, is_finished: bool) -> Color32 {
@@ -445,60 +956,128 @@ impl SortVisApp {
         background
     }
 
-    fn draw_bar_chart(
+    fn draw_chart(
         &self,
         painter: &egui::Painter,
         chart_rect: egui::Rect,
-        values: &[u32],
-        is_finished: bool
+        chart_style: ChartStyle,
+        algorithm_state: &AlgorithmStateSnapshot,
+        sweep_view: Option<&SweepView>
     ) {
+        let values = &algorithm_state.current_values;
+        let is_finished = algorithm_state.is_finished;
         if values.is_empty() {
             return;
         }
 
-        let maximum_value = values.iter().copied().max().unwrap_or(1) as f32;
-        if maximum_value <= 0.0 {
-            return;
-        }
-
         let visuals = &painter.ctx().style().visuals;
 
         let chart_background_color = self.chart_background_color(visuals, is_finished);
         painter.rect_filled(chart_rect, 4.0, chart_background_color);
 
-        let bar_count = values.len();
-        let bar_width = chart_rect.width() / (bar_count as f32);
-        let bar_spacing_factor = 0.9;
-
-        for (value_index, value) in values.iter().enumerate() {
-            let normalized_height = (*value as f32) / maximum_value;
-            let bar_height = chart_rect.height() * normalized_height.max(0.0);
-
-            let left_position = chart_rect.left() + bar_width * (value_index as f32);
-            let right_position = left_position + bar_width * bar_spacing_factor;
-
-            let bottom_position = chart_rect.bottom();
-            let top_position = bottom_position - bar_height;
-
-            let bar_rect = egui::Rect::from_min_max(
-                egui::pos2(left_position, top_position),
-                egui::pos2(right_position, bottom_position)
-            );
+        let palette = self.settings_state.palette(visuals.dark_mode);
+        let mut shapes = chart_shapes(
+            chart_style,
+            ChartRect {
+                left: chart_rect.left(),
+                top: chart_rect.top(),
+                right: chart_rect.right(),
+                bottom: chart_rect.bottom(),
+            },
+            values,
+            &algorithm_state.highlighted_indices,
+            &palette,
+            is_finished
+        );
+
+        if let Some(sweep_view) = sweep_view {
+            let verified_color = palette.verified_color();
+            let failed_color = palette.failed_color();
+            for (index, shape) in shapes.iter_mut().enumerate().take(sweep_view.swept_count) {
+                let color = shape.color_mut();
+                *color = if sweep_view.correct_positions.get(index).copied().unwrap_or(false) {
+                    blend_rgba(*color, verified_color, sweep_view.verified_strength)
+                } else {
+                    failed_color
+                };
+            }
+        }
 
-            let bar_color = self.bar_fill_color(visuals, normalized_height, is_finished);
+        // triangles are batched into a single mesh
+        let mut mesh = egui::Mesh::default();
+        for shape in shapes {
+            match shape {
+                ChartShape::Rect { rect, corner_radius, color } => {
+                    painter.rect_filled(
+                        egui::Rect::from_min_max(
+                            egui::pos2(rect.left, rect.top),
+                            egui::pos2(rect.right, rect.bottom)
+                        ),
+                        corner_radius,
+                        color32_from_rgba(color)
+                    );
+                }
+                ChartShape::Circle { center, radius, color } => {
+                    painter.circle_filled(
+                        egui::pos2(center[0], center[1]),
+                        radius,
+                        color32_from_rgba(color)
+                    );
+                }
+                ChartShape::Triangle { points, color } => {
+                    let first_vertex = mesh.vertices.len() as u32;
+                    for [x, y] in points {
+                        mesh.colored_vertex(egui::pos2(x, y), color32_from_rgba(color));
+                    }
+                    mesh.add_triangle(first_vertex, first_vertex + 1, first_vertex + 2);
+                }
+            }
+        }
 
-            painter.rect_filled(bar_rect, 2.0, bar_color);
+        if !mesh.is_empty() {
+            painter.add(egui::Shape::mesh(mesh));
         }
     }
 }
 
 impl eframe::App for SortVisApp {
     fn update(&mut self, context: &egui::Context, _frame: &mut eframe::Frame) {
-        let engine_state_snapshot = {
+        let mut engine_state_snapshot = {
             let locked_state = self.shared_state.lock().unwrap();
             locked_state.clone()
         };
 
+        self.collect_finished_self_check(context);
+
+        if let Some(live_trace) = &mut self.live_trace {
+            live_trace.advance(self.settings_state.live_steps_per_frame);
+            self.settings_state.live_status = Some(
+                match live_trace.status() {
+                    LiveTraceStatus::Waiting => "Waiting for a connection...".to_owned(),
+                    LiveTraceStatus::Streaming => "Streaming".to_owned(),
+                    LiveTraceStatus::Finished(reason) => format!("Finished: {}", reason),
+                    LiveTraceStatus::Failed(message) => format!("Error: {}", message),
+                }
+            );
+
+            // the live panel is not played by the engine, so its notes are timed by the repaint
+            if let Some(live_snapshot) = live_trace.snapshot() {
+                if self.live_sounded_step != Some(live_snapshot.current_step) {
+                    self.live_sounded_step = Some(live_snapshot.current_step);
+                    self.audio_output.play_notes(
+                        notes_for_changes(
+                            live_snapshot.panel_id,
+                            &live_snapshot.current_values,
+                            &live_snapshot.highlighted_indices,
+                            Instant::now(),
+                            Duration::from_secs_f32(context.input(|input| input.stable_dt))
+                        )
+                    );
+                }
+                engine_state_snapshot.algorithm_states.push(live_snapshot);
+            }
+        }
+
         egui::TopBottomPanel::top("top_panel").show(context, |ui| {
             ui.horizontal(|horizontal_ui| {
                 horizontal_ui.heading("Sorting Visualizer");
@@ -519,63 +1098,223 @@ impl eframe::App for SortVisApp {
                         horizontal_ui.label("Status: Running");
                     }
                 }
+
+                horizontal_ui.with_layout(
+                    egui::Layout::right_to_left(egui::Align::Center),
+                    |right_ui| {
+                        right_ui
+                            .toggle_value(&mut self.tournament_panel.is_open, "Tournament")
+                            .on_hover_text("Run every algorithm on many seeded inputs and rank them");
+                        right_ui
+                            .toggle_value(&mut self.complexity_panel.is_open, "Complexity")
+                            .on_hover_text("Sweep n and fit operation counts on log-log axes");
+                    }
+                );
             });
         });
 
-        egui::SidePanel
+        let settings_panel_response = egui::SidePanel
             ::left("settings_panel")
             .resizable(true)
-            .default_width(220.0)
+            .default_width(self.window_layout.settings_panel_width)
             .show(context, |ui| {
-                let action = self.settings_state.show(ui, &engine_state_snapshot.engine_state);
-                self.handle_settings_action(action);
+                let action = self.settings_state.show(
+                    ui,
+                    &engine_state_snapshot.engine_state,
+                    self.source_image.as_deref(),
+                    self.playlist_player.as_mut()
+                );
+                let dark_mode = ui.visuals().dark_mode;
+                self.handle_settings_action(action, &engine_state_snapshot, dark_mode);
             });
+        self.window_layout.settings_panel_width = settings_panel_response.response.rect.width();
+
+        if self.complexity_panel.is_open {
+            let complexity_panel_response = egui::SidePanel
+                ::right("complexity_panel")
+                .resizable(true)
+                .default_width(self.window_layout.complexity_panel_width)
+                .show(context, |ui| {
+                    egui::ScrollArea::vertical().show(ui, |scroll_ui| {
+                        self.complexity_panel.show(
+                            scroll_ui,
+                            self.settings_state.selected_algorithm_kinds(),
+                            self.settings_state.input_distribution
+                        );
+                    });
+                });
+            self.window_layout.complexity_panel_width = complexity_panel_response.response.rect.width();
+        }
+
+        if self.tournament_panel.is_open {
+            let tournament_panel_response = egui::SidePanel
+                ::right("tournament_panel")
+                .resizable(true)
+                .default_width(self.window_layout.tournament_panel_width)
+                .show(context, |ui| {
+                    egui::ScrollArea::vertical().show(ui, |scroll_ui| {
+                        self.tournament_panel.show(
+                            scroll_ui,
+                            self.settings_state.selected_algorithm_kinds(),
+                            self.settings_state.input_distribution
+                        );
+                    });
+                });
+            self.window_layout.tournament_panel_width = tournament_panel_response.response.rect.width();
+        }
 
         egui::CentralPanel::default().show(context, |ui| {
             self.draw_algorithm_grid(ui, &engine_state_snapshot);
+            if let Some(scenario) = self.playlist_player.as_ref().and_then(PlaylistPlayer::showing) {
+                draw_caption(ui, &scenario.title, &scenario.caption);
+            }
         });
 
-        if let Some(sink) = &self.audio_sink {
-            sink.set_volume(self.settings_state.audio_volume);
+        // arrow keys and presentation clickers (page up/down) step through the playlist
+        if self.playlist_player.is_some() && !context.wants_keyboard_input() {
+            let (next_pressed, previous_pressed) = context.input(|input| {
+                (
+                    input.key_pressed(egui::Key::ArrowRight) || input.key_pressed(egui::Key::PageDown),
+                    input.key_pressed(egui::Key::ArrowLeft) || input.key_pressed(egui::Key::PageUp),
+                )
+            });
+            let requested_index = self.playlist_player.as_ref().and_then(|playlist_player| {
+                if next_pressed {
+                    playlist_player.next_index()
+                } else if previous_pressed {
+                    playlist_player.previous_index()
+                } else {
+                    None
+                }
+            });
+            if let Some(scenario_index) = requested_index {
+                self.play_scenario(scenario_index);
+            }
+        }
+
+        let auto_advance_index = self.playlist_player
+            .as_mut()
+            .and_then(|playlist_player| playlist_player.poll_auto_advance(&engine_state_snapshot));
+        if let Some(scenario_index) = auto_advance_index {
+            self.play_scenario(scenario_index);
+        }
+
+        self.audio_output.set_volume(self.settings_state.audio_volume);
+        self.audio_output.set_enabled(self.settings_state.enable_audio);
+        self.audio_output.set_sonification_settings(&self.settings_state.sonification);
+
+        self.window_layout.complexity_panel_open = self.complexity_panel.is_open;
+        self.window_layout.tournament_panel_open = self.tournament_panel.is_open;
+        if let Some(inner_rect) = context.input(|input| input.viewport().inner_rect) {
+            self.window_layout.window_size = Some([inner_rect.width(), inner_rect.height()]);
         }
-        self.handle_audio_for_frame(&engine_state_snapshot);
+        self.settings_file.save_if_changed(&self.settings_state, &self.window_layout);
 
         context.request_repaint();
     }
 }
 
-fn emitter_position_from_normalized_index(normalized_index: f32) -> [f32; 3] {
-    // 0..1 -> -1..1
-    let x = normalized_index.clamp(0.0, 1.0) * 2.0 - 1.0;
-    [x, 0.0, EMITTER_Z]
-}
+// title and caption of the playing scenario, in a translucent box along the bottom of the grid
+fn draw_caption(ui: &egui::Ui, title: &str, caption: &str) {
+    if title.is_empty() && caption.is_empty() {
+        return;
+    }
 
-fn freq_from_semitones(base_freq: f32, semitone_offset: i32) -> f32 {
-    base_freq * (2.0f32).powf((semitone_offset as f32) / 12.0)
-}
+    let grid_rect = ui.max_rect();
+    let padding = 12.0;
+    let wrap_width = (grid_rect.width() - 4.0 * padding).max(100.0);
+    let text_color = Color32::WHITE;
+
+    let mut layout_job = egui::text::LayoutJob::default();
+    if !title.is_empty() {
+        layout_job.append(
+            title,
+            0.0,
+            egui::TextFormat::simple(egui::FontId::proportional(24.0), text_color)
+        );
+    }
+    if !caption.is_empty() {
+        let separator = if title.is_empty() { "" } else { "\n" };
+        layout_job.append(
+            &format!("{}{}", separator, caption),
+            0.0,
+            egui::TextFormat::simple(egui::FontId::proportional(18.0), text_color)
+        );
+    }
+    layout_job.wrap.max_width = wrap_width;
 
-fn c_major_scale_frequency(normalized_index: f32) -> f32 {
-    let x = normalized_index.clamp(0.0, 1.0);
+    let painter = ui.painter();
+    let galley = painter.layout_job(layout_job);
+    let caption_rect = egui::Rect::from_center_size(
+        egui::pos2(grid_rect.center().x, grid_rect.bottom() - padding - galley.size().y * 0.5 - padding),
+        galley.size() + egui::vec2(2.0 * padding, 2.0 * padding)
+    );
 
-    // 3 octaves of c major = 21 steps
-    let total_steps = 21;
+    painter.rect_filled(caption_rect, 8.0, Color32::from_black_alpha(190));
+    painter.galley(caption_rect.min + egui::vec2(padding, padding), galley, text_color);
+}
 
-    // map to 0..20
-    let step_index = (x * ((total_steps - 1) as f32)).round() as i32;
+fn blend_rgba(from: Rgba8, to: Rgba8, amount: f32) -> Rgba8 {
+    let amount = amount.clamp(0.0, 1.0);
+    std::array::from_fn(|channel| {
+        ((from[channel] as f32) + ((to[channel] as f32) - (from[channel] as f32)) * amount).round() as u8
+    })
+}
 
-    // split into octave and degree
-    let degrees_per_octave = C_MAJOR_DEGREES.len() as i32;
-    let octave = step_index / degrees_per_octave;
-    let degree_index = (step_index % degrees_per_octave).max(0);
+fn color32_from_rgba([red, green, blue, alpha]: Rgba8) -> Color32 {
+    Color32::from_rgba_unmultiplied(red, green, blue, alpha)
+}
 
-    // lookup semitone offset
-    let degree_semitones = C_MAJOR_DEGREES[degree_index as usize];
+// "snapshot.svg" + "Quick Sort (Hoare)" -> "snapshot-quick-sort-hoare.svg"; labels are free
+// text, so only [a-z0-9-] is kept and a label cannot point into another directory
+// imported traces do not name their algorithm, so they are expected to end sorted
+fn check_recorded_run(recorded_run: &RecordedRun) -> SelfCheckOutcome {
+    let mut violations = Vec::new();
+    let mut failed_panel_count = 0;
+    for (panel_id, replay) in recorded_run.replays.iter().enumerate() {
+        let input = recorded_run.replay_input(panel_id);
+        let expected_result = expected_result(recorded_run.algorithm_kind(panel_id), input);
+
+        let panel_violations = check_replay(replay, input, &expected_result);
+        if !panel_violations.is_empty() {
+            failed_panel_count += 1;
+        }
+        violations.extend(panel_violations.iter().map(|violation| violation.to_string()));
+    }
 
-    // total = octave * 12 + degree
-    let total_semitones = octave * 12 + degree_semitones;
+    let step_count: usize = recorded_run.replays
+        .iter()
+        .map(|replay| replay.step_count())
+        .sum();
+    SelfCheckOutcome {
+        status: format!(
+            "Checked {} steps in {} panels: {} failed",
+            step_count,
+            recorded_run.replays.len(),
+            failed_panel_count
+        ),
+        violations,
+    }
+}
 
-    // base: a4 = 440 hz
-    let base_freq = 440.0;
+fn panel_snapshot_path(grid_snapshot_path: &Path, algorithm_name: &str) -> PathBuf {
+    let file_stem = grid_snapshot_path
+        .file_stem()
+        .and_then(|file_stem| file_stem.to_str())
+        .unwrap_or("sortvis-snapshot");
+    let slug_characters: String = algorithm_name
+        .to_ascii_lowercase()
+        .chars()
+        .map(|character| if character.is_ascii_alphanumeric() { character } else { '-' })
+        .collect();
+    let mut algorithm_slug = slug_characters
+        .split('-')
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if algorithm_slug.is_empty() {
+        algorithm_slug = "panel".to_owned();
+    }
 
-    freq_from_semitones(base_freq, total_semitones)
+    grid_snapshot_path.with_file_name(format!("{}-{}.svg", file_stem, algorithm_slug))
 }
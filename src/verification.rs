@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+
+use crate::sorting_algorithms::std_sorts::select_nth_index;
+use crate::sorting_algorithms::SortingAlgorithmKind;
+
+/// How the result of a finished run compares with its input.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Verification {
+    /// For each position, whether its value is where the algorithm should leave it (for a
+    /// sort: in order after the one before it) and was in the input.
+    pub correct_positions: Vec<bool>,
+    /// Positions whose value is not where the algorithm should leave it, e.g. smaller than the
+    /// one before it after a sort.
+    pub out_of_order_count: usize,
+    /// Values the input holds more often than the result.
+    pub missing_value_count: usize,
+    /// Values the result holds more often than the input.
+    pub extra_value_count: usize,
+}
+
+impl Verification {
+    /// Checks that `result` is sorted and holds exactly the values of `input`.
+    pub fn check(input: &[u32], result: &[u32]) -> Self {
+        Self::check_positions(input, result, |index, value| index == 0 || result[index - 1] <= value)
+    }
+
+    /// Checks that `result` holds exactly the values of `input`, partitioned around
+    /// `pivot_index` as `select_nth_unstable` leaves them: the value that sorts there at
+    /// `pivot_index`, none greater before it and none smaller after it.
+    pub fn check_partitioned(input: &[u32], result: &[u32], pivot_index: usize) -> Self {
+        let mut sorted_input = input.to_vec();
+        sorted_input.sort_unstable();
+        let Some(pivot_value) = sorted_input.get(pivot_index).copied() else {
+            return Self::check(input, result);
+        };
+
+        Self::check_positions(input, result, |index, value| {
+            match index.cmp(&pivot_index) {
+                std::cmp::Ordering::Less => value <= pivot_value,
+                std::cmp::Ordering::Equal => value == pivot_value,
+                std::cmp::Ordering::Greater => value >= pivot_value,
+            }
+        })
+    }
+
+    /// Checks `result` against what `algorithm_kind` should leave: a partition around the
+    /// median for `std select_nth_unstable`, sorted for every other algorithm and for runs
+    /// whose algorithm is not known.
+    pub fn for_algorithm(
+        algorithm_kind: Option<SortingAlgorithmKind>,
+        input: &[u32],
+        result: &[u32]
+    ) -> Self {
+        match algorithm_kind {
+            Some(SortingAlgorithmKind::StdSelectNth) => {
+                Self::check_partitioned(input, result, select_nth_index(input.len()))
+            }
+            _ => Self::check(input, result),
+        }
+    }
+
+    // counts values missing from or added to the input, and positions is_in_place rejects
+    fn check_positions(
+        input: &[u32],
+        result: &[u32],
+        is_in_place: impl Fn(usize, u32) -> bool
+    ) -> Self {
+        let mut remaining_counts: HashMap<u32, usize> = HashMap::new();
+        for value in input.iter().copied() {
+            *remaining_counts.entry(value).or_default() += 1;
+        }
+
+        let mut out_of_order_count = 0;
+        let mut extra_value_count = 0;
+        let correct_positions = result
+            .iter()
+            .enumerate()
+            .map(|(index, value)| {
+                let is_placed = is_in_place(index, *value);
+                if !is_placed {
+                    out_of_order_count += 1;
+                }
+
+                let is_from_input = match remaining_counts.get_mut(value) {
+                    Some(remaining_count) if *remaining_count > 0 => {
+                        *remaining_count -= 1;
+                        true
+                    }
+                    _ => {
+                        extra_value_count += 1;
+                        false
+                    }
+                };
+
+                is_placed && is_from_input
+            })
+            .collect();
+
+        Self {
+            correct_positions,
+            out_of_order_count,
+            missing_value_count: remaining_counts.values().sum(),
+            extra_value_count,
+        }
+    }
+
+    pub fn is_sorted(&self) -> bool {
+        self.out_of_order_count == 0
+    }
+
+    /// Whether the result holds every value of the input, as often as the input does.
+    pub fn keeps_input_values(&self) -> bool {
+        self.missing_value_count == 0 && self.extra_value_count == 0
+    }
+
+    pub fn passed(&self) -> bool {
+        self.is_sorted() && self.keeps_input_values()
+    }
+
+    /// What went wrong, e.g. "3 positions out of order, 2 input values lost"; `None` when the
+    /// check passed.
+    pub fn failure_message(&self) -> Option<String> {
+        let mut problems = Vec::new();
+        if self.out_of_order_count > 0 {
+            problems.push(format!("{} out of order", count_of(self.out_of_order_count, "position")));
+        }
+        if self.missing_value_count > 0 {
+            problems.push(format!("{} lost", count_of(self.missing_value_count, "input value")));
+        }
+        if self.extra_value_count > 0 {
+            problems.push(format!("{} not in the input", count_of(self.extra_value_count, "value")));
+        }
+
+        if problems.is_empty() {
+            None
+        } else {
+            Some(problems.join(", "))
+        }
+    }
+}
+
+// e.g. "1 position", "3 positions"
+fn count_of(count: usize, noun: &str) -> String {
+    if count == 1 { format!("1 {}", noun) } else { format!("{} {}s", count, noun) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorted_permutation_passes() {
+        let verification = Verification::check(&[3, 1, 2, 1], &[1, 1, 2, 3]);
+        assert!(verification.passed());
+        assert_eq!(verification.correct_positions, [true; 4]);
+        assert_eq!(verification.failure_message(), None);
+    }
+
+    #[test]
+    fn out_of_order_position_is_counted() {
+        let verification = Verification::check(&[1, 2, 3], &[2, 1, 3]);
+        assert!(!verification.is_sorted());
+        assert!(verification.keeps_input_values());
+        assert_eq!(verification.correct_positions, [true, false, true]);
+        assert_eq!(verification.failure_message().as_deref(), Some("1 position out of order"));
+    }
+
+    #[test]
+    fn lost_value_is_counted() {
+        let verification = Verification::check(&[1, 2, 3], &[1, 3]);
+        assert!(verification.is_sorted());
+        assert_eq!((verification.missing_value_count, verification.extra_value_count), (1, 0));
+        assert_eq!(verification.failure_message().as_deref(), Some("1 input value lost"));
+    }
+
+    #[test]
+    fn extra_value_is_counted_at_its_position() {
+        let verification = Verification::check(&[1, 2], &[1, 2, 5]);
+        assert_eq!((verification.missing_value_count, verification.extra_value_count), (0, 1));
+        assert_eq!(verification.correct_positions, [true, true, false]);
+        assert_eq!(verification.failure_message().as_deref(), Some("1 value not in the input"));
+    }
+
+    #[test]
+    fn duplicate_replacing_a_value_is_lost_and_extra() {
+        let verification = Verification::check(&[1, 2, 3], &[1, 1, 3]);
+        assert!(verification.is_sorted());
+        assert_eq!(verification.correct_positions, [true, false, true]);
+        assert_eq!(
+            verification.failure_message().as_deref(),
+            Some("1 input value lost, 1 value not in the input")
+        );
+    }
+
+    #[test]
+    fn partition_with_duplicates_around_the_pivot_passes() {
+        let input = [5, 3, 3, 3, 1, 3, 7];
+        // sorted: 1 3 3 3 3 5 7, so 3 belongs at index 3 and equal values may sit on both sides
+        let verification = Verification::check_partitioned(&input, &[3, 1, 3, 3, 3, 7, 5], 3);
+        assert!(verification.passed());
+        assert!(!Verification::check(&input, &[3, 1, 3, 3, 3, 7, 5]).passed());
+    }
+
+    #[test]
+    fn partition_rejects_values_on_the_wrong_side() {
+        let input = [5, 3, 3, 3, 1, 3, 7];
+        let wrong_pivot = Verification::check_partitioned(&input, &[3, 1, 3, 5, 3, 7, 3], 3);
+        assert_eq!(wrong_pivot.correct_positions, [true, true, true, false, true, true, true]);
+        assert!(wrong_pivot.keeps_input_values());
+
+        let greater_before = Verification::check_partitioned(&input, &[5, 1, 3, 3, 3, 7, 3], 3);
+        assert_eq!(greater_before.out_of_order_count, 1);
+        assert!(!greater_before.correct_positions[0]);
+    }
+
+    #[test]
+    fn select_nth_runs_are_checked_as_partitions() {
+        let input = [4, 1, 3, 0, 2];
+        let mut result = input;
+        result.select_nth_unstable(select_nth_index(input.len()));
+        let partitioned = [1, 0, 2, 4, 3];
+
+        let select_nth = Some(SortingAlgorithmKind::StdSelectNth);
+        for result in [result, partitioned] {
+            assert!(Verification::for_algorithm(select_nth, &input, &result).passed(), "{:?}", result);
+        }
+        for algorithm_kind in [None, Some(SortingAlgorithmKind::QuickSort)] {
+            assert!(!Verification::for_algorithm(algorithm_kind, &input, &partitioned).passed());
+        }
+    }
+}